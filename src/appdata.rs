@@ -1,6 +1,10 @@
 use crate::database::Database;
 use tera::Tera;
-use crate::config::ServicesConfig;
+use crate::config::{ServicesConfig, SharedServicesConfigs};
+use crate::services::knx::KnxTunnels;
+use crate::stream::StreamHub;
+use crate::events::EventBus;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppData {
@@ -13,13 +17,34 @@ pub struct AppData {
     */
     pub oauth_credentials:  Vec<OAuthCredentials>,
 
-    pub services_configs:    Vec<ServicesConfig>
+    /// Swapped when services.jsonc is reloaded, use get_services_configs()
+    pub services_configs:   SharedServicesConfigs,
+
+    /// Running KNXnet/IP tunnels, by service_id
+    pub knx_tunnels:        KnxTunnels,
+
+    /// Live events for connected clients
+    pub stream:             StreamHub,
+
+    /// Events of the whole system, see EventBus
+    pub events:             EventBus
+}
+
+impl AppData {
+
+    /**
+    Get the current Service configurations. The returned configurations are not affected by later reloads
+    */
+    pub fn get_services_configs(&self) -> Arc<Vec<ServicesConfig>> {
+        self.services_configs.read().unwrap().clone()
+    }
 }
 
 #[derive(Clone)]
 pub struct OAuthCredentials {
     pub client_id:      String,
     pub client_secret:  String,
+    #[allow(dead_code)]
     pub identifier:     OAuthIdentifier
 }
 
@@ -0,0 +1,187 @@
+use crate::database::Database;
+use crate::types::device::{Device, DeviceKind, RegisteredDevice};
+
+use mysql::{Error, Params, params, Row};
+use mysql::prelude::Queryable;
+use rand::Rng;
+use std::str::FromStr;
+
+const SELECT_COLUMNS: &str = "device_id, user_id, service_id, provider_device_id, kind, capabilities, provider_name, name, nicknames, room_id, exposed, last_seen";
+
+/**
+Register the Devices discovered through a Service. New Devices get an internal ID, known Devices are updated
+with what the Service reports. The name, nicknames, room and exposure chosen by the user are left untouched
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User owning the Service
+    service_id: The ID of the Service
+    devices: The Devices reported by the Service
+
+## Returns
+    Err: If an error occurred
+    Ok: Every Device along with its registry entry. A Device without an entry, e.g. when the Service reported the same ID twice, is left out
+*/
+pub fn register_devices(db: Database, user_id: String, service_id: String, devices: Vec<Device>) -> Result<Vec<(Device, RegisteredDevice)>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let now = chrono::Utc::now().timestamp();
+
+    for device in &devices {
+        let device_id: String = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(32).map(char::from).collect();
+
+        //device_id is only used if the Device is new, (service_id, provider_device_id) is unique
+        let _ = conn.exec::<usize, &str, Params>("INSERT INTO devices (device_id, user_id, service_id, provider_device_id, kind, capabilities, provider_name, nicknames, exposed, last_seen) \
+            VALUES (:device_id, :user_id, :service_id, :provider_device_id, :kind, :capabilities, :provider_name, '[]', TRUE, :last_seen) \
+            ON DUPLICATE KEY UPDATE kind = :kind, capabilities = :capabilities, provider_name = :provider_name, last_seen = :last_seen", params! {
+            "device_id" => device_id,
+            "user_id" => user_id.clone(),
+            "service_id" => service_id.clone(),
+            "provider_device_id" => device.id.clone(),
+            "kind" => device.kind.to_string(),
+            "capabilities" => serde_json::to_string(&device.capabilities).unwrap(),
+            "provider_name" => device.name.clone(),
+            "last_seen" => now
+        })?;
+    }
+
+    let registered = get_service_devices(db, service_id)?;
+    let result = devices.into_iter()
+        .filter_map(|device| {
+            let entry = registered.iter().find(|entry| entry.provider_device_id == device.id).cloned()?;
+            Some((device, entry))
+        })
+        .collect();
+
+    Ok(result)
+}
+
+/**
+Get all registered Devices of a User, including Devices of which the Service is currently unreachable
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: The registered Devices
+*/
+pub fn get_devices(db: Database, user_id: String) -> Result<Vec<RegisteredDevice>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, String, Params>(format!("SELECT {} FROM devices WHERE user_id = :user_id", SELECT_COLUMNS), params! {
+        "user_id" => user_id
+    })?;
+
+    Ok(fetch_result.iter().map(row_to_device).collect())
+}
+
+/**
+Get all registered Devices of a Service
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+
+## Returns
+    Err: If an error occurred
+    Ok: The registered Devices
+*/
+pub fn get_service_devices(db: Database, service_id: String) -> Result<Vec<RegisteredDevice>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, String, Params>(format!("SELECT {} FROM devices WHERE service_id = :service_id", SELECT_COLUMNS), params! {
+        "service_id" => service_id
+    })?;
+
+    Ok(fetch_result.iter().map(row_to_device).collect())
+}
+
+/**
+Get a registered Device
+
+## Parameters
+    db: An instance of Database
+    device_id: The internal ID of the Device
+
+## Returns
+    Err: If an error occurred
+    Ok:
+        Some: The registered Device
+        None: The Device does not exist
+*/
+pub fn get_device(db: Database, device_id: String) -> Result<Option<RegisteredDevice>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, String, Params>(format!("SELECT {} FROM devices WHERE device_id = :device_id", SELECT_COLUMNS), params! {
+        "device_id" => device_id
+    })?;
+
+    Ok(fetch_result.first().map(row_to_device))
+}
+
+/**
+Set the name, nicknames and room chosen by the user for a Device
+
+## Parameters
+    db: An instance of Database
+    device_id: The internal ID of the Device
+    name: The name of the Device, None to use the name reported by the Service
+    nicknames: Alternative names, used by Google Assistant
+    room_id: The ID of the room the Device is in
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn update_device(db: Database, device_id: String, name: Option<String>, nicknames: &[String], room_id: Option<String>) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("UPDATE devices SET name = :name, nicknames = :nicknames, room_id = :room_id WHERE device_id = :device_id", params! {
+        "device_id" => device_id,
+        "name" => name,
+        "nicknames" => serde_json::to_string(nicknames).unwrap(),
+        "room_id" => room_id
+    })?;
+
+    Ok(())
+}
+
+/**
+Set if a Device is exposed to Google Assistant
+
+## Parameters
+    db: An instance of Database
+    device_id: The internal ID of the Device
+    exposed: If the Device should be exposed
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_exposed(db: Database, device_id: String, exposed: bool) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("UPDATE devices SET exposed = :exposed WHERE device_id = :device_id", params! {
+        "device_id" => device_id,
+        "exposed" => exposed
+    })?;
+
+    Ok(())
+}
+
+fn row_to_device(row: &Row) -> RegisteredDevice {
+    let kind = row.get::<String, &str>("kind").unwrap();
+    let capabilities = row.get::<String, &str>("capabilities").unwrap();
+    let nicknames = row.get::<String, &str>("nicknames").unwrap();
+
+    RegisteredDevice {
+        device_id: row.get::<String, &str>("device_id").unwrap(),
+        user_id: row.get::<String, &str>("user_id").unwrap(),
+        service_id: row.get::<String, &str>("service_id").unwrap(),
+        provider_device_id: row.get::<String, &str>("provider_device_id").unwrap(),
+        kind: DeviceKind::from_str(&kind).unwrap(),
+        capabilities: serde_json::from_str(&capabilities).unwrap_or_default(),
+        provider_name: row.get::<String, &str>("provider_name").unwrap(),
+        name: row.get::<Option<String>, &str>("name").unwrap(),
+        nicknames: serde_json::from_str(&nicknames).unwrap_or_default(),
+        room_id: row.get::<Option<String>, &str>("room_id").unwrap(),
+        exposed: row.get::<bool, &str>("exposed").unwrap(),
+        last_seen: row.get::<i64, &str>("last_seen").unwrap()
+    }
+}
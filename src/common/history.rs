@@ -0,0 +1,319 @@
+use crate::database::Database;
+use crate::types::device::DeviceProperty;
+use crate::types::history::{Aggregation, HistoryPoint, HistoryQuery, Resolution};
+
+use mysql::{Error, Params, params, Row};
+use mysql::prelude::Queryable;
+use std::collections::BTreeMap;
+
+/// How long raw samples are kept
+pub const RAW_RETENTION_SECONDS: i64 = 7 * 86400;
+/// How long hourly aggregates are kept
+pub const HOURLY_RETENTION_SECONDS: i64 = 90 * 86400;
+/// How long daily aggregates are kept
+pub const DAILY_RETENTION_SECONDS: i64 = 2 * 365 * 86400;
+/// The setpoint has to be this far above the temperature, in °C, for a Zone to be heating at full power
+const HEATING_MARGIN: f32 = 1.0;
+
+/**
+Store raw samples
+
+## Parameters
+    db: An instance of Database
+    samples: The samples, as (device_id, property, timestamp, value)
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn record(db: Database, samples: &[(String, DeviceProperty, i64, f32)]) -> Result<(), Error> {
+    if samples.is_empty() {
+        return Ok(());
+    }
+
+    let mut conn = db.pool.get_conn()?;
+    conn.exec_batch("INSERT INTO device_history (device_id, property, recorded_at, value) VALUES (:device_id, :property, :recorded_at, :value)",
+        samples.iter().map(|(device_id, property, recorded_at, value)| params! {
+            "device_id" => device_id,
+            "property" => property.to_string(),
+            "recorded_at" => recorded_at,
+            "value" => value
+        })
+    )?;
+
+    Ok(())
+}
+
+/**
+Downsample raw samples into hourly aggregates, and hourly aggregates into daily aggregates.
+Only completed hours and days are aggregated. Aggregates of the last day are recalculated, so this is safe to run repeatedly
+
+## Parameters
+    db: An instance of Database
+    now: The current Unix timestamp
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn downsample(db: Database, now: i64) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+
+    let hour = now - now % 3600;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO device_history_hourly (device_id, property, period_start, min, max, avg, count) \
+        SELECT device_id, property, recorded_at - recorded_at % 3600 AS period, MIN(value), MAX(value), AVG(value), COUNT(*) FROM device_history \
+        WHERE recorded_at >= :from AND recorded_at < :to GROUP BY device_id, property, period \
+        ON DUPLICATE KEY UPDATE min = VALUES(min), max = VALUES(max), avg = VALUES(avg), count = VALUES(count)", params! {
+        "from" => hour - 86400,
+        "to" => hour
+    })?;
+
+    //Days are in UTC. The average is weighted by the number of samples in every hour
+    let day = now - now % 86400;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO device_history_daily (device_id, property, period_start, min, max, avg, count) \
+        SELECT device_id, property, period_start - period_start % 86400 AS period, MIN(min), MAX(max), SUM(avg * count) / SUM(count), SUM(count) FROM device_history_hourly \
+        WHERE period_start >= :from AND period_start < :to GROUP BY device_id, property, period \
+        ON DUPLICATE KEY UPDATE min = VALUES(min), max = VALUES(max), avg = VALUES(avg), count = VALUES(count)", params! {
+        "from" => day - 3 * 86400,
+        "to" => day
+    })?;
+
+    Ok(())
+}
+
+/**
+Remove history which is past its retention
+
+## Parameters
+    db: An instance of Database
+    now: The current Unix timestamp
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn prune(db: Database, now: i64) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM device_history WHERE recorded_at < :threshold", params! {
+        "threshold" => now - RAW_RETENTION_SECONDS
+    })?;
+
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM device_history_hourly WHERE period_start < :threshold", params! {
+        "threshold" => now - HOURLY_RETENTION_SECONDS
+    })?;
+
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM device_history_daily WHERE period_start < :threshold", params! {
+        "threshold" => now - DAILY_RETENTION_SECONDS
+    })?;
+
+    Ok(())
+}
+
+/**
+Get the history of a property of a Device
+
+## Parameters
+    db: An instance of Database
+    device_id: The internal ID of the Device
+    property: The property
+    query: The range, resolution and aggregation
+
+## Returns
+    Err: If an error occurred
+    Ok: The points, ordered by time
+*/
+pub fn get_history(db: Database, device_id: String, property: DeviceProperty, query: &HistoryQuery) -> Result<Vec<HistoryPoint>, Error> {
+    let (table, time_column) = match query.resolution {
+        Resolution::RAW     => ("device_history", "recorded_at"),
+        Resolution::HOURLY  => ("device_history_hourly", "period_start"),
+        Resolution::DAILY   => ("device_history_daily", "period_start")
+    };
+
+    let value = match (query.resolution, query.aggregation) {
+        (Resolution::RAW, Aggregation::AVG) => "AVG(value)",
+        (Resolution::RAW, Aggregation::MIN) => "MIN(value)",
+        (Resolution::RAW, Aggregation::MAX) => "MAX(value)",
+        (_, Aggregation::AVG) => "SUM(avg * count) / SUM(count)",
+        (_, Aggregation::MIN) => "MIN(min)",
+        (_, Aggregation::MAX) => "MAX(max)"
+    };
+
+    //Raw samples are returned as they are when no interval is requested
+    let interval = query.interval.or_else(|| query.resolution.period_seconds());
+    let sql = match interval {
+        Some(_) => format!("SELECT {time} - {time} % :interval AS point_time, {value} AS point_value FROM {table} \
+            WHERE device_id = :device_id AND property = :property AND {time} >= :from AND {time} < :to GROUP BY point_time ORDER BY point_time",
+            time = time_column, value = value, table = table),
+        None => format!("SELECT recorded_at AS point_time, value AS point_value FROM {table} \
+            WHERE device_id = :device_id AND property = :property AND recorded_at >= :from AND recorded_at < :to ORDER BY recorded_at",
+            table = table)
+    };
+
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, String, Params>(sql, params! {
+        "device_id" => device_id,
+        "property" => property.to_string(),
+        "from" => query.from,
+        "to" => query.to,
+        "interval" => interval.unwrap_or(1)
+    })?;
+
+    let points = fetch_result.iter()
+        .map(|row| HistoryPoint {
+            timestamp: row.get::<i64, &str>("point_time").unwrap(),
+            value: row.get::<f64, &str>("point_value").unwrap() as f32
+        })
+        .collect();
+
+    Ok(points)
+}
+
+/**
+Estimate how fast a Zone heats up, from the hourly history of its temperature and setpoint
+
+## Parameters
+    db: An instance of Database
+    device_id: The ID of the Device
+    since: Unix timestamp from which history is used
+
+## Returns
+    Err: If an error occurred
+    None: If there is too little history to tell
+    Some: The median rise in °C per hour
+*/
+pub fn get_heating_rate(db: Database, device_id: String, since: i64) -> Result<Option<f32>, Error> {
+    const MIN_SAMPLES: usize = 5;
+
+    let mut samples: Vec<f32> = get_heating_samples(db, device_id, since, i64::MAX)?.into_iter()
+        .map(|(_, rise)| rise)
+        .collect();
+
+    if samples.len() < MIN_SAMPLES {
+        return Ok(None);
+    }
+
+    samples.sort_by(|a, b| a.total_cmp(b));
+    Ok(Some(samples[samples.len() / 2]))
+}
+
+/**
+Find the hours in which a Zone was heating, from the hourly history of its temperature and setpoint.
+Every hour in which the setpoint was well above the temperature, and the temperature rose by the next hour, counts as a sample
+
+## Parameters
+    db: An instance of Database
+    device_id: The ID of the Device
+    from: Unix timestamp from which history is used, inclusive
+    to: Unix timestamp until which history is used, exclusive
+
+## Returns
+    Err: If an error occurred
+    Ok: The start of every heating hour, with the rise in °C during that hour
+*/
+pub fn get_heating_samples(db: Database, device_id: String, from: i64, to: i64) -> Result<Vec<(i64, f32)>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT period_start, property, avg FROM device_history_hourly \
+        WHERE device_id = :device_id AND property IN (:ambient, :setpoint) AND period_start >= :from AND period_start < :to ORDER BY period_start", params! {
+        "device_id" => device_id,
+        "ambient" => DeviceProperty::AMBIENTTEMPERATURE.to_string(),
+        "setpoint" => DeviceProperty::SETPOINTTEMPERATURE.to_string(),
+        "from" => from,
+        "to" => to
+    })?;
+
+    //Per hour: the average temperature and setpoint
+    let mut hours: BTreeMap<i64, (Option<f32>, Option<f32>)> = BTreeMap::new();
+    for row in fetch_result {
+        let hour = hours.entry(row.get::<i64, &str>("period_start").unwrap()).or_insert((None, None));
+        let value = row.get::<f32, &str>("avg").unwrap();
+        if row.get::<String, &str>("property").unwrap() == DeviceProperty::AMBIENTTEMPERATURE.to_string() {
+            hour.0 = Some(value);
+        } else {
+            hour.1 = Some(value);
+        }
+    }
+
+    Ok(heating_samples(&hours))
+}
+
+/**
+Pair every hour in which a Zone was heating with the next hour, to find the rise during that hour
+
+## Parameters
+    hours: Per start of the hour, the average temperature and setpoint
+
+## Returns
+    The start of every heating hour, with the rise in °C during that hour
+*/
+fn heating_samples(hours: &BTreeMap<i64, (Option<f32>, Option<f32>)>) -> Vec<(i64, f32)> {
+    hours.iter()
+        .filter_map(|(period_start, hour)| {
+            let (ambient, setpoint) = (hour.0?, hour.1?);
+            let next_ambient = hours.get(&(period_start + 3600))?.0?;
+            if setpoint - ambient >= HEATING_MARGIN && next_ambient > ambient {
+                Some((*period_start, next_ambient - ambient))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/**
+Get the most recent raw sample of a property of a Device
+
+## Parameters
+    db: An instance of Database
+    device_id: The internal ID of the Device
+    property: The property
+
+## Returns
+    Err: If an error occurred
+    None: If the property has no samples within the raw retention
+    Some: The Unix timestamp of the sample, and its value
+*/
+pub fn get_latest(db: Database, device_id: String, property: DeviceProperty) -> Result<Option<(i64, f32)>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT recorded_at, value FROM device_history WHERE device_id = :device_id AND property = :property ORDER BY recorded_at DESC LIMIT 1", params! {
+        "device_id" => device_id,
+        "property" => property.to_string()
+    })?;
+
+    Ok(fetch_result.first().map(|row| (row.get::<i64, &str>("recorded_at").unwrap(), row.get::<f32, &str>("value").unwrap())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heating_hours_are_paired_with_the_next_hour() {
+        let hours: BTreeMap<i64, (Option<f32>, Option<f32>)> = vec![
+            (0, (Some(17.0), Some(20.0))),
+            (3600, (Some(18.5), Some(20.0))),
+            (7200, (Some(19.5), Some(20.0))),
+            (10800, (Some(20.0), Some(20.0)))
+        ].into_iter().collect();
+
+        //Close to the setpoint the Zone no longer heats at full power, and the last hour has no next hour
+        assert_eq!(heating_samples(&hours), vec![(0, 1.5), (3600, 1.0)]);
+    }
+
+    #[test]
+    fn hours_without_a_rise_or_a_next_hour_are_skipped() {
+        let hours: BTreeMap<i64, (Option<f32>, Option<f32>)> = vec![
+            //Cooling down while heating, e.g. with a window open
+            (0, (Some(17.0), Some(21.0))),
+            (3600, (Some(16.5), Some(21.0))),
+            //The next hour is missing
+            (14400, (Some(17.0), Some(21.0))),
+            //The setpoint wasn't recorded
+            (21600, (Some(17.0), None)),
+            (25200, (Some(18.0), Some(21.0))),
+            //The temperature wasn't recorded in the next hour
+            (28800, (None, Some(21.0)))
+        ].into_iter().collect();
+
+        assert_eq!(heating_samples(&hours), vec![]);
+    }
+}
@@ -0,0 +1,206 @@
+use crate::environment::Environment;
+
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+use rand::Rng;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::sync::Mutex;
+
+const HOMEGRAPH_ENDPOINT: &str = "https://homegraph.googleapis.com/v1";
+const HOMEGRAPH_SCOPE: &str = "https://www.googleapis.com/auth/homegraph";
+
+/// Access token for the HomeGraph API and the Unix timestamp at which it expires
+static ACCESS_TOKEN: Mutex<Option<(String, i64)>> = Mutex::new(None);
+
+#[derive(Deserialize)]
+struct ServiceAccountKey {
+    client_email:       String,
+    private_key:        String,
+    token_uri:          String
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token:       String,
+    expires_in:         i64
+}
+
+/**
+Ask Google to send a SYNC intent for a User, e.g. after the set of exposed Devices changed
+
+## Parameters
+    user_id: The ID of the User, this is the agentUserId known to Google
+
+## Returns
+    Err: If an error occurred, or if no service account is configured
+    Ok: If Google accepted the request
+*/
+pub fn request_sync(user_id: &str) -> Result<(), ()> {
+    let access_token = get_access_token()?;
+
+    let request = reqwest::blocking::Client::new().post(format!("{}/devices:requestSync", HOMEGRAPH_ENDPOINT))
+        .bearer_auth(access_token)
+        .json(&json!({ "agentUserId": user_id, "async": true }))
+        .send();
+
+    if request.is_err() {
+        eprintln!("An error occurred: {:?}", request.err());
+        return Err(());
+    }
+
+    let response = request.unwrap();
+    if !response.status().is_success() {
+        eprintln!("HomeGraph returned status {} for requestSync: {:?}", response.status(), response.text());
+        return Err(());
+    }
+
+    Ok(())
+}
+
+/**
+Request a SYNC for a User without waiting for Google, errors are only logged
+
+## Parameters
+    user_id: The ID of the User
+*/
+pub fn request_sync_in_background(user_id: String) {
+    std::thread::spawn(move || {
+        if request_sync(&user_id).is_err() {
+            eprintln!("Request Sync for User '{}' failed", user_id);
+        }
+    });
+}
+
+/**
+Report the state of Devices of a User to Google, so Google doesn't have to QUERY for it
+
+## Parameters
+    user_id: The ID of the User, this is the agentUserId known to Google
+    states: The states of the Devices, keyed by the ID of the Device, in the format Google expects
+
+## Returns
+    Err: If an error occurred, or if no service account is configured
+    Ok: If Google accepted the report
+*/
+pub fn report_state(user_id: &str, states: Map<String, Value>) -> Result<(), ()> {
+    let access_token = get_access_token()?;
+
+    let request_id: String = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(32).map(char::from).collect();
+    let request = reqwest::blocking::Client::new().post(format!("{}/devices:reportStateAndNotification", HOMEGRAPH_ENDPOINT))
+        .bearer_auth(access_token)
+        .json(&json!({
+            "requestId": request_id,
+            "agentUserId": user_id,
+            "payload": { "devices": { "states": states } }
+        }))
+        .send();
+
+    if request.is_err() {
+        eprintln!("An error occurred: {:?}", request.err());
+        return Err(());
+    }
+
+    let response = request.unwrap();
+    if !response.status().is_success() {
+        eprintln!("HomeGraph returned status {} for reportStateAndNotification: {:?}", response.status(), response.text());
+        return Err(());
+    }
+
+    Ok(())
+}
+
+/**
+Get an access token for the HomeGraph API, using the service account key. Tokens are cached until they're about to expire
+*/
+fn get_access_token() -> Result<String, ()> {
+    let now = chrono::Utc::now().timestamp();
+    if let Some((access_token, expiry)) = ACCESS_TOKEN.lock().unwrap().as_ref() {
+        if *expiry - crate::common::oauth::REFRESH_MARGIN_SECONDS > now {
+            return Ok(access_token.clone());
+        }
+    }
+
+    let env = Environment::new();
+    if env.google_service_account_key.is_none() {
+        eprintln!("GOOGLE_SERVICE_ACCOUNT_KEY is not set, unable to use the HomeGraph API");
+        return Err(());
+    }
+
+    let key_content = fs::read_to_string(env.google_service_account_key.unwrap());
+    if key_content.is_err() {
+        eprintln!("Unable to read the Google service account key: {:?}", key_content.err());
+        return Err(());
+    }
+
+    let key = serde_json::from_str::<ServiceAccountKey>(&key_content.unwrap());
+    if key.is_err() {
+        eprintln!("Invalid Google service account key: {:?}", key.err());
+        return Err(());
+    }
+
+    let key = key.unwrap();
+    let assertion = create_assertion(&key, now)?;
+
+    let request = reqwest::blocking::Client::new().post(&key.token_uri)
+        .form(&[("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"), ("assertion", assertion.as_str())])
+        .send();
+
+    if request.is_err() {
+        eprintln!("An error occurred: {:?}", request.err());
+        return Err(());
+    }
+
+    let response = request.unwrap();
+    if !response.status().is_success() {
+        eprintln!("Google token endpoint returned status {}: {:?}", response.status(), response.text());
+        return Err(());
+    }
+
+    let tokens = response.json::<TokenResponse>();
+    if tokens.is_err() {
+        eprintln!("Unable to deserialize Google token response: {:?}", tokens.err());
+        return Err(());
+    }
+
+    let tokens = tokens.unwrap();
+    *ACCESS_TOKEN.lock().unwrap() = Some((tokens.access_token.clone(), now + tokens.expires_in));
+
+    Ok(tokens.access_token)
+}
+
+/**
+Create a JWT signed with the service account's private key, which is exchanged for an access token
+*/
+fn create_assertion(key: &ServiceAccountKey, now: i64) -> Result<String, ()> {
+    let header = json!({ "alg": "RS256", "typ": "JWT" });
+    let claims = json!({
+        "iss": key.client_email,
+        "scope": HOMEGRAPH_SCOPE,
+        "aud": key.token_uri,
+        "iat": now,
+        "exp": now + 3600
+    });
+
+    let encode = |value: &serde_json::Value| base64::encode_config(value.to_string(), base64::URL_SAFE_NO_PAD);
+    let unsigned = format!("{}.{}", encode(&header), encode(&claims));
+
+    let private_key = PKey::private_key_from_pem(key.private_key.as_bytes());
+    if private_key.is_err() {
+        eprintln!("Invalid private key in the Google service account key: {:?}", private_key.err());
+        return Err(());
+    }
+
+    let private_key = private_key.unwrap();
+    let signature = Signer::new(MessageDigest::sha256(), &private_key)
+        .and_then(|mut signer| signer.sign_oneshot_to_vec(unsigned.as_bytes()));
+
+    if signature.is_err() {
+        eprintln!("Unable to sign JWT: {:?}", signature.err());
+        return Err(());
+    }
+
+    Ok(format!("{}.{}", unsigned, base64::encode_config(signature.unwrap(), base64::URL_SAFE_NO_PAD)))
+}
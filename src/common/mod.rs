@@ -1,2 +1,17 @@
 pub mod user;
-pub mod service;
\ No newline at end of file
+pub mod service;
+pub mod oauth;
+pub mod device;
+pub mod room;
+pub mod homegraph;
+pub mod scene;
+pub mod schedule;
+pub mod sun;
+pub mod presence;
+pub mod rule;
+pub mod history;
+pub mod notification;
+pub mod webhook;
+pub mod vacation;
+pub mod optimal_start;
+pub mod open_window;
@@ -0,0 +1,277 @@
+use crate::appdata::AppData;
+use crate::database::Database;
+use crate::environment::Environment;
+use crate::types::device::DeviceAlert;
+use crate::types::notification::{Notification, NotificationChannel, NotificationTarget};
+
+use lettre::{Message, SmtpTransport, Transport};
+use lettre::transport::smtp::authentication::Credentials;
+use mysql::{Error, Params, params, Row};
+use mysql::prelude::Queryable;
+use tera::{Context, Tera};
+
+const DEFAULT_SMTP_PORT: u16 = 587;
+
+/**
+Get all notification channels of a User, ordered by name
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: The channels
+*/
+pub fn get_channels(db: Database, user_id: String) -> Result<Vec<NotificationChannel>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT channel_id, name, enabled, target, alerts, template FROM notification_channels WHERE user_id = :user_id ORDER BY name", params! {
+        "user_id" => user_id
+    })?;
+
+    Ok(fetch_result.iter().map(row_to_channel).collect())
+}
+
+/**
+Create or update a notification channel
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User owning the channel
+    channel: The channel
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_channel(db: Database, user_id: String, channel: &NotificationChannel) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO notification_channels (channel_id, user_id, name, enabled, target, alerts, template) VALUES (:channel_id, :user_id, :name, :enabled, :target, :alerts, :template) \
+        ON DUPLICATE KEY UPDATE name = :name, enabled = :enabled, target = :target, alerts = :alerts, template = :template", params! {
+        "channel_id" => channel.channel_id.clone(),
+        "user_id" => user_id,
+        "name" => channel.name.clone(),
+        "enabled" => channel.enabled,
+        "target" => serde_json::to_string(&channel.target).unwrap(),
+        "alerts" => serde_json::to_string(&channel.alerts).unwrap(),
+        "template" => channel.template.clone()
+    })?;
+
+    Ok(())
+}
+
+/**
+Remove a notification channel
+
+## Parameters
+    db: An instance of Database
+    channel_id: The ID of the channel
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn delete_channel(db: Database, channel_id: String) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM notification_channels WHERE channel_id = :channel_id", params! {
+        "channel_id" => channel_id
+    })?;
+
+    Ok(())
+}
+
+/**
+Get the alerts of a Device the User has already been notified of
+
+## Parameters
+    db: An instance of Database
+    device_id: The ID of the Device
+
+## Returns
+    Err: If an error occurred
+    Ok: The alerts
+*/
+pub fn get_active_alerts(db: Database, device_id: String) -> Result<Vec<DeviceAlert>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT alert FROM device_alerts WHERE device_id = :device_id", params! {
+        "device_id" => device_id
+    })?;
+
+    Ok(fetch_result.iter()
+        .filter_map(|row| DeviceAlert::from_name(&row.get::<String, &str>("alert").unwrap()))
+        .collect())
+}
+
+/**
+Mark an alert of a Device as active, so the User isn't notified of it again until it has cleared
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User owning the Device
+    device_id: The ID of the Device
+    alert: The alert
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_alert_active(db: Database, user_id: String, device_id: String, alert: DeviceAlert) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT IGNORE INTO device_alerts (device_id, user_id, alert, raised_at) VALUES (:device_id, :user_id, :alert, :raised_at)", params! {
+        "device_id" => device_id,
+        "user_id" => user_id,
+        "alert" => alert.to_string(),
+        "raised_at" => chrono::Utc::now().timestamp()
+    })?;
+
+    Ok(())
+}
+
+/**
+Mark an alert of a Device as cleared
+
+## Parameters
+    db: An instance of Database
+    device_id: The ID of the Device
+    alert: The alert
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn clear_alert(db: Database, device_id: String, alert: DeviceAlert) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM device_alerts WHERE device_id = :device_id AND alert = :alert", params! {
+        "device_id" => device_id,
+        "alert" => alert.to_string()
+    })?;
+
+    Ok(())
+}
+
+/**
+Render the body of a notification. The channel's own template is used if it has one,
+otherwise the default template for the alert from templates/notifications/
+
+## Parameters
+    data: An instance of AppData
+    channel: The channel the notification is sent through
+    alert: The alert the notification is about
+    context: The values available to the template
+
+## Returns
+    Err: If the template could not be rendered
+    Ok: The body
+*/
+pub fn render(data: &AppData, channel: &NotificationChannel, alert: DeviceAlert, context: &Context) -> Result<String, ()> {
+    let rendered = match &channel.template {
+        Some(template) => Tera::one_off(template, context, false),
+        None => data.tera.render(&format!("notifications/{}.txt", alert), context)
+    };
+
+    rendered.map(|body| body.trim().to_string()).map_err(|e| eprintln!("Unable to render notification template: {:?}", e))
+}
+
+/**
+Send a notification
+
+## Parameters
+    target: Where to send the notification to
+    notification: The notification
+
+## Returns
+    Err: If the notification could not be sent
+    Ok: If the notification was sent
+*/
+pub fn send(target: &NotificationTarget, notification: &Notification) -> Result<(), ()> {
+    let client = reqwest::blocking::Client::new();
+    let request = match target {
+        NotificationTarget::EMAIL { address } => return send_email(address, notification),
+        NotificationTarget::NTFY { url, topic, token } => {
+            let request = client.post(format!("{}/{}", url.trim_end_matches('/'), topic))
+                .header("Title", notification.title.clone())
+                .body(notification.body.clone());
+
+            match token {
+                Some(token) => request.bearer_auth(token),
+                None => request
+            }
+        },
+        NotificationTarget::GOTIFY { url, token } => client.post(format!("{}/message", url.trim_end_matches('/')))
+            .header("X-Gotify-Key", token.clone())
+            .json(&serde_json::json!({ "title": notification.title, "message": notification.body })),
+        NotificationTarget::WEBHOOK { url } => client.post(url).json(notification)
+    };
+
+    let response = request.send();
+    if response.is_err() {
+        eprintln!("An error occurred: {:?}", response.err());
+        return Err(());
+    }
+
+    let response = response.unwrap();
+    if !response.status().is_success() {
+        eprintln!("Notification target returned status {}", response.status());
+        return Err(());
+    }
+
+    Ok(())
+}
+
+/**
+Send a notification by email, through the SMTP server configured in the environment
+*/
+fn send_email(address: &str, notification: &Notification) -> Result<(), ()> {
+    let env = Environment::new();
+    if env.smtp_host.is_none() || env.smtp_from.is_none() {
+        eprintln!("SMTP_HOST or SMTP_FROM is not set, unable to send email notifications");
+        return Err(());
+    }
+
+    let (from, to) = (env.smtp_from.unwrap().parse(), address.parse());
+    if from.is_err() || to.is_err() {
+        eprintln!("Invalid email address: {:?} {:?}", from.err(), to.err());
+        return Err(());
+    }
+
+    let message = Message::builder()
+        .from(from.unwrap())
+        .to(to.unwrap())
+        .subject(notification.title.clone())
+        .body(notification.body.clone());
+
+    if message.is_err() {
+        eprintln!("Unable to build email: {:?}", message.err());
+        return Err(());
+    }
+
+    let transport = SmtpTransport::starttls_relay(&env.smtp_host.unwrap());
+    if transport.is_err() {
+        eprintln!("Unable to set up SMTP transport: {:?}", transport.err());
+        return Err(());
+    }
+
+    let mut transport = transport.unwrap().port(env.smtp_port.unwrap_or(DEFAULT_SMTP_PORT));
+    if let (Some(username), Some(password)) = (env.smtp_username, env.smtp_password) {
+        transport = transport.credentials(Credentials::new(username, password));
+    }
+
+    let send_result = transport.build().send(&message.unwrap());
+    if send_result.is_err() {
+        eprintln!("Unable to send email: {:?}", send_result.err());
+        return Err(());
+    }
+
+    Ok(())
+}
+
+fn row_to_channel(row: &Row) -> NotificationChannel {
+    NotificationChannel {
+        channel_id: row.get::<String, &str>("channel_id").unwrap(),
+        name: row.get::<String, &str>("name").unwrap(),
+        enabled: row.get::<bool, &str>("enabled").unwrap(),
+        target: serde_json::from_str(&row.get::<String, &str>("target").unwrap()).unwrap(),
+        alerts: serde_json::from_str(&row.get::<String, &str>("alerts").unwrap()).unwrap_or_default(),
+        template: row.get::<Option<String>, &str>("template").unwrap()
+    }
+}
@@ -0,0 +1,354 @@
+use crate::config::OAuthServiceConfig;
+use crate::database::Database;
+use crate::environment::Environment;
+
+use mysql::{Error, Params, params, Row};
+use mysql::prelude::Queryable;
+use magic_crypt::MagicCryptTrait;
+use magic_crypt::MagicCrypt256;
+use serde::Deserialize;
+
+/// Tokens are refreshed when they expire within this many seconds
+pub const REFRESH_MARGIN_SECONDS: i64 = 300;
+/// A pending authorization must be completed within this many seconds
+const STATE_TTL_SECONDS: i64 = 600;
+
+#[derive(Clone)]
+pub struct OAuthTokens {
+    pub access_token:   String,
+    pub refresh_token:  String,
+    /// Unix timestamp at which the access_token expires
+    pub expiry:         i64
+}
+
+#[derive(Deserialize)]
+struct TokenEndpointResponse {
+    access_token:       String,
+    refresh_token:      Option<String>,
+    expires_in:         Option<i64>
+}
+
+/**
+Get the redirect_uri which OAuth providers should redirect to after the user authorized us
+
+## Returns
+    The redirect_uri
+*/
+pub fn get_redirect_uri() -> String {
+    let env = Environment::new();
+    format!("{}/services/oauth/callback", env.host)
+}
+
+/**
+Create the URL the user should be sent to, to authorize us with an OAuth provider
+
+## Parameters
+    config: The OAuth configuration of the Service
+    state: The state to pass along, this is returned to us in the callback
+
+## Returns
+    Err: If the authorization_endpoint in the configuration is not a valid URL
+    Ok: The authorization URL
+*/
+pub fn get_authorization_url(config: &OAuthServiceConfig, state: &str) -> Result<String, ()> {
+    let mut url = reqwest::Url::parse(&config.authorization_endpoint).map_err(|e| eprintln!("Invalid authorization_endpoint '{}': {:?}", config.authorization_endpoint, e))?;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &config.client_id)
+        .append_pair("redirect_uri", &get_redirect_uri())
+        .append_pair("state", state);
+
+    if let Some(scope) = &config.scope {
+        url.query_pairs_mut().append_pair("scope", scope);
+    }
+
+    Ok(url.to_string())
+}
+
+/**
+Exchange an authorization code for an access and refresh token
+
+## Parameters
+    config: The OAuth configuration of the Service
+    code: The authorization code received in the callback
+
+## Returns
+    Err: If an error occurred, or if the provider rejected the code
+    Ok: The tokens
+*/
+pub fn exchange_code(config: &OAuthServiceConfig, code: &str) -> Result<OAuthTokens, ()> {
+    let redirect_uri = get_redirect_uri();
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", &redirect_uri),
+        ("client_id", &config.client_id)
+    ];
+
+    if let Some(client_secret) = &config.client_secret {
+        form.push(("client_secret", client_secret));
+    }
+
+    let response = request_tokens(config, &form)?;
+    if response.refresh_token.is_none() {
+        eprintln!("OAuth provider did not return a refresh_token");
+        return Err(());
+    }
+
+    Ok(OAuthTokens {
+        access_token: response.access_token,
+        refresh_token: response.refresh_token.unwrap(),
+        expiry: chrono::Utc::now().timestamp() + response.expires_in.unwrap_or(3600)
+    })
+}
+
+/**
+Use a refresh token to get a new access token
+
+## Parameters
+    config: The OAuth configuration of the Service
+    refresh_token: The refresh token
+
+## Returns
+    Err: If an error occurred, or if the provider rejected the refresh token
+    Ok: The new tokens. If the provider did not issue a new refresh token, the old one is kept
+*/
+pub fn refresh(config: &OAuthServiceConfig, refresh_token: &str) -> Result<OAuthTokens, ()> {
+    let mut form = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+        ("client_id", &config.client_id)
+    ];
+
+    if let Some(client_secret) = &config.client_secret {
+        form.push(("client_secret", client_secret));
+    }
+
+    let response = request_tokens(config, &form)?;
+
+    Ok(OAuthTokens {
+        access_token: response.access_token,
+        refresh_token: response.refresh_token.unwrap_or_else(|| refresh_token.to_string()),
+        expiry: chrono::Utc::now().timestamp() + response.expires_in.unwrap_or(3600)
+    })
+}
+
+fn request_tokens(config: &OAuthServiceConfig, form: &[(&str, &str)]) -> Result<TokenEndpointResponse, ()> {
+    let request = reqwest::blocking::Client::new().post(&config.token_endpoint).form(form).send();
+    if request.is_err() {
+        eprintln!("An error occurred: {:?}", request.err());
+        return Err(());
+    }
+
+    let response = request.unwrap();
+    if !response.status().is_success() {
+        eprintln!("OAuth token endpoint returned status {}: {:?}", response.status(), response.text());
+        return Err(());
+    }
+
+    let tokens = response.json::<TokenEndpointResponse>();
+    if tokens.is_err() {
+        eprintln!("Unable to deserialize OAuth token response: {:?}", tokens.err());
+        return Err(());
+    }
+
+    Ok(tokens.unwrap())
+}
+
+/**
+Store the OAuth tokens for a Service. Existing tokens are overwritten
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+    tokens: The tokens to store
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_tokens(db: Database, service_id: String, tokens: OAuthTokens) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+
+    let env = Environment::new();
+    let mc: MagicCrypt256 = new_magic_crypt!(env.password_pepper, 256);
+
+    let access_token_encrypted = mc.encrypt_str_to_base64(tokens.access_token);
+    let refresh_token_encrypted = mc.encrypt_str_to_base64(tokens.refresh_token);
+
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO services_oauth_tokens (service_id, access_token, refresh_token, expiry) VALUES (:service_id, :access_token, :refresh_token, :expiry) \
+        ON DUPLICATE KEY UPDATE access_token = :access_token, refresh_token = :refresh_token, expiry = :expiry", params! {
+        "service_id" => service_id,
+        "access_token" => access_token_encrypted,
+        "refresh_token" => refresh_token_encrypted,
+        "expiry" => tokens.expiry
+    })?;
+
+    Ok(())
+}
+
+/**
+Get the OAuth tokens of a Service
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+
+## Returns
+    Err: If an error occurred
+    Ok:
+        Some: The tokens
+        None: The Service has no OAuth tokens
+*/
+pub fn get_tokens(db: Database, service_id: String) -> Result<Option<OAuthTokens>, Error> {
+    let mut conn = db.pool.get_conn()?;
+
+    let env = Environment::new();
+    let mc: MagicCrypt256 = new_magic_crypt!(env.password_pepper, 256);
+
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT access_token, refresh_token, expiry FROM services_oauth_tokens WHERE service_id = :service_id", params! {
+        "service_id" => service_id
+    })?;
+
+    if fetch_result.is_empty() {
+        return Ok(None);
+    }
+
+    let row = fetch_result.first().unwrap();
+    let access_token_encrypted = row.get::<String, &str>("access_token").unwrap();
+    let refresh_token_encrypted = row.get::<String, &str>("refresh_token").unwrap();
+    let expiry = row.get::<i64, &str>("expiry").unwrap();
+
+    Ok(Some(OAuthTokens {
+        access_token: mc.decrypt_base64_to_string(&access_token_encrypted).unwrap(),
+        refresh_token: mc.decrypt_base64_to_string(&refresh_token_encrypted).unwrap(),
+        expiry
+    }))
+}
+
+/**
+Get a valid access token for a Service, refreshing it first if it is about to expire
+
+## Parameters
+    db: An instance of Database
+    config: The OAuth configuration of the Service
+    service_id: The ID of the Service
+
+## Returns
+    Err: If an error occurred, if the Service has no tokens or if refreshing failed
+    Ok: The access token
+*/
+pub fn get_access_token(db: Database, config: &OAuthServiceConfig, service_id: String) -> Result<String, ()> {
+    let tokens = get_tokens(db.clone(), service_id.clone());
+    if tokens.is_err() {
+        eprintln!("An error occurred: {:?}", tokens.err());
+        return Err(());
+    }
+
+    let tokens_unwrapped = tokens.unwrap();
+    if tokens_unwrapped.is_none() {
+        eprintln!("No OAuth tokens found for Service '{}'", service_id);
+        return Err(());
+    }
+
+    let tokens = tokens_unwrapped.unwrap();
+    if tokens.expiry - REFRESH_MARGIN_SECONDS > chrono::Utc::now().timestamp() {
+        return Ok(tokens.access_token);
+    }
+
+    let refreshed = refresh(config, &tokens.refresh_token)?;
+    let set_result = set_tokens(db, service_id, refreshed.clone());
+    if set_result.is_err() {
+        eprintln!("Unable to store refreshed OAuth tokens: {:?}", set_result.err());
+    }
+
+    Ok(refreshed.access_token)
+}
+
+/**
+Get all Services of which the OAuth access token is about to expire
+
+## Parameters
+    db: An instance of Database
+
+## Returns
+    Err: If an error occurred
+    Ok: A Vector of (service_id, identifier)
+*/
+pub fn get_expiring_services(db: Database) -> Result<Vec<(String, String)>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT services_oauth_tokens.service_id, services.identifier FROM services_oauth_tokens \
+        INNER JOIN services ON services.service_id = services_oauth_tokens.service_id WHERE services_oauth_tokens.expiry < :threshold", params! {
+        "threshold" => chrono::Utc::now().timestamp() + REFRESH_MARGIN_SECONDS
+    })?;
+
+    let result = fetch_result.iter()
+        .map(|row| (row.get::<String, &str>("service_id").unwrap(), row.get::<String, &str>("identifier").unwrap()))
+        .collect();
+
+    Ok(result)
+}
+
+/**
+Store a pending authorization, created when a user adds a Service with the OAUTH login method
+
+## Parameters
+    db: An instance of Database
+    state: The state passed to the OAuth provider
+    user_id: The ID of the User adding the Service
+    identifier: The ServiceType being added
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn create_state(db: Database, state: String, user_id: String, identifier: String) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO services_oauth_states (state, user_id, identifier, created_at) VALUES (:state, :user_id, :identifier, :created_at)", params! {
+        "state" => state,
+        "user_id" => user_id,
+        "identifier" => identifier,
+        "created_at" => chrono::Utc::now().timestamp()
+    })?;
+
+    Ok(())
+}
+
+/**
+Take a pending authorization. The authorization is removed, a state can only be used once.
+Expired authorizations are removed as well
+
+## Parameters
+    db: An instance of Database
+    state: The state returned by the OAuth provider
+
+## Returns
+    Err: If an error occurred
+    Ok:
+        Some: (user_id, identifier)
+        None: The state is unknown, or has expired
+*/
+pub fn take_state(db: Database, state: String) -> Result<Option<(String, String)>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM services_oauth_states WHERE created_at < :expired_before", params! {
+        "expired_before" => chrono::Utc::now().timestamp() - STATE_TTL_SECONDS
+    })?;
+
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT user_id, identifier FROM services_oauth_states WHERE state = :state", params! {
+        "state" => state.clone()
+    })?;
+
+    if fetch_result.is_empty() {
+        return Ok(None);
+    }
+
+    let row = fetch_result.first().unwrap();
+    let user_id = row.get::<String, &str>("user_id").unwrap();
+    let identifier = row.get::<String, &str>("identifier").unwrap();
+
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM services_oauth_states WHERE state = :state", params! {
+        "state" => state
+    })?;
+
+    Ok(Some((user_id, identifier)))
+}
@@ -0,0 +1,222 @@
+use crate::database::Database;
+use crate::types::open_window::{OpenWindow, OpenWindowDetection};
+
+use mysql::{Error, Params, params, Row};
+use mysql::prelude::Queryable;
+
+/**
+Get the open window detection of all Devices of a User
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: The detections
+*/
+pub fn get_detections(db: Database, user_id: String) -> Result<Vec<OpenWindowDetection>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT device_id, enabled, drop_rate, frost_setpoint, timeout_minutes FROM open_window_detection WHERE user_id = :user_id", params! {
+        "user_id" => user_id
+    })?;
+
+    Ok(fetch_result.iter().map(row_to_detection).collect())
+}
+
+/**
+Get the open window detection of a Device
+
+## Parameters
+    db: An instance of Database
+    device_id: The internal ID of the Device
+
+## Returns
+    Err: If an error occurred
+    None: If open window detection is not configured for the Device
+    Some: The detection
+*/
+pub fn get_detection(db: Database, device_id: String) -> Result<Option<OpenWindowDetection>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT device_id, enabled, drop_rate, frost_setpoint, timeout_minutes FROM open_window_detection WHERE device_id = :device_id", params! {
+        "device_id" => device_id
+    })?;
+
+    Ok(fetch_result.first().map(row_to_detection))
+}
+
+/**
+Create or replace the open window detection of a Device
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User owning the Device
+    detection: The detection, it must be valid
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_detection(db: Database, user_id: String, detection: &OpenWindowDetection) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO open_window_detection (device_id, user_id, enabled, drop_rate, frost_setpoint, timeout_minutes) \
+        VALUES (:device_id, :user_id, :enabled, :drop_rate, :frost_setpoint, :timeout_minutes) \
+        ON DUPLICATE KEY UPDATE enabled = :enabled, drop_rate = :drop_rate, frost_setpoint = :frost_setpoint, timeout_minutes = :timeout_minutes", params! {
+        "device_id" => detection.device_id.clone(),
+        "user_id" => user_id,
+        "enabled" => detection.enabled,
+        "drop_rate" => detection.drop_rate,
+        "frost_setpoint" => detection.frost_setpoint,
+        "timeout_minutes" => detection.timeout_minutes
+    })?;
+
+    Ok(())
+}
+
+/**
+Remove the open window detection of a Device
+
+## Parameters
+    db: An instance of Database
+    device_id: The internal ID of the Device
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn delete_detection(db: Database, device_id: String) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM open_window_detection WHERE device_id = :device_id", params! {
+        "device_id" => device_id
+    })?;
+
+    Ok(())
+}
+
+/**
+Get all windows currently detected as open, of all Users
+
+## Parameters
+    db: An instance of Database
+
+## Returns
+    Err: If an error occurred
+    Ok: The open windows, along with the ID of the User they belong to
+*/
+pub fn get_open_windows(db: Database) -> Result<Vec<(String, OpenWindow)>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.query::<Row, &str>("SELECT device_id, user_id, setpoint, lowest_temperature, detected_at FROM open_windows")?;
+
+    let open_windows = fetch_result.iter()
+        .map(|row| (row.get::<String, &str>("user_id").unwrap(), row_to_open_window(row)))
+        .collect();
+
+    Ok(open_windows)
+}
+
+/**
+Get the window of a Device, if it is currently detected as open
+
+## Parameters
+    db: An instance of Database
+    device_id: The internal ID of the Device
+
+## Returns
+    Err: If an error occurred
+    None: If the window is not detected as open
+    Some: The open window
+*/
+pub fn get_open_window(db: Database, device_id: String) -> Result<Option<OpenWindow>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT device_id, setpoint, lowest_temperature, detected_at FROM open_windows WHERE device_id = :device_id", params! {
+        "device_id" => device_id
+    })?;
+
+    Ok(fetch_result.first().map(row_to_open_window))
+}
+
+/**
+Get the windows currently detected as open in the home of a User
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: The open windows
+*/
+pub fn get_user_open_windows(db: Database, user_id: String) -> Result<Vec<OpenWindow>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT device_id, setpoint, lowest_temperature, detected_at FROM open_windows WHERE user_id = :user_id", params! {
+        "user_id" => user_id
+    })?;
+
+    Ok(fetch_result.iter().map(row_to_open_window).collect())
+}
+
+/**
+Store a window detected as open, or update the lowest temperature since it was opened
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User owning the Device
+    open_window: The open window
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_open_window(db: Database, user_id: String, open_window: &OpenWindow) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO open_windows (device_id, user_id, setpoint, lowest_temperature, detected_at) \
+        VALUES (:device_id, :user_id, :setpoint, :lowest_temperature, :detected_at) \
+        ON DUPLICATE KEY UPDATE setpoint = :setpoint, lowest_temperature = :lowest_temperature, detected_at = :detected_at", params! {
+        "device_id" => open_window.device_id.clone(),
+        "user_id" => user_id,
+        "setpoint" => open_window.setpoint,
+        "lowest_temperature" => open_window.lowest_temperature,
+        "detected_at" => open_window.detected_at
+    })?;
+
+    Ok(())
+}
+
+/**
+Remove a window detected as open, after its setpoint was restored
+
+## Parameters
+    db: An instance of Database
+    device_id: The internal ID of the Device
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn delete_open_window(db: Database, device_id: String) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM open_windows WHERE device_id = :device_id", params! {
+        "device_id" => device_id
+    })?;
+
+    Ok(())
+}
+
+fn row_to_detection(row: &Row) -> OpenWindowDetection {
+    OpenWindowDetection {
+        device_id: row.get::<String, &str>("device_id").unwrap(),
+        enabled: row.get::<bool, &str>("enabled").unwrap(),
+        drop_rate: row.get::<f32, &str>("drop_rate").unwrap(),
+        frost_setpoint: row.get::<f32, &str>("frost_setpoint").unwrap(),
+        timeout_minutes: row.get::<u32, &str>("timeout_minutes").unwrap()
+    }
+}
+
+fn row_to_open_window(row: &Row) -> OpenWindow {
+    OpenWindow {
+        device_id: row.get::<String, &str>("device_id").unwrap(),
+        setpoint: row.get::<f32, &str>("setpoint").unwrap(),
+        lowest_temperature: row.get::<f32, &str>("lowest_temperature").unwrap(),
+        detected_at: row.get::<i64, &str>("detected_at").unwrap()
+    }
+}
@@ -0,0 +1,158 @@
+use crate::database::Database;
+use crate::types::optimal_start::{HeatingModel, HeatingObservation};
+
+use mysql::{Error, Params, params, Row};
+use mysql::prelude::Queryable;
+
+/**
+Store a heating hour of a Zone. An hour which was already stored is left as it is
+
+## Parameters
+    db: An instance of Database
+    device_id: The internal ID of the Device
+    observation: The observation
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn record_observation(db: Database, device_id: String, observation: &HeatingObservation) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT IGNORE INTO heating_observations (device_id, observed_at, outdoor_temperature, minutes_per_degree) \
+        VALUES (:device_id, :observed_at, :outdoor_temperature, :minutes_per_degree)", params! {
+        "device_id" => device_id,
+        "observed_at" => observation.observed_at,
+        "outdoor_temperature" => observation.outdoor_temperature,
+        "minutes_per_degree" => observation.minutes_per_degree
+    })?;
+
+    Ok(())
+}
+
+/**
+Get the heating hours of a Zone
+
+## Parameters
+    db: An instance of Database
+    device_id: The internal ID of the Device
+
+## Returns
+    Err: If an error occurred
+    Ok: The observations, oldest first
+*/
+pub fn get_observations(db: Database, device_id: String) -> Result<Vec<HeatingObservation>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT observed_at, outdoor_temperature, minutes_per_degree FROM heating_observations WHERE device_id = :device_id ORDER BY observed_at", params! {
+        "device_id" => device_id
+    })?;
+
+    let observations = fetch_result.iter()
+        .map(|row| HeatingObservation {
+            observed_at: row.get::<i64, &str>("observed_at").unwrap(),
+            outdoor_temperature: row.get::<Option<f32>, &str>("outdoor_temperature").unwrap(),
+            minutes_per_degree: row.get::<f32, &str>("minutes_per_degree").unwrap()
+        })
+        .collect();
+
+    Ok(observations)
+}
+
+/**
+Remove heating hours older than a moment, so the models follow changes to the home
+
+## Parameters
+    db: An instance of Database
+    threshold: Unix timestamp before which observations are removed
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn prune_observations(db: Database, threshold: i64) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM heating_observations WHERE observed_at < :threshold", params! {
+        "threshold" => threshold
+    })?;
+
+    Ok(())
+}
+
+/**
+Get the learned model of a Zone
+
+## Parameters
+    db: An instance of Database
+    device_id: The internal ID of the Device
+
+## Returns
+    Err: If an error occurred
+    None: If the Zone has not learned enough yet
+    Some: The model
+*/
+pub fn get_model(db: Database, device_id: String) -> Result<Option<HeatingModel>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT device_id, intercept, slope, mean_outdoor_temperature, observations, updated_at FROM heating_models WHERE device_id = :device_id", params! {
+        "device_id" => device_id
+    })?;
+
+    Ok(fetch_result.first().map(row_to_model))
+}
+
+/**
+Get the learned models of all scheduled Zones of a User
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: The models
+*/
+pub fn get_models(db: Database, user_id: String) -> Result<Vec<HeatingModel>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT heating_models.device_id, intercept, slope, mean_outdoor_temperature, observations, updated_at FROM heating_models \
+        INNER JOIN schedules ON schedules.device_id = heating_models.device_id WHERE schedules.user_id = :user_id", params! {
+        "user_id" => user_id
+    })?;
+
+    Ok(fetch_result.iter().map(row_to_model).collect())
+}
+
+/**
+Create or replace the learned model of a Zone
+
+## Parameters
+    db: An instance of Database
+    model: The model
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_model(db: Database, model: &HeatingModel) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO heating_models (device_id, intercept, slope, mean_outdoor_temperature, observations, updated_at) \
+        VALUES (:device_id, :intercept, :slope, :mean_outdoor_temperature, :observations, :updated_at) \
+        ON DUPLICATE KEY UPDATE intercept = :intercept, slope = :slope, mean_outdoor_temperature = :mean_outdoor_temperature, observations = :observations, updated_at = :updated_at", params! {
+        "device_id" => model.device_id.clone(),
+        "intercept" => model.intercept,
+        "slope" => model.slope,
+        "mean_outdoor_temperature" => model.mean_outdoor_temperature,
+        "observations" => model.observations,
+        "updated_at" => model.updated_at
+    })?;
+
+    Ok(())
+}
+
+fn row_to_model(row: &Row) -> HeatingModel {
+    HeatingModel {
+        device_id: row.get::<String, &str>("device_id").unwrap(),
+        intercept: row.get::<f32, &str>("intercept").unwrap(),
+        slope: row.get::<f32, &str>("slope").unwrap(),
+        mean_outdoor_temperature: row.get::<Option<f32>, &str>("mean_outdoor_temperature").unwrap(),
+        observations: row.get::<usize, &str>("observations").unwrap(),
+        updated_at: row.get::<i64, &str>("updated_at").unwrap()
+    }
+}
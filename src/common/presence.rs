@@ -0,0 +1,227 @@
+use crate::appdata::AppData;
+use crate::database::Database;
+use crate::types::event::Event;
+use crate::types::honeywell::SystemMode;
+use crate::types::presence::{AwayMode, Presence, PresenceSource};
+
+use mysql::{Error, Params, params, Row};
+use mysql::prelude::Queryable;
+use std::collections::HashMap;
+
+/**
+Check if anyone of the household of a User is home
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    None: If the presence of nobody is known
+    Some: If anyone is home
+*/
+pub fn is_anyone_home(db: Database, user_id: String) -> Result<Option<bool>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT home FROM presence WHERE user_id = :user_id", params! {
+        "user_id" => user_id
+    })?;
+
+    if fetch_result.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(fetch_result.iter().any(|row| row.get::<bool, &str>("home").unwrap())))
+}
+
+/**
+Get the presence of everyone in the household of a User, ordered by name
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: The presence of every person
+*/
+pub fn get_presence(db: Database, user_id: String) -> Result<Vec<Presence>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT person, home, source, updated_at FROM presence WHERE user_id = :user_id ORDER BY person", params! {
+        "user_id" => user_id
+    })?;
+
+    let presence = fetch_result.iter()
+        .map(|row| Presence {
+            person: row.get::<String, &str>("person").unwrap(),
+            home: row.get::<bool, &str>("home").unwrap(),
+            source: match row.get::<String, &str>("source").unwrap().as_str() {
+                "MANUAL" => PresenceSource::MANUAL,
+                _ => PresenceSource::GEOFENCE
+            },
+            updated_at: row.get::<i64, &str>("updated_at").unwrap()
+        })
+        .collect();
+
+    Ok(presence)
+}
+
+/**
+Set whether a person of the household of a User is home. The person is added if they're not known yet
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+    person: The name of the person
+    home: If the person is home
+    source: Where the update came from
+
+## Returns
+    Err: If an error occurred
+    Ok: If the presence of the person changed
+*/
+pub fn set_presence(db: Database, user_id: String, person: String, home: bool, source: PresenceSource) -> Result<bool, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT home FROM presence WHERE user_id = :user_id AND person = :person", params! {
+        "user_id" => user_id.clone(),
+        "person" => person.clone()
+    })?;
+
+    let changed = fetch_result.first().map(|row| row.get::<bool, &str>("home").unwrap() != home).unwrap_or(true);
+
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO presence (user_id, person, home, source, updated_at) VALUES (:user_id, :person, :home, :source, :updated_at) \
+        ON DUPLICATE KEY UPDATE home = :home, source = :source, updated_at = :updated_at", params! {
+        "user_id" => user_id,
+        "person" => person,
+        "home" => home,
+        "source" => format!("{:?}", source),
+        "updated_at" => chrono::Utc::now().timestamp()
+    })?;
+
+    Ok(changed)
+}
+
+/**
+Update whether a person is home, and let the rest of the application know if that changed
+
+## Parameters
+    data: An instance of AppData
+    user_id: The ID of the User
+    person: The name of the person
+    home: If the person is home
+    source: Where the update came from
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn update_presence(data: &AppData, user_id: String, person: String, home: bool, source: PresenceSource) -> Result<(), ()> {
+    let changed = set_presence(data.database.clone(), user_id.clone(), person.clone(), home, source);
+    if changed.is_err() {
+        eprintln!("An error occurred: {:?}", changed.err());
+        return Err(());
+    }
+
+    if changed.unwrap() {
+        data.events.publish(Event::PRESENCECHANGED { user_id, person, home });
+    }
+
+    Ok(())
+}
+
+/**
+Remove a person from the household of a User
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+    person: The name of the person
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn delete_person(db: Database, user_id: String, person: String) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM presence WHERE user_id = :user_id AND person = :person", params! {
+        "user_id" => user_id,
+        "person" => person
+    })?;
+
+    Ok(())
+}
+
+/**
+Get the away mode of a User
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    None: If the User has not configured away mode
+    Some: The away mode
+*/
+pub fn get_away_mode(db: Database, user_id: String) -> Result<Option<AwayMode>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT enabled, actions, away, previous_modes FROM away_mode WHERE user_id = :user_id", params! {
+        "user_id" => user_id
+    })?;
+
+    let away_mode = fetch_result.first().map(|row| AwayMode {
+        enabled: row.get::<bool, &str>("enabled").unwrap(),
+        actions: serde_json::from_str(&row.get::<String, &str>("actions").unwrap()).unwrap_or_default(),
+        away: row.get::<bool, &str>("away").unwrap(),
+        previous_modes: serde_json::from_str(&row.get::<String, &str>("previous_modes").unwrap()).unwrap_or_default()
+    });
+
+    Ok(away_mode)
+}
+
+/**
+Configure the away mode of a User. Whether the home is currently away is left as is
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+    away_mode: The away mode
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_away_mode(db: Database, user_id: String, away_mode: &AwayMode) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO away_mode (user_id, enabled, actions, away, previous_modes) VALUES (:user_id, :enabled, :actions, FALSE, '{}') \
+        ON DUPLICATE KEY UPDATE enabled = :enabled, actions = :actions", params! {
+        "user_id" => user_id,
+        "enabled" => away_mode.enabled,
+        "actions" => serde_json::to_string(&away_mode.actions).unwrap()
+    })?;
+
+    Ok(())
+}
+
+/**
+Set whether the home of a User is currently in away mode
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+    away: If the home is away
+    previous_modes: Per Honeywell Service, the mode to restore when someone returns
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_away(db: Database, user_id: String, away: bool, previous_modes: &HashMap<String, SystemMode>) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("UPDATE away_mode SET away = :away, previous_modes = :previous_modes WHERE user_id = :user_id", params! {
+        "user_id" => user_id,
+        "away" => away,
+        "previous_modes" => serde_json::to_string(previous_modes).unwrap()
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use crate::database::Database;
+use crate::types::room::{Floor, Room};
+
+use mysql::{Error, Params, params, Row};
+use mysql::prelude::Queryable;
+
+/**
+Get all floors of a User, ordered by level
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: The floors
+*/
+pub fn get_floors(db: Database, user_id: String) -> Result<Vec<Floor>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT floor_id, name, level FROM floors WHERE user_id = :user_id ORDER BY level", params! {
+        "user_id" => user_id
+    })?;
+
+    let floors = fetch_result.iter()
+        .map(|row| Floor {
+            floor_id: row.get::<String, &str>("floor_id").unwrap(),
+            name: row.get::<String, &str>("name").unwrap(),
+            level: row.get::<i32, &str>("level").unwrap()
+        })
+        .collect();
+
+    Ok(floors)
+}
+
+/**
+Get all rooms of a User, ordered by name
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: The rooms
+*/
+pub fn get_rooms(db: Database, user_id: String) -> Result<Vec<Room>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT room_id, name, floor_id FROM rooms WHERE user_id = :user_id ORDER BY name", params! {
+        "user_id" => user_id
+    })?;
+
+    let rooms = fetch_result.iter()
+        .map(|row| Room {
+            room_id: row.get::<String, &str>("room_id").unwrap(),
+            name: row.get::<String, &str>("name").unwrap(),
+            floor_id: row.get::<Option<String>, &str>("floor_id").unwrap()
+        })
+        .collect();
+
+    Ok(rooms)
+}
+
+/**
+Create or update a floor
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User owning the floor
+    floor: The floor
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_floor(db: Database, user_id: String, floor: &Floor) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO floors (floor_id, user_id, name, level) VALUES (:floor_id, :user_id, :name, :level) \
+        ON DUPLICATE KEY UPDATE name = :name, level = :level", params! {
+        "floor_id" => floor.floor_id.clone(),
+        "user_id" => user_id,
+        "name" => floor.name.clone(),
+        "level" => floor.level
+    })?;
+
+    Ok(())
+}
+
+/**
+Create or update a room
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User owning the room
+    room: The room
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_room(db: Database, user_id: String, room: &Room) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO rooms (room_id, user_id, name, floor_id) VALUES (:room_id, :user_id, :name, :floor_id) \
+        ON DUPLICATE KEY UPDATE name = :name, floor_id = :floor_id", params! {
+        "room_id" => room.room_id.clone(),
+        "user_id" => user_id,
+        "name" => room.name.clone(),
+        "floor_id" => room.floor_id.clone()
+    })?;
+
+    Ok(())
+}
+
+/**
+Remove a floor. Rooms on the floor are kept, but no longer belong to a floor
+
+## Parameters
+    db: An instance of Database
+    floor_id: The ID of the floor
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn delete_floor(db: Database, floor_id: String) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("UPDATE rooms SET floor_id = NULL WHERE floor_id = :floor_id", params! {
+        "floor_id" => floor_id.clone()
+    })?;
+
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM floors WHERE floor_id = :floor_id", params! {
+        "floor_id" => floor_id
+    })?;
+
+    Ok(())
+}
+
+/**
+Remove a room. Devices in the room are kept, but no longer belong to a room
+
+## Parameters
+    db: An instance of Database
+    room_id: The ID of the room
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn delete_room(db: Database, room_id: String) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("UPDATE devices SET room_id = NULL WHERE room_id = :room_id", params! {
+        "room_id" => room_id.clone()
+    })?;
+
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM rooms WHERE room_id = :room_id", params! {
+        "room_id" => room_id
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,259 @@
+use crate::appdata::AppData;
+use crate::database::Database;
+use crate::services::UserDevice;
+use crate::types::rule::{Action, Condition, Rule};
+use crate::types::schedule::Day;
+use crate::types::event::Event;
+
+use chrono::Datelike;
+use mysql::{Error, Params, params, Row, Transaction};
+use mysql::prelude::Queryable;
+
+/**
+Get all Rules of a User, ordered by name
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: The Rules
+*/
+pub fn get_rules(db: Database, user_id: String) -> Result<Vec<Rule>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT rule_id, name, enabled, triggers, conditions, actions FROM rules WHERE user_id = :user_id ORDER BY name", params! {
+        "user_id" => user_id
+    })?;
+
+    Ok(fetch_result.iter().map(row_to_rule).collect())
+}
+
+/**
+Get all enabled Rules, of all Users
+
+## Parameters
+    db: An instance of Database
+
+## Returns
+    Err: If an error occurred
+    Ok: The Rules, along with the ID of the User owning them
+*/
+pub fn get_enabled_rules(db: Database) -> Result<Vec<(String, Rule)>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.query::<Row, &str>("SELECT rule_id, user_id, name, enabled, triggers, conditions, actions FROM rules WHERE enabled = TRUE")?;
+
+    let rules = fetch_result.iter()
+        .map(|row| (row.get::<String, &str>("user_id").unwrap(), row_to_rule(row)))
+        .collect();
+
+    Ok(rules)
+}
+
+/**
+Create or update a Rule
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User owning the Rule
+    rule: The Rule
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_rule(db: Database, user_id: String, rule: &Rule) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO rules (rule_id, user_id, name, enabled, triggers, conditions, actions) VALUES (:rule_id, :user_id, :name, :enabled, :triggers, :conditions, :actions) \
+        ON DUPLICATE KEY UPDATE name = :name, enabled = :enabled, triggers = :triggers, conditions = :conditions, actions = :actions", params! {
+        "rule_id" => rule.rule_id.clone(),
+        "user_id" => user_id,
+        "name" => rule.name.clone(),
+        "enabled" => rule.enabled,
+        "triggers" => serde_json::to_string(&rule.triggers).unwrap(),
+        "conditions" => serde_json::to_string(&rule.conditions).unwrap(),
+        "actions" => serde_json::to_string(&rule.actions).unwrap()
+    })?;
+
+    Ok(())
+}
+
+/**
+Remove a Rule
+
+## Parameters
+    db: An instance of Database
+    rule_id: The ID of the Rule
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn delete_rule(db: Database, rule_id: String) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM rules WHERE rule_id = :rule_id", params! {
+        "rule_id" => rule_id
+    })?;
+
+    Ok(())
+}
+
+/**
+Remove the Rules of a User which refer to Devices that are removed. Used when a Service is removed
+
+## Parameters
+    tx: The transaction removing the Service
+    user_id: The ID of the User
+    device_ids: The IDs of the removed Devices
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn delete_device_rules(tx: &mut Transaction, user_id: &str, device_ids: &[String]) -> Result<(), Error> {
+    let fetch_result = tx.exec::<Row, &str, Params>("SELECT rule_id, name, enabled, triggers, conditions, actions FROM rules WHERE user_id = :user_id", params! {
+        "user_id" => user_id
+    })?;
+
+    for rule in fetch_result.iter().map(row_to_rule).filter(|rule| rule.uses_device(device_ids)) {
+        let _ = tx.exec::<usize, &str, Params>("DELETE FROM rules WHERE rule_id = :rule_id", params! {
+            "rule_id" => rule.rule_id
+        })?;
+    }
+
+    Ok(())
+}
+
+/**
+Run a Rule of which a trigger fired: check its conditions, and execute its actions if they all hold.
+Actions are executed in order, a failing action does not stop the actions after it
+
+## Parameters
+    data: An instance of AppData
+    user_id: The ID of the User owning the Rule
+    rule: The Rule
+    devices: The current Devices of the User, if already known. Fetched when a condition needs them
+
+## Returns
+    Err: If the conditions could not be checked
+    Ok: If the actions were executed
+*/
+pub fn run(data: &AppData, user_id: String, rule: &Rule, devices: Option<&[UserDevice]>) -> Result<bool, ()> {
+    let needs_devices = rule.conditions.iter().any(|condition| matches!(condition, Condition::DEVICESTATE { .. }));
+    let fetched = if needs_devices && devices.is_none() {
+        Some(crate::services::get_user_devices(data, user_id.clone())?)
+    } else {
+        None
+    };
+
+    let devices = devices.or(fetched.as_deref()).unwrap_or(&[]);
+    for condition in &rule.conditions {
+        if !check_condition(data, user_id.clone(), condition, devices)? {
+            data.events.publish(Event::RULEFIRED { user_id: user_id.clone(), rule_id: rule.rule_id.clone(), name: rule.name.clone(), executed: false });
+            return Ok(false);
+        }
+    }
+
+    data.events.publish(Event::RULEFIRED { user_id: user_id.clone(), rule_id: rule.rule_id.clone(), name: rule.name.clone(), executed: true });
+
+    for action in &rule.actions {
+        if execute_action(data, user_id.clone(), action).is_err() {
+            eprintln!("Action {:?} of Rule '{}' failed", action, rule.rule_id);
+        }
+    }
+
+    Ok(true)
+}
+
+fn check_condition(data: &AppData, user_id: String, condition: &Condition, devices: &[UserDevice]) -> Result<bool, ()> {
+    match condition {
+        Condition::PRESENCE { anyone_home } => {
+            let presence = crate::common::presence::is_anyone_home(data.database.clone(), user_id);
+            if presence.is_err() {
+                eprintln!("An error occurred: {:?}", presence.err());
+                return Err(());
+            }
+
+            //Without any known presence the condition can't hold either way
+            Ok(presence.unwrap() == Some(*anyone_home))
+        },
+        Condition::PERSONHOME { person, home } => {
+            let presence = crate::common::presence::get_presence(data.database.clone(), user_id);
+            if presence.is_err() {
+                eprintln!("An error occurred: {:?}", presence.err());
+                return Err(());
+            }
+
+            //An unknown person is neither home nor away
+            Ok(presence.unwrap().iter().any(|presence| presence.person == *person && presence.home == *home))
+        },
+        Condition::DAYOFWEEK { days } => Ok(days.contains(&Day::from_weekday(chrono::Local::now().weekday()))),
+        Condition::DEVICESTATE { device_id, on, property, above, below } => {
+            let device = crate::services::find_device(devices, device_id);
+            if device.is_none() {
+                return Ok(false);
+            }
+
+            let state = &device.unwrap().device.state;
+            let on_matches = on.map(|on| state.on == Some(on)).unwrap_or(true);
+            let property_matches = property.map(|property| property.in_range(state, *above, *below)).unwrap_or(true);
+            Ok(on_matches && property_matches)
+        }
+    }
+}
+
+fn execute_action(data: &AppData, user_id: String, action: &Action) -> Result<(), ()> {
+    match action {
+        Action::SETDEVICE { device_id, command } => crate::services::execute_registered(data, device_id.clone(), command.clone()),
+        Action::ACTIVATESCENE { scene_id } => {
+            let scenes = crate::common::scene::get_scenes(data.database.clone(), user_id.clone());
+            if scenes.is_err() {
+                eprintln!("An error occurred: {:?}", scenes.err());
+                return Err(());
+            }
+
+            let scenes = scenes.unwrap();
+            let scene = scenes.iter().find(|scene| scene.scene_id == *scene_id);
+            if scene.is_none() {
+                return Err(());
+            }
+
+            let results = crate::common::scene::activate(data, user_id, scene.unwrap())?;
+            if results.iter().any(|result| !result.success) {
+                return Err(());
+            }
+
+            Ok(())
+        },
+        Action::NOTIFY { url, message } => {
+            let request = reqwest::blocking::Client::new().post(url).body(message.clone()).send();
+            if request.is_err() {
+                eprintln!("An error occurred: {:?}", request.err());
+                return Err(());
+            }
+
+            let response = request.unwrap();
+            if !response.status().is_success() {
+                eprintln!("Notification to '{}' returned {}", url, response.status());
+                return Err(());
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn row_to_rule(row: &Row) -> Rule {
+    let triggers = row.get::<String, &str>("triggers").unwrap();
+    let conditions = row.get::<String, &str>("conditions").unwrap();
+    let actions = row.get::<String, &str>("actions").unwrap();
+
+    Rule {
+        rule_id: row.get::<String, &str>("rule_id").unwrap(),
+        name: row.get::<String, &str>("name").unwrap(),
+        enabled: row.get::<bool, &str>("enabled").unwrap(),
+        triggers: serde_json::from_str(&triggers).unwrap_or_default(),
+        conditions: serde_json::from_str(&conditions).unwrap_or_default(),
+        actions: serde_json::from_str(&actions).unwrap_or_default()
+    }
+}
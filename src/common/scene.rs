@@ -0,0 +1,194 @@
+use crate::appdata::AppData;
+use crate::database::Database;
+use crate::types::scene::{Scene, SceneAction, SceneActionResult};
+use crate::types::device::DeviceCommand;
+
+use mysql::{Error, Params, params, Row, Transaction};
+use mysql::prelude::Queryable;
+use std::thread;
+
+/**
+Get all Scenes of a User, ordered by name
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: The Scenes
+*/
+pub fn get_scenes(db: Database, user_id: String) -> Result<Vec<Scene>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT scene_id, name, actions FROM scenes WHERE user_id = :user_id ORDER BY name", params! {
+        "user_id" => user_id
+    })?;
+
+    Ok(fetch_result.iter().map(row_to_scene).collect())
+}
+
+/**
+Create or update a Scene
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User owning the Scene
+    scene: The Scene
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_scene(db: Database, user_id: String, scene: &Scene) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO scenes (scene_id, user_id, name, actions) VALUES (:scene_id, :user_id, :name, :actions) \
+        ON DUPLICATE KEY UPDATE name = :name, actions = :actions", params! {
+        "scene_id" => scene.scene_id.clone(),
+        "user_id" => user_id,
+        "name" => scene.name.clone(),
+        "actions" => serde_json::to_string(&scene.actions).unwrap()
+    })?;
+
+    Ok(())
+}
+
+/**
+Remove a Scene
+
+## Parameters
+    db: An instance of Database
+    scene_id: The ID of the Scene
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn delete_scene(db: Database, scene_id: String) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM scenes WHERE scene_id = :scene_id", params! {
+        "scene_id" => scene_id
+    })?;
+
+    Ok(())
+}
+
+/**
+Remove the actions for Devices that are removed from the Scenes of a User. Used when a Service is removed.
+A Scene left without actions is removed
+
+## Parameters
+    tx: The transaction removing the Service
+    user_id: The ID of the User
+    device_ids: The IDs of the removed Devices
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn delete_device_actions(tx: &mut Transaction, user_id: &str, device_ids: &[String]) -> Result<(), Error> {
+    let fetch_result = tx.exec::<Row, &str, Params>("SELECT scene_id, name, actions FROM scenes WHERE user_id = :user_id", params! {
+        "user_id" => user_id
+    })?;
+
+    for mut scene in fetch_result.iter().map(row_to_scene) {
+        let action_count = scene.actions.len();
+        scene.actions.retain(|action| !device_ids.contains(&action.device_id));
+
+        if scene.actions.len() == action_count {
+            continue;
+        }
+
+        if scene.actions.is_empty() {
+            let _ = tx.exec::<usize, &str, Params>("DELETE FROM scenes WHERE scene_id = :scene_id", params! {
+                "scene_id" => scene.scene_id
+            })?;
+        } else {
+            let _ = tx.exec::<usize, &str, Params>("UPDATE scenes SET actions = :actions WHERE scene_id = :scene_id", params! {
+                "scene_id" => scene.scene_id.clone(),
+                "actions" => serde_json::to_string(&scene.actions).unwrap()
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/**
+Activate a Scene. Devices are set in parallel, the actions of a single Device are executed in order
+
+## Parameters
+    data: An instance of AppData
+    user_id: The ID of the User owning the Scene
+    scene: The Scene to activate
+
+## Returns
+    Err: If the Devices or Services of the User could not be fetched
+    Ok: The result per Device
+*/
+pub fn activate(data: &AppData, user_id: String, scene: &Scene) -> Result<Vec<SceneActionResult>, ()> {
+    let registered = crate::common::device::get_devices(data.database.clone(), user_id.clone());
+    let services = crate::common::service::get_services(data.database.clone(), user_id);
+    if registered.is_err() || services.is_err() {
+        eprintln!("An error occurred: {:?} {:?}", registered.err(), services.err());
+        return Err(());
+    }
+
+    let (registered, services) = (registered.unwrap(), services.unwrap());
+
+    //Group the actions per Device, keeping their order
+    let mut per_device: Vec<(String, Vec<DeviceCommand>)> = vec![];
+    for SceneAction { device_id, command } in &scene.actions {
+        match per_device.iter_mut().find(|(id, _)| id == device_id) {
+            Some((_, commands)) => commands.push(command.clone()),
+            None => per_device.push((device_id.clone(), vec![command.clone()]))
+        }
+    }
+
+    let results = thread::scope(|scope| {
+        let handles: Vec<_> = per_device.iter()
+            .map(|(device_id, commands)| {
+                let registered = &registered;
+                let services = &services;
+
+                scope.spawn(move || {
+                    let device = registered.iter().find(|device| device.device_id == *device_id);
+                    let service = device.and_then(|device| services.iter().find(|(service_id, _)| *service_id == device.service_id));
+                    if device.is_none() || service.is_none() {
+                        return SceneActionResult { device_id: device_id.clone(), success: false, error: Some("Device not found".to_string()) };
+                    }
+
+                    let (device, (service_id, service_type)) = (device.unwrap(), service.unwrap());
+                    for command in commands {
+                        if !command.is_supported(&device.capabilities) {
+                            return SceneActionResult { device_id: device_id.clone(), success: false, error: Some(format!("{:?} is not supported by the Device", command)) };
+                        }
+
+                        let result = crate::services::execute(data, service_id.clone(), service_type.clone(), device.provider_device_id.clone(), command.clone());
+                        if result.is_err() {
+                            return SceneActionResult { device_id: device_id.clone(), success: false, error: Some(format!("Unable to execute {:?}", command)) };
+                        }
+                    }
+
+                    SceneActionResult { device_id: device_id.clone(), success: true, error: None }
+                })
+            })
+            .collect();
+
+        handles.into_iter()
+            .zip(per_device.iter())
+            .map(|(handle, (device_id, _))| handle.join().unwrap_or_else(|_| SceneActionResult { device_id: device_id.clone(), success: false, error: Some("Internal error".to_string()) }))
+            .collect()
+    });
+
+    Ok(results)
+}
+
+fn row_to_scene(row: &Row) -> Scene {
+    let actions = row.get::<String, &str>("actions").unwrap();
+
+    Scene {
+        scene_id: row.get::<String, &str>("scene_id").unwrap(),
+        name: row.get::<String, &str>("name").unwrap(),
+        actions: serde_json::from_str(&actions).unwrap_or_default()
+    }
+}
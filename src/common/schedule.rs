@@ -0,0 +1,126 @@
+use crate::database::Database;
+use crate::types::schedule::Schedule;
+
+use mysql::{Error, Params, params, Row};
+use mysql::prelude::Queryable;
+
+/**
+Get the heating schedules of all Devices of a User
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: The schedules
+*/
+pub fn get_schedules(db: Database, user_id: String) -> Result<Vec<Schedule>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT device_id, enabled, days, exceptions FROM schedules WHERE user_id = :user_id", params! {
+        "user_id" => user_id
+    })?;
+
+    Ok(fetch_result.iter().map(row_to_schedule).collect())
+}
+
+/**
+Get all enabled heating schedules, of all Users
+
+## Parameters
+    db: An instance of Database
+
+## Returns
+    Err: If an error occurred
+    Ok: The schedules, along with the ID of the User owning the Device and the Unix timestamp at which a setpoint was last applied for them
+*/
+pub fn get_enabled_schedules(db: Database) -> Result<Vec<(String, Schedule, i64)>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.query::<Row, &str>("SELECT device_id, user_id, enabled, days, exceptions, last_applied FROM schedules WHERE enabled = TRUE")?;
+
+    let schedules = fetch_result.iter()
+        .map(|row| (row.get::<String, &str>("user_id").unwrap(), row_to_schedule(row), row.get::<i64, &str>("last_applied").unwrap()))
+        .collect();
+
+    Ok(schedules)
+}
+
+/**
+Create or replace the heating schedule of a Device. The active switchpoint is applied again on the next evaluation
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User owning the Device
+    schedule: The schedule
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_schedule(db: Database, user_id: String, schedule: &Schedule) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO schedules (device_id, user_id, enabled, days, exceptions, last_applied) VALUES (:device_id, :user_id, :enabled, :days, :exceptions, 0) \
+        ON DUPLICATE KEY UPDATE enabled = :enabled, days = :days, exceptions = :exceptions, last_applied = 0", params! {
+        "device_id" => schedule.device_id.clone(),
+        "user_id" => user_id,
+        "enabled" => schedule.enabled,
+        "days" => serde_json::to_string(&schedule.days).unwrap(),
+        "exceptions" => serde_json::to_string(&schedule.exceptions).unwrap()
+    })?;
+
+    Ok(())
+}
+
+/**
+Remove the heating schedule of a Device
+
+## Parameters
+    db: An instance of Database
+    device_id: The internal ID of the Device
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn delete_schedule(db: Database, device_id: String) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM schedules WHERE device_id = :device_id", params! {
+        "device_id" => device_id
+    })?;
+
+    Ok(())
+}
+
+/**
+Record that the active setpoint of a schedule was applied
+
+## Parameters
+    db: An instance of Database
+    device_id: The internal ID of the Device
+    timestamp: Unix timestamp at which the setpoint was applied
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_applied(db: Database, device_id: String, timestamp: i64) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("UPDATE schedules SET last_applied = :last_applied WHERE device_id = :device_id", params! {
+        "device_id" => device_id,
+        "last_applied" => timestamp
+    })?;
+
+    Ok(())
+}
+
+fn row_to_schedule(row: &Row) -> Schedule {
+    let days = row.get::<String, &str>("days").unwrap();
+    let exceptions = row.get::<String, &str>("exceptions").unwrap();
+
+    Schedule {
+        device_id: row.get::<String, &str>("device_id").unwrap(),
+        enabled: row.get::<bool, &str>("enabled").unwrap(),
+        days: serde_json::from_str(&days).unwrap_or_default(),
+        exceptions: serde_json::from_str(&exceptions).unwrap_or_default()
+    }
+}
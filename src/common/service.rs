@@ -1,12 +1,14 @@
 use crate::environment::Environment;
 use crate::database::Database;
-use crate::types::service::ServiceType;
+use crate::types::service::{ServiceType, ServiceAppearance};
 
 use std::str::FromStr;
-use mysql::{Error, Params, params, Row};
+use mysql::{Error, Params, params, Row, TxOpts};
 use mysql::prelude::Queryable;
 use magic_crypt::MagicCryptTrait;
 use magic_crypt::MagicCrypt256;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 
 #[derive(Clone)]
 pub struct Credentials {
@@ -14,6 +16,13 @@ pub struct Credentials {
     pub password: String
 }
 
+#[derive(Clone)]
+pub struct TokenCredentials {
+    /// The base URL of the API the token is valid for, e.g. 'http://homeassistant.local:8123'
+    pub host:   String,
+    pub token:  String
+}
+
 /**
 Set a username/password for a Service
 
@@ -93,6 +102,133 @@ pub fn get_password_credentials(db: Database, service_id: String) -> Result<Opti
     }))
 }
 
+/**
+Set the host/token credentials for a Service
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service to set the credentials for
+    credentials: A TokenCredentials object containing the host and token
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_token_credentials(db: Database, service_id: String, credentials: TokenCredentials) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+
+    let env = Environment::new();
+    let mc: MagicCrypt256 = new_magic_crypt!(env.password_pepper, 256);
+
+    //Only the token is secret, the host is stored as-is
+    let token_encrypted_base64 = mc.encrypt_str_to_base64(credentials.token);
+
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO services_token_credentials (service_id, host, token) VALUES (:service_id, :host, :token)", params! {
+        "service_id" => service_id,
+        "host" => credentials.host,
+        "token" => token_encrypted_base64
+    })?;
+
+    Ok(())
+}
+
+/**
+Get the host/token credentials for a Service
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service to get the credentials for
+
+## Returns
+    Err: If an error occurred
+    Ok:
+        Some: An instance of TokenCredentials containing the requested credentials
+        None: The Service with the provided ID does not have host/token credentials
+*/
+pub fn get_token_credentials(db: Database, service_id: String) -> Result<Option<TokenCredentials>, Error> {
+    let mut conn = db.pool.get_conn()?;
+
+    let env = Environment::new();
+    let mc: MagicCrypt256 = new_magic_crypt!(env.password_pepper, 256);
+
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT host, token FROM services_token_credentials WHERE service_id = :service_id", params! {
+        "service_id" => service_id
+    })?;
+
+    if fetch_result.is_empty() {
+        return Ok(None);
+    }
+
+    let first_row = fetch_result.first().unwrap();
+    let host = first_row.get::<String, &str>("host").unwrap();
+    let token_encrypted = first_row.get::<String, &str>("token").unwrap();
+
+    let token_decrypted = mc.decrypt_base64_to_string(&token_encrypted).unwrap();
+
+    Ok(Some(TokenCredentials {
+        host,
+        token: token_decrypted
+    }))
+}
+
+/**
+Store the Service specific settings for a Service, e.g. which entities to import.
+Existing settings are overwritten
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service to store the settings for
+    settings: The settings, these are stored as JSON
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_settings<T: Serialize>(db: Database, service_id: String, settings: &T) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let settings_json = serde_json::to_string(settings).unwrap();
+
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO services_settings (service_id, settings) VALUES (:service_id, :settings) ON DUPLICATE KEY UPDATE settings = :settings", params! {
+        "service_id" => service_id,
+        "settings" => settings_json
+    })?;
+
+    Ok(())
+}
+
+/**
+Get the Service specific settings for a Service
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service to get the settings for
+
+## Returns
+    Err: If an error occurred
+    Ok:
+        Some: The settings of the Service
+        None: The Service has no settings, or the stored settings are not valid for T
+*/
+pub fn get_settings<T: DeserializeOwned>(db: Database, service_id: String) -> Result<Option<T>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT settings FROM services_settings WHERE service_id = :service_id", params! {
+        "service_id" => service_id.clone()
+    })?;
+
+    if fetch_result.is_empty() {
+        return Ok(None);
+    }
+
+    let settings_json = fetch_result.first().unwrap().get::<String, &str>("settings").unwrap();
+    let settings = serde_json::from_str::<T>(&settings_json);
+    if settings.is_err() {
+        eprintln!("Stored settings for Service '{}' are invalid: {:?}", service_id, settings.err());
+        return Ok(None);
+    }
+
+    Ok(Some(settings.unwrap()))
+}
+
 /**
 Create a Service
 
@@ -117,6 +253,53 @@ pub fn create_service(db: Database, user_id: String, service_id: String, service
     Ok(())
 }
 
+/**
+Remove a Service, along with everything stored for it and its Devices. Rules referring to its Devices are removed,
+as are the actions for its Devices in Scenes, and a vacation no longer holds them. Everything is removed in a single transaction
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+
+## Returns
+    Err: If an error occurred
+    Ok: The IDs of the removed Devices
+*/
+pub fn delete_service(db: Database, service_id: String) -> Result<Vec<String>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let mut tx = conn.start_transaction(TxOpts::default())?;
+
+    let user_id = tx.exec_first::<String, &str, Params>("SELECT user_id FROM services WHERE service_id = :service_id", params! {
+        "service_id" => service_id.clone()
+    })?;
+
+    let device_ids = tx.exec::<String, &str, Params>("SELECT device_id FROM devices WHERE service_id = :service_id", params! {
+        "service_id" => service_id.clone()
+    })?;
+
+    for table in ["schedules", "device_alerts", "device_history", "device_history_hourly", "device_history_daily", "heating_observations", "heating_models", "open_window_detection", "open_windows"].iter() {
+        let _ = tx.exec::<usize, String, Params>(format!("DELETE FROM {} WHERE device_id IN (SELECT device_id FROM devices WHERE service_id = :service_id)", table), params! {
+            "service_id" => service_id.clone()
+        })?;
+    }
+
+    if let Some(user_id) = &user_id {
+        crate::common::rule::delete_device_rules(&mut tx, user_id, &device_ids)?;
+        crate::common::scene::delete_device_actions(&mut tx, user_id, &device_ids)?;
+        crate::common::vacation::remove_service(&mut tx, user_id, &service_id, &device_ids)?;
+    }
+
+    for table in ["devices", "services_password_credentials", "services_token_credentials", "services_settings", "services_oauth_tokens", "services_appearance", "services_exposure", "services"].iter() {
+        let _ = tx.exec::<usize, String, Params>(format!("DELETE FROM {} WHERE service_id = :service_id", table), params! {
+            "service_id" => service_id.clone()
+        })?;
+    }
+
+    tx.commit()?;
+
+    Ok(device_ids)
+}
+
 /**
 Get all Services owned by a specific User
 
@@ -146,4 +329,180 @@ pub fn get_services(db: Database, user_id: String) -> Result<Vec<(String, Servic
     }
 
     Ok(result)
-}
\ No newline at end of file
+}
+
+/**
+Get all Services of a specific type, of all Users
+
+## Parameters
+    db: An instance of Database
+    service_type: The type of Service to fetch
+
+## Returns
+    Err: If an error occurred
+    Ok: Returns a Vector containing the service_id of every Service of the requested type
+*/
+pub fn get_services_of_type(db: Database, service_type: ServiceType) -> Result<Vec<String>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let get_services_query = conn.exec::<Row, &str, Params>("SELECT service_id FROM services WHERE identifier = :identifier", params! {
+        "identifier" => service_type.to_string()
+    })?;
+
+    let result = get_services_query.iter()
+        .map(|row| row.get::<String, &str>("service_id").unwrap())
+        .collect();
+
+    Ok(result)
+}
+
+/**
+Get all Users which have at least one Service
+
+## Parameters
+    db: An instance of Database
+
+## Returns
+    Err: If an error occurred
+    Ok: The ID of every User with a Service
+*/
+pub fn get_users_with_services(db: Database) -> Result<Vec<String>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let get_users_query = conn.query::<Row, &str>("SELECT DISTINCT user_id FROM services")?;
+
+    let result = get_users_query.iter()
+        .map(|row| row.get::<String, &str>("user_id").unwrap())
+        .collect();
+
+    Ok(result)
+}
+
+/**
+Set how a Service is presented to its owner. An existing appearance is overwritten
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+    appearance: The label, icon and colour of the Service
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_appearance(db: Database, service_id: String, appearance: &ServiceAppearance) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO services_appearance (service_id, label, icon, colour) VALUES (:service_id, :label, :icon, :colour) \
+        ON DUPLICATE KEY UPDATE label = :label, icon = :icon, colour = :colour", params! {
+        "service_id" => service_id,
+        "label" => appearance.label.clone(),
+        "icon" => appearance.icon.clone(),
+        "colour" => appearance.colour.clone()
+    })?;
+
+    Ok(())
+}
+
+/**
+Get how a Service is presented to its owner
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+
+## Returns
+    Err: If an error occurred
+    Ok: The appearance of the Service. All fields are None if it was never set
+*/
+pub fn get_appearance(db: Database, service_id: String) -> Result<ServiceAppearance, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT label, icon, colour FROM services_appearance WHERE service_id = :service_id", params! {
+        "service_id" => service_id
+    })?;
+
+    if fetch_result.is_empty() {
+        return Ok(ServiceAppearance::default());
+    }
+
+    let row = fetch_result.first().unwrap();
+    Ok(ServiceAppearance {
+        label: row.get::<Option<String>, &str>("label").unwrap(),
+        icon: row.get::<Option<String>, &str>("icon").unwrap(),
+        colour: row.get::<Option<String>, &str>("colour").unwrap()
+    })
+}
+
+/**
+Get the owner and type of a Service
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+
+## Returns
+    Err: If an error occurred
+    Ok:
+        Some: (user_id, ServiceType)
+        None: The Service does not exist
+*/
+pub fn get_service(db: Database, service_id: String) -> Result<Option<(String, ServiceType)>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT user_id, identifier FROM services WHERE service_id = :service_id", params! {
+        "service_id" => service_id
+    })?;
+
+    if fetch_result.is_empty() {
+        return Ok(None);
+    }
+
+    let row = fetch_result.first().unwrap();
+    let user_id = row.get::<String, &str>("user_id").unwrap();
+    let identifier = row.get::<String, &str>("identifier").unwrap();
+
+    Ok(Some((user_id, ServiceType::from_str(&identifier).unwrap())))
+}
+
+/**
+Set if the Devices of a Service are exposed to Google Assistant. Devices of an exposed Service can still be hidden individually
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+    exposed: If the Devices of the Service should be exposed
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_exposed(db: Database, service_id: String, exposed: bool) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO services_exposure (service_id, exposed) VALUES (:service_id, :exposed) ON DUPLICATE KEY UPDATE exposed = :exposed", params! {
+        "service_id" => service_id,
+        "exposed" => exposed
+    })?;
+
+    Ok(())
+}
+
+/**
+Get the Services of a User of which the Devices are not exposed to Google Assistant
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: The service_id's of the hidden Services
+*/
+pub fn get_unexposed_services(db: Database, user_id: String) -> Result<Vec<String>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT services.service_id FROM services \
+        INNER JOIN services_exposure ON services_exposure.service_id = services.service_id WHERE services.user_id = :user_id AND services_exposure.exposed = FALSE", params! {
+        "user_id" => user_id
+    })?;
+
+    let result = fetch_result.iter()
+        .map(|row| row.get::<String, &str>("service_id").unwrap())
+        .collect();
+
+    Ok(result)
+}
@@ -0,0 +1,101 @@
+use chrono::NaiveDate;
+
+/// Julian date of 2000-01-01 12:00 UTC
+const J2000: f64 = 2451545.0;
+/// Julian date of the Unix epoch
+const UNIX_EPOCH_JULIAN: f64 = 2440587.5;
+/// Axial tilt of the earth in degrees
+const OBLIQUITY: f64 = 23.4397;
+
+/**
+Calculate sunrise and sunset on a date, using the sunrise equation. Accurate to about a minute
+
+## Parameters
+    date: The date, in UTC
+    latitude: Latitude of the location in degrees, north is positive
+    longitude: Longitude of the location in degrees, east is positive
+
+## Returns
+    None: If the sun does not rise or set on this date, i.e. during polar day or night
+    Some: The Unix timestamps of sunrise and sunset
+*/
+pub fn sun_times(date: NaiveDate, latitude: f64, longitude: f64) -> Option<(i64, i64)> {
+    let midnight = date.and_hms_opt(0, 0, 0)?.and_utc().timestamp() as f64;
+    let julian_date = midnight / 86400.0 + UNIX_EPOCH_JULIAN;
+
+    //Mean solar time
+    let day = (julian_date - J2000 + 0.0008).ceil();
+    let mean_solar_time = day - longitude / 360.0;
+
+    let mean_anomaly = (357.5291 + 0.98560028 * mean_solar_time).rem_euclid(360.0);
+    let center = 1.9148 * sin(mean_anomaly) + 0.02 * sin(2.0 * mean_anomaly) + 0.0003 * sin(3.0 * mean_anomaly);
+    let ecliptic_longitude = (mean_anomaly + center + 180.0 + 102.9372).rem_euclid(360.0);
+    let transit = J2000 + mean_solar_time + 0.0053 * sin(mean_anomaly) - 0.0069 * sin(2.0 * ecliptic_longitude);
+
+    let declination = (sin(ecliptic_longitude) * sin(OBLIQUITY)).asin().to_degrees();
+
+    //-0.833° accounts for refraction and the size of the sun
+    let cos_hour_angle = (sin(-0.833) - sin(latitude) * sin(declination)) / (cos(latitude) * cos(declination));
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+    let sunrise = transit - hour_angle / 360.0;
+    let sunset = transit + hour_angle / 360.0;
+
+    Some((to_timestamp(sunrise), to_timestamp(sunset)))
+}
+
+fn sin(degrees: f64) -> f64 {
+    degrees.to_radians().sin()
+}
+
+fn cos(degrees: f64) -> f64 {
+    degrees.to_radians().cos()
+}
+
+fn to_timestamp(julian_date: f64) -> i64 {
+    ((julian_date - UNIX_EPOCH_JULIAN) * 86400.0).round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sunrise and sunset tables are rounded to the minute, the sunrise equation is accurate to about a minute
+    const TOLERANCE_SECONDS: i64 = 180;
+
+    fn utc(date: &str, time: &str) -> i64 {
+        chrono::NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap().and_utc().timestamp()
+    }
+
+    fn date(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn sun_times_in_amsterdam() {
+        let (sunrise, sunset) = sun_times(date("2024-06-21"), 52.37, 4.90).unwrap();
+        assert!((sunrise - utc("2024-06-21", "03:18")).abs() <= TOLERANCE_SECONDS);
+        assert!((sunset - utc("2024-06-21", "20:06")).abs() <= TOLERANCE_SECONDS);
+
+        let (sunrise, sunset) = sun_times(date("2024-12-21"), 52.37, 4.90).unwrap();
+        assert!((sunrise - utc("2024-12-21", "07:48")).abs() <= TOLERANCE_SECONDS);
+        assert!((sunset - utc("2024-12-21", "15:29")).abs() <= TOLERANCE_SECONDS);
+    }
+
+    #[test]
+    fn sun_times_west_of_greenwich() {
+        //New York, sunset is after midnight UTC
+        let (sunrise, sunset) = sun_times(date("2024-06-21"), 40.71, -74.01).unwrap();
+        assert!((sunrise - utc("2024-06-21", "09:25")).abs() <= TOLERANCE_SECONDS);
+        assert!((sunset - utc("2024-06-22", "00:31")).abs() <= TOLERANCE_SECONDS);
+    }
+
+    #[test]
+    fn no_sun_times_during_polar_day_and_night() {
+        assert!(sun_times(date("2024-06-21"), 78.22, 15.65).is_none());
+        assert!(sun_times(date("2024-12-21"), 78.22, 15.65).is_none());
+    }
+}
@@ -30,7 +30,7 @@ pub fn get_user(session_id: &str, appdata: &AppData) -> Result<Option<User>, Err
     }
     let mut conn = conn_wrapped.unwrap();
     let sql_fetch_result = conn.exec::<Row, &str, Params>("SELECT user_id FROM users WHERE session_id = :session_id", params! {
-        "session_id" => session_id.clone()
+        "session_id" => session_id
     });
 
     if sql_fetch_result.is_err() {
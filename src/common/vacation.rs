@@ -0,0 +1,211 @@
+use crate::appdata::AppData;
+use crate::database::Database;
+use crate::types::vacation::{Vacation, VacationStatus, format_local};
+
+use mysql::{Error, Params, params, Row, Transaction};
+use mysql::prelude::Queryable;
+
+/**
+Get the vacation of a User
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    None: If the User has no vacation planned
+    Some: The vacation
+*/
+pub fn get_vacation(db: Database, user_id: String) -> Result<Option<Vacation>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT start, end, frost_setpoint, status, held, hot_water_services FROM vacations WHERE user_id = :user_id", params! {
+        "user_id" => user_id
+    })?;
+
+    Ok(fetch_result.first().map(row_to_vacation))
+}
+
+/**
+Get the vacations of all Users
+
+## Parameters
+    db: An instance of Database
+
+## Returns
+    Err: If an error occurred
+    Ok: The vacations, along with the ID of the User they belong to
+*/
+pub fn get_vacations(db: Database) -> Result<Vec<(String, Vacation)>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.query::<Row, &str>("SELECT user_id, start, end, frost_setpoint, status, held, hot_water_services FROM vacations")?;
+
+    let vacations = fetch_result.iter()
+        .map(|row| (row.get::<String, &str>("user_id").unwrap(), row_to_vacation(row)))
+        .collect();
+
+    Ok(vacations)
+}
+
+/**
+Create or update the vacation of a User
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+    vacation: The vacation, it must be valid
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_vacation(db: Database, user_id: String, vacation: &Vacation) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO vacations (user_id, start, end, frost_setpoint, status, held, hot_water_services) VALUES (:user_id, :start, :end, :frost_setpoint, :status, :held, :hot_water_services) \
+        ON DUPLICATE KEY UPDATE start = :start, end = :end, frost_setpoint = :frost_setpoint, status = :status, held = :held, hot_water_services = :hot_water_services", params! {
+        "user_id" => user_id,
+        "start" => vacation.start_timestamp(),
+        "end" => vacation.end_timestamp(),
+        "frost_setpoint" => vacation.frost_setpoint,
+        "status" => format!("{:?}", vacation.status),
+        "held" => serde_json::to_string(&vacation.held).unwrap(),
+        "hot_water_services" => serde_json::to_string(&vacation.hot_water_services).unwrap()
+    })?;
+
+    Ok(())
+}
+
+/**
+Remove the vacation of a User
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn delete_vacation(db: Database, user_id: String) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM vacations WHERE user_id = :user_id", params! {
+        "user_id" => user_id
+    })?;
+
+    Ok(())
+}
+
+/**
+Stop holding the Devices and hot water of a Service which is removed
+
+## Parameters
+    tx: The transaction removing the Service
+    user_id: The ID of the User
+    service_id: The ID of the Service
+    device_ids: The IDs of the removed Devices
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn remove_service(tx: &mut Transaction, user_id: &str, service_id: &str, device_ids: &[String]) -> Result<(), Error> {
+    let fetch_result = tx.exec::<Row, &str, Params>("SELECT start, end, frost_setpoint, status, held, hot_water_services FROM vacations WHERE user_id = :user_id", params! {
+        "user_id" => user_id
+    })?;
+
+    let vacation = fetch_result.first().map(row_to_vacation);
+    if vacation.is_none() {
+        return Ok(());
+    }
+
+    let mut vacation = vacation.unwrap();
+    vacation.held.retain(|held| !device_ids.contains(&held.device_id));
+    vacation.hot_water_services.retain(|hot_water_service| hot_water_service != service_id);
+
+    let _ = tx.exec::<usize, &str, Params>("UPDATE vacations SET held = :held, hot_water_services = :hot_water_services WHERE user_id = :user_id", params! {
+        "user_id" => user_id,
+        "held" => serde_json::to_string(&vacation.held).unwrap(),
+        "hot_water_services" => serde_json::to_string(&vacation.hot_water_services).unwrap()
+    })?;
+
+    Ok(())
+}
+
+/**
+Get the IDs of all Devices held by a vacation, which have not been restored yet
+
+## Parameters
+    db: An instance of Database
+
+## Returns
+    Err: If an error occurred
+    Ok: The IDs of the Devices
+*/
+pub fn get_held_devices(db: Database) -> Result<Vec<String>, Error> {
+    let held = get_vacations(db)?.into_iter()
+        .filter(|(_, vacation)| vacation.status == VacationStatus::ACTIVE)
+        .flat_map(|(_, vacation)| vacation.held)
+        .filter(|held| !held.restored)
+        .map(|held| held.device_id)
+        .collect();
+
+    Ok(held)
+}
+
+/**
+Cancel the vacation of a User. A vacation which has not started is removed,
+an active vacation ends now, so all held Devices are restored right away
+
+## Parameters
+    data: An instance of AppData
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: If the User had a vacation
+*/
+pub fn cancel(data: &AppData, user_id: String) -> Result<bool, ()> {
+    let vacation = get_vacation(data.database.clone(), user_id.clone());
+    if vacation.is_err() {
+        eprintln!("An error occurred: {:?}", vacation.err());
+        return Err(());
+    }
+
+    let vacation = vacation.unwrap();
+    if vacation.is_none() {
+        return Ok(false);
+    }
+
+    let mut vacation = vacation.unwrap();
+    let result = match vacation.status {
+        VacationStatus::SCHEDULED => {
+            crate::common::homegraph::request_sync_in_background(user_id.clone());
+            delete_vacation(data.database.clone(), user_id)
+        },
+        VacationStatus::ACTIVE => {
+            vacation.end = format_local(chrono::Utc::now().timestamp());
+            set_vacation(data.database.clone(), user_id, &vacation)
+        }
+    };
+
+    if result.is_err() {
+        eprintln!("An error occurred: {:?}", result.err());
+        return Err(());
+    }
+
+    Ok(true)
+}
+
+fn row_to_vacation(row: &Row) -> Vacation {
+    Vacation {
+        start: format_local(row.get::<i64, &str>("start").unwrap()),
+        end: format_local(row.get::<i64, &str>("end").unwrap()),
+        frost_setpoint: row.get::<f32, &str>("frost_setpoint").unwrap(),
+        status: match row.get::<String, &str>("status").unwrap().as_str() {
+            "ACTIVE" => VacationStatus::ACTIVE,
+            _ => VacationStatus::SCHEDULED
+        },
+        held: serde_json::from_str(&row.get::<String, &str>("held").unwrap()).unwrap_or_default(),
+        hot_water_services: serde_json::from_str(&row.get::<String, &str>("hot_water_services").unwrap()).unwrap_or_default()
+    }
+}
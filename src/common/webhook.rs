@@ -0,0 +1,322 @@
+use crate::database::Database;
+use crate::types::webhook::{DeliveryAttempt, DeliveryStatus, DueDelivery, WebhookDelivery, WebhookSubscription};
+
+use mysql::{Error, Params, params, Row};
+use mysql::prelude::Queryable;
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+
+/**
+Get all webhook subscriptions of a User
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: The subscriptions
+*/
+pub fn get_subscriptions(db: Database, user_id: String) -> Result<Vec<WebhookSubscription>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT subscription_id, url, enabled, events, device_ids, secret FROM webhook_subscriptions WHERE user_id = :user_id ORDER BY url", params! {
+        "user_id" => user_id
+    })?;
+
+    Ok(fetch_result.iter().map(row_to_subscription).collect())
+}
+
+/**
+Create or update a webhook subscription
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User owning the subscription
+    subscription: The subscription
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn set_subscription(db: Database, user_id: String, subscription: &WebhookSubscription) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO webhook_subscriptions (subscription_id, user_id, url, enabled, events, device_ids, secret) VALUES (:subscription_id, :user_id, :url, :enabled, :events, :device_ids, :secret) \
+        ON DUPLICATE KEY UPDATE url = :url, enabled = :enabled, events = :events, device_ids = :device_ids, secret = :secret", params! {
+        "subscription_id" => subscription.subscription_id.clone(),
+        "user_id" => user_id,
+        "url" => subscription.url.clone(),
+        "enabled" => subscription.enabled,
+        "events" => serde_json::to_string(&subscription.events).unwrap(),
+        "device_ids" => serde_json::to_string(&subscription.device_ids).unwrap(),
+        "secret" => subscription.secret.clone()
+    })?;
+
+    Ok(())
+}
+
+/**
+Remove a webhook subscription, along with its deliveries
+
+## Parameters
+    db: An instance of Database
+    subscription_id: The ID of the subscription
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn delete_subscription(db: Database, subscription_id: String) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM webhook_delivery_attempts WHERE delivery_id IN (SELECT delivery_id FROM webhook_deliveries WHERE subscription_id = :subscription_id)", params! {
+        "subscription_id" => subscription_id.clone()
+    })?;
+
+    for table in ["webhook_deliveries", "webhook_subscriptions"].iter() {
+        let _ = conn.exec::<usize, String, Params>(format!("DELETE FROM {} WHERE subscription_id = :subscription_id", table), params! {
+            "subscription_id" => subscription_id.clone()
+        })?;
+    }
+
+    Ok(())
+}
+
+/**
+Queue a delivery, it is attempted right away
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User owning the subscription
+    subscription_id: The ID of the subscription
+    delivery_id: The ID of the delivery
+    event: The name of the event
+    payload: The body to POST
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn queue_delivery(db: Database, user_id: String, subscription_id: String, delivery_id: String, event: &str, payload: String) -> Result<(), Error> {
+    let now = chrono::Utc::now().timestamp();
+
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO webhook_deliveries (delivery_id, subscription_id, user_id, event, payload, status, attempts, created_at, next_attempt_at) \
+        VALUES (:delivery_id, :subscription_id, :user_id, :event, :payload, :status, 0, :now, :now)", params! {
+        "delivery_id" => delivery_id,
+        "subscription_id" => subscription_id,
+        "user_id" => user_id,
+        "event" => event,
+        "payload" => payload,
+        "status" => format!("{:?}", DeliveryStatus::PENDING),
+        "now" => now
+    })?;
+
+    Ok(())
+}
+
+/**
+Get the pending deliveries of which the next attempt is due
+
+## Parameters
+    db: An instance of Database
+    now: The current Unix timestamp
+
+## Returns
+    Err: If an error occurred
+    Ok: The deliveries, oldest first
+*/
+pub fn get_due_deliveries(db: Database, now: i64) -> Result<Vec<DueDelivery>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT webhook_deliveries.delivery_id, webhook_deliveries.attempts, webhook_deliveries.payload, webhook_subscriptions.url, webhook_subscriptions.secret \
+        FROM webhook_deliveries INNER JOIN webhook_subscriptions ON webhook_subscriptions.subscription_id = webhook_deliveries.subscription_id \
+        WHERE webhook_deliveries.status = :status AND webhook_deliveries.next_attempt_at <= :now ORDER BY webhook_deliveries.next_attempt_at", params! {
+        "status" => format!("{:?}", DeliveryStatus::PENDING),
+        "now" => now
+    })?;
+
+    let deliveries = fetch_result.iter()
+        .map(|row| DueDelivery {
+            delivery_id: row.get::<String, &str>("delivery_id").unwrap(),
+            attempts: row.get::<u32, &str>("attempts").unwrap(),
+            payload: row.get::<String, &str>("payload").unwrap(),
+            url: row.get::<String, &str>("url").unwrap(),
+            secret: row.get::<String, &str>("secret").unwrap()
+        })
+        .collect();
+
+    Ok(deliveries)
+}
+
+/**
+Record an attempt of a delivery, and update the delivery with its outcome
+
+## Parameters
+    db: An instance of Database
+    delivery_id: The ID of the delivery
+    attempt: The attempt
+    status: The status of the delivery after the attempt
+    next_attempt_at: When the delivery should be attempted again, None if it isn't retried
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn record_attempt(db: Database, delivery_id: String, attempt: &DeliveryAttempt, status: DeliveryStatus, next_attempt_at: Option<i64>) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("INSERT INTO webhook_delivery_attempts (delivery_id, attempted_at, response_status, response) VALUES (:delivery_id, :attempted_at, :response_status, :response)", params! {
+        "delivery_id" => delivery_id.clone(),
+        "attempted_at" => attempt.attempted_at,
+        "response_status" => attempt.response_status,
+        "response" => attempt.response.clone()
+    })?;
+
+    let _ = conn.exec::<usize, &str, Params>("UPDATE webhook_deliveries SET status = :status, attempts = attempts + 1, next_attempt_at = :next_attempt_at WHERE delivery_id = :delivery_id", params! {
+        "delivery_id" => delivery_id,
+        "status" => format!("{:?}", status),
+        "next_attempt_at" => next_attempt_at
+    })?;
+
+    Ok(())
+}
+
+/**
+Get the most recent deliveries of a User, with their attempts
+
+## Parameters
+    db: An instance of Database
+    user_id: The ID of the User
+    subscription_id: Only get the deliveries of this subscription, if Some
+    limit: The maximum number of deliveries
+
+## Returns
+    Err: If an error occurred
+    Ok: The deliveries, newest first
+*/
+pub fn get_deliveries(db: Database, user_id: String, subscription_id: Option<String>, limit: u32) -> Result<Vec<WebhookDelivery>, Error> {
+    let mut conn = db.pool.get_conn()?;
+    let fetch_result = conn.exec::<Row, &str, Params>("SELECT deliveries.delivery_id, deliveries.subscription_id, deliveries.event, deliveries.status, deliveries.created_at, deliveries.next_attempt_at, \
+        webhook_delivery_attempts.attempted_at, webhook_delivery_attempts.response_status, webhook_delivery_attempts.response \
+        FROM (SELECT * FROM webhook_deliveries WHERE user_id = :user_id AND (:subscription_id IS NULL OR subscription_id = :subscription_id) ORDER BY created_at DESC LIMIT :limit) AS deliveries \
+        LEFT JOIN webhook_delivery_attempts ON webhook_delivery_attempts.delivery_id = deliveries.delivery_id \
+        ORDER BY deliveries.created_at DESC, deliveries.delivery_id, webhook_delivery_attempts.attempted_at", params! {
+        "user_id" => user_id,
+        "subscription_id" => subscription_id,
+        "limit" => limit
+    })?;
+
+    let mut deliveries: Vec<WebhookDelivery> = vec![];
+    for row in fetch_result {
+        let delivery_id = row.get::<String, &str>("delivery_id").unwrap();
+        if deliveries.last().map(|delivery| delivery.delivery_id != delivery_id).unwrap_or(true) {
+            let status = row.get::<String, &str>("status").unwrap();
+            deliveries.push(WebhookDelivery {
+                delivery_id,
+                subscription_id: row.get::<String, &str>("subscription_id").unwrap(),
+                event: row.get::<String, &str>("event").unwrap(),
+                status: serde_json::from_value(serde_json::Value::String(status)).unwrap_or(DeliveryStatus::FAILED),
+                created_at: row.get::<i64, &str>("created_at").unwrap(),
+                next_attempt_at: row.get::<Option<i64>, &str>("next_attempt_at").unwrap(),
+                attempts: vec![]
+            });
+        }
+
+        //Deliveries which were not attempted yet have no attempts to join
+        if let Some(attempted_at) = row.get::<Option<i64>, &str>("attempted_at").unwrap() {
+            deliveries.last_mut().unwrap().attempts.push(DeliveryAttempt {
+                attempted_at,
+                response_status: row.get::<Option<u16>, &str>("response_status").unwrap(),
+                response: row.get::<String, &str>("response").unwrap()
+            });
+        }
+    }
+
+    Ok(deliveries)
+}
+
+/**
+Remove deliveries, and their attempts, created before a moment
+
+## Parameters
+    db: An instance of Database
+    before: Unix timestamp
+
+## Returns
+    Err: If an error occurred
+    Ok: If everything went OK
+*/
+pub fn prune_deliveries(db: Database, before: i64) -> Result<(), Error> {
+    let mut conn = db.pool.get_conn()?;
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM webhook_delivery_attempts WHERE delivery_id IN (SELECT delivery_id FROM webhook_deliveries WHERE created_at < :before)", params! {
+        "before" => before
+    })?;
+
+    let _ = conn.exec::<usize, &str, Params>("DELETE FROM webhook_deliveries WHERE created_at < :before", params! {
+        "before" => before
+    })?;
+
+    Ok(())
+}
+
+/**
+Sign a delivery. The signature is the hex encoded HMAC-SHA256 of '{timestamp}.{payload}', keyed with the secret of the subscription
+
+## Parameters
+    secret: The secret of the subscription
+    timestamp: The Unix timestamp sent along with the delivery
+    payload: The body of the delivery
+
+## Returns
+    Err: If signing failed
+    Ok: The signature
+*/
+pub fn sign(secret: &str, timestamp: i64, payload: &str) -> Result<String, ()> {
+    let key = PKey::hmac(secret.as_bytes());
+    if key.is_err() {
+        eprintln!("Unable to create HMAC key: {:?}", key.err());
+        return Err(());
+    }
+
+    let key = key.unwrap();
+    let signature = Signer::new(MessageDigest::sha256(), &key)
+        .and_then(|mut signer| signer.sign_oneshot_to_vec(format!("{}.{}", timestamp, payload).as_bytes()));
+
+    if signature.is_err() {
+        eprintln!("Unable to sign delivery: {:?}", signature.err());
+        return Err(());
+    }
+
+    Ok(signature.unwrap().iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+fn row_to_subscription(row: &Row) -> WebhookSubscription {
+    WebhookSubscription {
+        subscription_id: row.get::<String, &str>("subscription_id").unwrap(),
+        url: row.get::<String, &str>("url").unwrap(),
+        enabled: row.get::<bool, &str>("enabled").unwrap(),
+        events: serde_json::from_str(&row.get::<String, &str>("events").unwrap()).unwrap_or_default(),
+        device_ids: serde_json::from_str(&row.get::<String, &str>("device_ids").unwrap()).unwrap_or_default(),
+        secret: row.get::<String, &str>("secret").unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deliveries_are_signed_with_hmac_sha256() {
+        let signature = sign("secret", 1700000000, r#"{"event":"PING"}"#).unwrap();
+        assert_eq!(signature, "57370410fb5cb13b4ad6e0aa2bc06567c219374187a4237c78512954499759da");
+        assert_eq!(sign("secret", 0, "").unwrap(), "3445798a051818ef95def46c2eb62b43d377ce6e3c29b4d0aec3da0e59577f79");
+    }
+
+    #[test]
+    fn signature_covers_the_secret_timestamp_and_payload() {
+        let signature = sign("secret", 1700000000, "payload").unwrap();
+        assert_eq!(signature.len(), 64);
+        assert_eq!(signature, sign("secret", 1700000000, "payload").unwrap());
+        assert_ne!(signature, sign("other", 1700000000, "payload").unwrap());
+        assert_ne!(signature, sign("secret", 1700000001, "payload").unwrap());
+        assert_ne!(signature, sign("secret", 1700000000, "payload ").unwrap());
+    }
+}
@@ -5,35 +5,240 @@ use mysql::{Row, Params, params};
 use mysql::prelude::Queryable;
 use rand::Rng;
 use crate::database::Database;
+use crate::jsonc::{self, Document, Position};
+use crate::types::service::{ServiceType, LoginMethod};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+pub const SERVICES_CONF_PATH: &str = "config/services.jsonc";
+
+/// The Service configurations shared by all workers. The inner Arc is replaced as a whole when services.jsonc is reloaded
+pub type SharedServicesConfigs = Arc<RwLock<Arc<Vec<ServicesConfig>>>>;
 
 #[derive(Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct ServicesConfig {
     pub name:           String,
     pub identifier:     crate::types::service::ServiceType,
     pub icon:           String,
     pub requires_login: bool,
-    pub login_method:   crate::types::service::LoginMethod
+    /// Only set if requires_login is true
+    pub login_method:   Option<crate::types::service::LoginMethod>,
+
+    /// The fields the user fills in when adding the Service, required for the PASSWORD and TOKEN login methods
+    pub credentials:    Option<Vec<CredentialField>>,
+
+    /// Base URL of the Service's API, overrides the default of the Service. E.g. to test against a local stand-in
+    #[serde(skip_serializing)]
+    pub api_endpoint:   Option<String>,
+
+    /// OAuth client configuration, required if login_method is OAUTH
+    #[serde(skip_serializing)]
+    pub oauth:          Option<OAuthServiceConfig>,
+
+    /// Device types which can be added to a HTTP Service
+    pub http_definitions:   Option<Vec<crate::types::http::HttpDeviceDefinition>>
+}
+
+/**
+A field of the add-service form, the frontend renders the form from these.
+The value is sent to /services/add under the name of the field
+*/
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CredentialField {
+    pub name:           String,
+    /// Label shown in the frontend
+    pub label:          String,
+    #[serde(rename = "type")]
+    pub field_type:     CredentialFieldType,
+    /// Defaults to false
+    #[serde(default)]
+    pub optional:       bool
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum CredentialFieldType {
+    TEXT,
+    PASSWORD,
+    URL
 }
 
-pub fn read_services_conf() -> Vec<ServicesConfig> {
-    let conf_content = fs::read_to_string("config/services.jsonc");
+/**
+A problem found in services.jsonc
+*/
+#[derive(Debug)]
+pub struct ConfigError {
+    /// Location in services.jsonc, None if the file could not be read
+    pub position:       Option<Position>,
+    pub message:        String
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.position {
+            Some(position) => write!(f, "{}:{}: {}", SERVICES_CONF_PATH, position, self.message),
+            None => write!(f, "{}: {}", SERVICES_CONF_PATH, self.message)
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OAuthServiceConfig {
+    pub authorization_endpoint: String,
+    pub token_endpoint:         String,
+    pub client_id:              String,
+    pub client_secret:          Option<String>,
+    pub scope:                  Option<String>
+}
+
+/**
+Get the configuration for a ServiceType
+
+## Parameters
+    configs: All Service configurations
+    service_type: The ServiceType to get the configuration for
+
+## Returns
+    None: If the ServiceType isn't configured
+    Some: The configuration
+*/
+pub fn get_service_config<'a>(configs: &'a [ServicesConfig], service_type: &ServiceType) -> Option<&'a ServicesConfig> {
+    configs.iter().find(|config| config.identifier == *service_type)
+}
+
+/**
+Read and validate config/services.jsonc
+
+## Returns
+    Err: Every problem found in the file, with its location
+    Ok: The Service configurations
+*/
+pub fn read_services_conf() -> Result<Vec<ServicesConfig>, Vec<ConfigError>> {
+    let conf_content = fs::read_to_string(SERVICES_CONF_PATH);
     if conf_content.is_err() {
-        eprintln!("Unable to read services.jsonc: {:?}", conf_content.err());
-        std::process::exit(1);
+        return Err(vec![ConfigError { position: None, message: format!("Unable to read file: {:?}", conf_content.err()) }]);
     }
 
-    //Remove the comments
-    let conf_str = conf_content.unwrap();
-    let regex = regex::Regex::new(r"/\*([^*]|[\r\n]|(\*+([^*/]|[\r\n])))*\*/+").unwrap();
-    let conf_str_filtered = regex.replace_all(&conf_str, "").as_ref().to_string().clone();
+    let document = jsonc::parse(&conf_content.unwrap());
+    if document.is_err() {
+        let error = document.err().unwrap();
+        return Err(vec![ConfigError { position: Some(error.position), message: error.message }]);
+    }
 
-    let config: Result<Vec<ServicesConfig>, serde_json::error::Error> = serde_json::from_str::<Vec<ServicesConfig>>(&conf_str_filtered);
-    if config.is_err() {
-        eprintln!("Invalid config 'services.jsonc': {:?}", config.err());
-        std::process::exit(1);
+    validate_services_conf(&document.unwrap())
+}
+
+/**
+Read services.jsonc again and replace the shared configurations with it.
+If the file is invalid, the current configurations are kept
+
+## Parameters
+    services_configs: The shared Service configurations
+
+## Returns
+    true: If the configurations were replaced
+    false: If the file is invalid
+*/
+pub fn reload_services_conf(services_configs: &SharedServicesConfigs) -> bool {
+    let configs = read_services_conf();
+    if configs.is_err() {
+        eprintln!("Not reloading {}, the current configuration is kept:", SERVICES_CONF_PATH);
+        for error in configs.err().unwrap() {
+            eprintln!("{}", error);
+        }
+
+        return false;
+    }
+
+    *services_configs.write().unwrap() = Arc::new(configs.unwrap());
+    println!("Reloaded {}", SERVICES_CONF_PATH);
+
+    true
+}
+
+/**
+Validate a parsed services.jsonc. All entries are checked, so every problem is reported at once
+*/
+fn validate_services_conf(document: &Document) -> Result<Vec<ServicesConfig>, Vec<ConfigError>> {
+    let error_at = |path: &str, message: String| ConfigError { position: document.position(path), message };
+
+    let entries = document.value.as_array();
+    if entries.is_none() {
+        return Err(vec![error_at("", "Expected an array of services".to_string())]);
+    }
+
+    let mut configs: Vec<ServicesConfig> = vec![];
+    let mut errors: Vec<ConfigError> = vec![];
+
+    for (index, entry) in entries.unwrap().iter().enumerate() {
+        let path = format!("[{}]", index);
+
+        //Check the identifier first, so an unknown identifier gets a clearer message than serde would give
+        let identifier = entry.get("identifier").and_then(|identifier| identifier.as_str());
+        if identifier.is_none() {
+            errors.push(error_at(&path, "Missing identifier".to_string()));
+            continue;
+        }
+
+        let identifier_path = format!("{}.identifier", path);
+        if ServiceType::from_str(identifier.unwrap()).is_err() {
+            errors.push(error_at(&identifier_path, format!("Unknown identifier '{}'", identifier.unwrap())));
+            continue;
+        }
+
+        let config = serde_json::from_value::<ServicesConfig>(entry.clone());
+        if config.is_err() {
+            errors.push(error_at(&path, format!("{} ({})", config.err().unwrap(), identifier.unwrap())));
+            continue;
+        }
+
+        let config = config.unwrap();
+        if configs.iter().any(|other| other.identifier == config.identifier) {
+            errors.push(error_at(&identifier_path, format!("Duplicate identifier '{}'", config.identifier)));
+            continue;
+        }
+
+        match (&config.requires_login, &config.login_method) {
+            (true, None) => errors.push(error_at(&path, format!("{} requires a login, but has no login_method", config.identifier))),
+            (false, Some(_)) => errors.push(error_at(&format!("{}.login_method", path), format!("{} does not require a login, but has a login_method", config.identifier))),
+            _ => {}
+        }
+
+        let missing_credentials = config.credentials.as_ref().map(|fields| fields.is_empty()).unwrap_or(true);
+        match &config.login_method {
+            Some(LoginMethod::OAUTH) if config.oauth.is_none() => {
+                errors.push(error_at(&path, format!("{} uses the OAUTH login method, but has no oauth configuration", config.identifier)));
+            },
+            Some(LoginMethod::PASSWORD) | Some(LoginMethod::TOKEN) if missing_credentials => {
+                errors.push(error_at(&path, format!("{} uses the {} login method, but declares no credentials", config.identifier, config.login_method.as_ref().unwrap())));
+            },
+            _ => {}
+        }
+
+        if let Some(fields) = &config.credentials {
+            for (field_index, field) in fields.iter().enumerate() {
+                if fields.iter().skip(field_index + 1).any(|other| other.name == field.name) {
+                    errors.push(error_at(&format!("{}.credentials[{}]", path, field_index), format!("Duplicate credential field '{}'", field.name)));
+                }
+            }
+        }
+
+        if config.http_definitions.is_some() && config.identifier != ServiceType::HTTP {
+            errors.push(error_at(&format!("{}.http_definitions", path), "http_definitions are only supported for HTTP".to_string()));
+        }
+
+        configs.push(config);
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
     }
 
-    config.unwrap()
+    Ok(configs)
 }
 
 pub fn get_oauth_credentials(database: &Database) -> Vec<OAuthCredentials> {
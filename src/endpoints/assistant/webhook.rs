@@ -2,7 +2,14 @@ use actix_web::{post, web, HttpResponse, HttpRequest};
 use mysql::prelude::Queryable;
 use mysql::{Row, Params, params};
 use crate::appdata::AppData;
-use crate::types::assistant_incoming::{FulfillmentRequest, GenericFulfillmentInput, FulfillmentIntent};
+use crate::types::assistant_incoming::{FulfillmentRequest, GenericFulfillmentInput, FulfillmentIntent, QueryFulfillmentInput, ExecuteFulfillmentInput, Execution, CommandAction};
+use crate::types::assistant_outgoing::{FulfillmentResponse, SyncFulfillmentPayload, SyncDevice, QueryFulfillmentPayload, ExecuteFulfillmentPayload, ExecuteCommandResult, ExecuteDeviceStatus, device_state_to_google};
+use crate::types::device::{DeviceState, DeviceCommand};
+use crate::types::scene::{Scene, SCENE_ID_PREFIX};
+use crate::types::vacation::VACATION_DEVICE_ID;
+use crate::services::UserDevice;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
 
 #[post("/assistant/webhook")]
 pub async fn post_webhook(data: web::Data<AppData>, req: HttpRequest, bytes: web::Bytes) -> HttpResponse {
@@ -55,17 +62,249 @@ pub async fn post_webhook(data: web::Data<AppData>, req: HttpRequest, bytes: web
     }
 
     //Access token validated.
-    let basic_fulfillment_request: FulfillmentRequest<GenericFulfillmentInput> = serde_json::from_slice(&body_unwrapped.clone().as_bytes()).unwrap();
-    for input in basic_fulfillment_request.inputs {
-        match input.intent {
-            FulfillmentIntent::SYNC => {
+    let basic_fulfillment_request = serde_json::from_slice::<FulfillmentRequest<GenericFulfillmentInput>>(body_unwrapped.as_bytes());
+    if basic_fulfillment_request.is_err() {
+        eprintln!("Unable to deserialize fulfillment request: {:?}", basic_fulfillment_request.err());
+        return HttpResponse::BadRequest().finish();
+    }
+
+    let basic_fulfillment_request = basic_fulfillment_request.unwrap();
+    let request_id = basic_fulfillment_request.request_id.clone();
+
+    //Google only ever sends a single input per request
+    let input = basic_fulfillment_request.inputs.first();
+    if input.is_none() {
+        return HttpResponse::BadRequest().finish();
+    }
+
+    match input.unwrap().intent {
+        FulfillmentIntent::SYNC => {
+            let devices = crate::services::get_exposed_devices(&data, user_id.clone());
+            if devices.is_err() {
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            let rooms = crate::common::room::get_rooms(data.database.clone(), user_id.clone());
+            let floors = crate::common::room::get_floors(data.database.clone(), user_id.clone());
+            if rooms.is_err() || floors.is_err() {
+                eprintln!("Unable to fetch rooms and floors: {:?} {:?}", rooms.err(), floors.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            let scenes = crate::common::scene::get_scenes(data.database.clone(), user_id.clone());
+            let vacation = crate::common::vacation::get_vacation(data.database.clone(), user_id.clone());
+            if scenes.is_err() || vacation.is_err() {
+                eprintln!("Unable to fetch scenes and vacation: {:?} {:?}", scenes.err(), vacation.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            let (rooms, floors) = (rooms.unwrap(), floors.unwrap());
+            let mut sync_devices: Vec<SyncDevice> = devices.unwrap().iter()
+                .map(|user_device| {
+                    let room = rooms.iter().find(|room| Some(&room.room_id) == user_device.registered.room_id.as_ref());
+                    let floor = room.and_then(|room| floors.iter().find(|floor| Some(&floor.floor_id) == room.floor_id.as_ref()));
+                    SyncDevice::from_device(&user_device.registered, &user_device.device, room, floor)
+                })
+                .collect();
+
+            //Scenes are synced as devices of their own
+            sync_devices.extend(scenes.unwrap().iter().map(SyncDevice::from_scene));
+
+            //A planned vacation can be cancelled from the Assistant
+            if vacation.unwrap().is_some() {
+                sync_devices.push(SyncDevice::from_vacation());
+            }
+
+            let payload = SyncFulfillmentPayload { agent_user_id: user_id, devices: sync_devices };
+            HttpResponse::Ok().json(FulfillmentResponse { request_id, payload })
+        },
+        FulfillmentIntent::QUERY => {
+            let query_request = serde_json::from_slice::<FulfillmentRequest<QueryFulfillmentInput>>(body_unwrapped.as_bytes());
+            if query_request.is_err() {
+                eprintln!("Unable to deserialize QUERY request: {:?}", query_request.err());
+                return HttpResponse::BadRequest().finish();
+            }
+
+            let devices = crate::services::get_exposed_devices(&data, user_id.clone());
+            let scenes = crate::common::scene::get_scenes(data.database.clone(), user_id);
+            if devices.is_err() || scenes.is_err() {
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            let (devices, scenes) = (devices.unwrap(), scenes.unwrap());
+            let mut states: HashMap<String, Map<String, Value>> = HashMap::new();
+            for input in query_request.unwrap().inputs {
+                for requested in input.payload.devices {
+                    //Scenes have no state, they're online as long as they exist
+                    if let Some(scene_id) = requested.id.strip_prefix(SCENE_ID_PREFIX) {
+                        let mut state: Map<String, Value> = Map::new();
+                        state.insert("online".to_string(), json!(scenes.iter().any(|scene| scene.scene_id == scene_id)));
+                        states.insert(requested.id, state);
+                        continue;
+                    }
+
+                    if requested.id == VACATION_DEVICE_ID {
+                        let mut state: Map<String, Value> = Map::new();
+                        state.insert("online".to_string(), json!(true));
+                        states.insert(requested.id, state);
+                        continue;
+                    }
+
+                    let device = crate::services::find_device(&devices, &requested.id);
 
-            },
-            FulfillmentIntent::QUERY => {
+                    //If we can't find the Device its Service is unreachable, it no longer exists or it is no longer exposed
+                    let state = match device {
+                        Some(user_device) => device_state_to_google(&user_device.device.state, &user_device.device.capabilities),
+                        None => device_state_to_google(&DeviceState::default(), &[])
+                    };
 
+                    states.insert(requested.id, state);
+                }
             }
+
+            let payload = QueryFulfillmentPayload { devices: states };
+            HttpResponse::Ok().json(FulfillmentResponse { request_id, payload })
+        },
+        FulfillmentIntent::EXECUTE => {
+            let execute_request = serde_json::from_slice::<FulfillmentRequest<ExecuteFulfillmentInput<Value>>>(body_unwrapped.as_bytes());
+            if execute_request.is_err() {
+                eprintln!("Unable to deserialize EXECUTE request: {:?}", execute_request.err());
+                return HttpResponse::BadRequest().finish();
+            }
+
+            let devices = crate::services::get_exposed_devices(&data, user_id.clone());
+            let scenes = crate::common::scene::get_scenes(data.database.clone(), user_id.clone());
+            if devices.is_err() || scenes.is_err() {
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            let (devices, scenes) = (devices.unwrap(), scenes.unwrap());
+            let mut results: Vec<ExecuteCommandResult> = vec![];
+            for input in execute_request.unwrap().inputs {
+                for command in input.payload.commands {
+                    for requested in &command.devices {
+                        if let Some(scene_id) = requested.id.strip_prefix(SCENE_ID_PREFIX) {
+                            results.push(activate_scene(&data, &user_id, &scenes, scene_id, &requested.id, &command.execution));
+                            continue;
+                        }
+
+                        if requested.id == VACATION_DEVICE_ID {
+                            results.push(cancel_vacation(&data, &user_id, &requested.id, &command.execution));
+                            continue;
+                        }
+
+                        results.push(execute_on_device(&data, &devices, &requested.id, &command.execution));
+                    }
+                }
+            }
+
+            let payload = ExecuteFulfillmentPayload { commands: results };
+            HttpResponse::Ok().json(FulfillmentResponse { request_id, payload })
+        },
+        FulfillmentIntent::DISCONNECT => {
+            //The user unlinked their account, revoke all grants
+            let conn = data.database.pool.get_conn();
+            if conn.is_err() {
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            let delete_result = conn.unwrap().exec::<usize, &str, Params>("DELETE FROM oauth_grants WHERE user_id = :user_id", params! {
+                "user_id" => user_id
+            });
+
+            if delete_result.is_err() {
+                eprintln!("An error occurred while removing OAuth grants: {:?}", delete_result.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            HttpResponse::Ok().json(json!({}))
         }
     }
+}
+
+/**
+Convert a Google execution into a DeviceCommand
+
+## Returns
+    None: If the execution is not supported, or its parameters are invalid
+    Some: The DeviceCommand
+*/
+fn to_device_command(execution: &Execution<Value>) -> Option<DeviceCommand> {
+    let params = &execution.params;
+    match execution.command {
+        CommandAction::ONOFF => params.get("on")?.as_bool().map(DeviceCommand::ONOFF),
+        CommandAction::BRIGHTNESSABSOLUTE => params.get("brightness")?.as_u64().map(|brightness| DeviceCommand::BRIGHTNESS(brightness.min(100) as u8)),
+        CommandAction::THERMOSTATTEMPERATURESETPOINT => params.get("thermostatTemperatureSetpoint")?.as_f64().map(|setpoint| DeviceCommand::SETPOINT(setpoint as f32)),
+        CommandAction::THERMOSTATSETMODE => params.get("thermostatMode")?.as_str().map(|mode| DeviceCommand::ONOFF(mode != "off")),
+        CommandAction::SETFANSPEED => params.get("fanSpeedPercent")?.as_u64().map(|speed| DeviceCommand::FANSPEED(speed.min(100) as u8)),
+        CommandAction::ACTIVATESCENE => None
+    }
+}
+
+/**
+Activate a Scene requested by Google. The Scene only succeeds if all its Devices were set
+*/
+fn activate_scene(data: &AppData, user_id: &str, scenes: &[Scene], scene_id: &str, id: &str, executions: &[Execution<Value>]) -> ExecuteCommandResult {
+    let scene = scenes.iter().find(|scene| scene.scene_id == scene_id);
+    if scene.is_none() {
+        return ExecuteCommandResult { ids: vec![id.to_string()], status: ExecuteDeviceStatus::ERROR, states: None, error_code: Some("deviceNotFound".to_string()) };
+    }
+
+    //Our Scenes are not reversible, so only ActivateScene without 'deactivate' is supported
+    let supported = executions.iter().all(|execution| matches!(execution.command, CommandAction::ACTIVATESCENE) && !execution.params.get("deactivate").and_then(|deactivate| deactivate.as_bool()).unwrap_or(false));
+    if !supported {
+        return ExecuteCommandResult { ids: vec![id.to_string()], status: ExecuteDeviceStatus::ERROR, states: None, error_code: Some("functionNotSupported".to_string()) };
+    }
+
+    let results = crate::common::scene::activate(data, user_id.to_string(), scene.unwrap());
+    if results.is_err() || results.unwrap().iter().any(|result| !result.success) {
+        return ExecuteCommandResult { ids: vec![id.to_string()], status: ExecuteDeviceStatus::ERROR, states: None, error_code: Some("transientError".to_string()) };
+    }
+
+    ExecuteCommandResult { ids: vec![id.to_string()], status: ExecuteDeviceStatus::SUCCESS, states: None, error_code: None }
+}
+
+/**
+Cancel the vacation of a User when Google deactivates the vacation mode. Activating it is not supported, a vacation needs a period
+*/
+fn cancel_vacation(data: &AppData, user_id: &str, id: &str, executions: &[Execution<Value>]) -> ExecuteCommandResult {
+    let supported = executions.iter().all(|execution| matches!(execution.command, CommandAction::ACTIVATESCENE) && execution.params.get("deactivate").and_then(|deactivate| deactivate.as_bool()).unwrap_or(false));
+    if !supported {
+        return ExecuteCommandResult { ids: vec![id.to_string()], status: ExecuteDeviceStatus::ERROR, states: None, error_code: Some("functionNotSupported".to_string()) };
+    }
+
+    match crate::common::vacation::cancel(data, user_id.to_string()) {
+        Ok(true) => ExecuteCommandResult { ids: vec![id.to_string()], status: ExecuteDeviceStatus::SUCCESS, states: None, error_code: None },
+        Ok(false) => ExecuteCommandResult { ids: vec![id.to_string()], status: ExecuteDeviceStatus::ERROR, states: None, error_code: Some("deviceNotFound".to_string()) },
+        Err(_) => ExecuteCommandResult { ids: vec![id.to_string()], status: ExecuteDeviceStatus::ERROR, states: None, error_code: Some("transientError".to_string()) }
+    }
+}
+
+/**
+Execute all executions of a Google command on a single Device
+*/
+fn execute_on_device(data: &AppData, devices: &[UserDevice], device_id: &str, executions: &[Execution<Value>]) -> ExecuteCommandResult {
+    let device = crate::services::find_device(devices, device_id);
+    if device.is_none() {
+        return ExecuteCommandResult { ids: vec![device_id.to_string()], status: ExecuteDeviceStatus::OFFLINE, states: None, error_code: Some("deviceOffline".to_string()) };
+    }
+
+    let UserDevice { service_id, service_type, device, .. } = device.unwrap();
+    let mut state = device.state.clone();
+    for execution in executions {
+        let command = to_device_command(execution);
+        if command.is_none() || !command.as_ref().unwrap().is_supported(&device.capabilities) {
+            return ExecuteCommandResult { ids: vec![device_id.to_string()], status: ExecuteDeviceStatus::ERROR, states: None, error_code: Some("functionNotSupported".to_string()) };
+        }
+
+        let command = command.unwrap();
+        let result = crate::services::execute(data, service_id.clone(), service_type.clone(), device.id.clone(), command.clone());
+        if result.is_err() {
+            return ExecuteCommandResult { ids: vec![device_id.to_string()], status: ExecuteDeviceStatus::ERROR, states: None, error_code: Some("transientError".to_string()) };
+        }
+
+        state.apply(&command);
+    }
 
-    HttpResponse::Ok().finish()
-}
\ No newline at end of file
+    ExecuteCommandResult { ids: vec![device_id.to_string()], status: ExecuteDeviceStatus::SUCCESS, states: Some(device_state_to_google(&state, &device.capabilities)), error_code: None }
+}
@@ -0,0 +1,72 @@
+use crate::appdata::AppData;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct ExposeDeviceRequest {
+    session_id:     String,
+    device_id:      String,
+    exposed:        bool
+}
+
+#[derive(Serialize)]
+pub struct ExposeDeviceResponse {
+    status:         i16
+}
+
+/**
+Set if a Device is exposed to Google Assistant. Google is asked to re-sync the User's Devices
+
+## Endpoint
+Path:   /devices/expose
+Method: POST
+*/
+#[post("/devices/expose")]
+pub async fn post_expose(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<ExposeDeviceRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(ExposeDeviceResponse { status: 401 });
+    }
+
+    //Users may only change their own Devices
+    let user = user_option.unwrap();
+    let device = crate::common::device::get_device(data.database.clone(), request_unwrapped.device_id.clone());
+    if device.is_err() {
+        eprintln!("An error occurred: {:?}", device.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let device_unwrapped = device.unwrap();
+    if device_unwrapped.is_none() || device_unwrapped.as_ref().unwrap().user_id != user.user_id {
+        return HttpResponse::NotFound().finish();
+    }
+
+    if device_unwrapped.unwrap().exposed != request_unwrapped.exposed {
+        let set_result = crate::common::device::set_exposed(data.database.clone(), request_unwrapped.device_id, request_unwrapped.exposed);
+        if set_result.is_err() {
+            eprintln!("An error occurred: {:?}", set_result.err());
+            return HttpResponse::InternalServerError().finish();
+        }
+
+        crate::common::homegraph::request_sync_in_background(user.user_id);
+    }
+
+    HttpResponse::Ok().json(ExposeDeviceResponse { status: 200 })
+}
@@ -0,0 +1,78 @@
+use crate::appdata::AppData;
+use crate::types::device::{RegisteredDevice, DeviceState};
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct GetDevicesRequest {
+    session_id:     String
+}
+
+#[derive(Serialize)]
+pub struct GetDevicesResponse {
+    status:         i16,
+    devices:        Option<Vec<DeviceEntry>>
+}
+
+#[derive(Serialize)]
+pub struct DeviceEntry {
+    #[serde(flatten)]
+    device:         RegisteredDevice,
+
+    /// The current state, None if the Service of the Device is unreachable or the Device was removed from it
+    state:          Option<DeviceState>
+}
+
+/**
+Get all registered Devices of the User, with their current state. Discovers the Devices of all Services first
+
+## Endpoint
+Path:   /devices/get
+Method: POST
+*/
+#[post("/devices/get")]
+pub async fn post_get(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<GetDevicesRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let user_result = crate::common::user::get_user(&request.unwrap().session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(GetDevicesResponse { status: 401, devices: None });
+    }
+
+    let user = user_option.unwrap();
+
+    //Discovery updates the registry
+    let user_devices = crate::services::get_user_devices(&data, user.user_id.clone());
+    if user_devices.is_err() {
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let registered = crate::common::device::get_devices(data.database.clone(), user.user_id);
+    if registered.is_err() {
+        eprintln!("An error occurred: {:?}", registered.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_devices = user_devices.unwrap();
+    let devices = registered.unwrap().into_iter()
+        .map(|device| {
+            let state = crate::services::find_device(&user_devices, &device.device_id).map(|user_device| user_device.device.state.clone());
+            DeviceEntry { device, state }
+        })
+        .collect();
+
+    HttpResponse::Ok().json(GetDevicesResponse { status: 200, devices: Some(devices) })
+}
@@ -0,0 +1,125 @@
+use crate::appdata::AppData;
+use crate::types::device::DeviceProperty;
+use crate::types::history::{Aggregation, HistoryQuery, HistorySeries, Resolution};
+use crate::common::history::{RAW_RETENTION_SECONDS, HOURLY_RETENTION_SECONDS};
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+/// The range returned when no start is requested
+const DEFAULT_RANGE_SECONDS: i64 = 86400;
+
+#[derive(Deserialize)]
+pub struct DeviceHistoryRequest {
+    session_id:     String,
+    /// Start of the range, Unix timestamp. Defaults to a day before 'to'
+    from:           Option<i64>,
+    /// End of the range, Unix timestamp. Defaults to now
+    to:             Option<i64>,
+    /// Defaults to every property with history
+    properties:     Option<Vec<DeviceProperty>>,
+    /// Defaults to the finest resolution still kept for 'from'
+    resolution:     Option<Resolution>,
+    /// Defaults to AVG
+    aggregation:    Option<Aggregation>,
+    /// Interval to aggregate over, in seconds. Defaults to no aggregation for raw history,
+    /// and to the period of the resolution otherwise
+    interval:       Option<i64>
+}
+
+#[derive(Serialize)]
+pub struct DeviceHistoryResponse {
+    status:         i16,
+    resolution:     Option<Resolution>,
+    series:         Option<Vec<HistorySeries>>
+}
+
+/**
+Get the recorded state history of a Device of the User
+
+## Endpoint
+Path:   /devices/{device_id}/history
+Method: POST
+*/
+#[post("/devices/{device_id}/history")]
+pub async fn post_history(data: web::Data<AppData>, device_id: web::Path<String>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<DeviceHistoryRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let now = chrono::Utc::now().timestamp();
+    let to = request_unwrapped.to.unwrap_or(now);
+    let from = request_unwrapped.from.unwrap_or(to - DEFAULT_RANGE_SECONDS);
+    if from >= to {
+        return HttpResponse::BadRequest().body("'from' must be before 'to'");
+    }
+
+    let resolution = request_unwrapped.resolution.unwrap_or(if from >= now - RAW_RETENTION_SECONDS {
+        Resolution::RAW
+    } else if from >= now - HOURLY_RETENTION_SECONDS {
+        Resolution::HOURLY
+    } else {
+        Resolution::DAILY
+    });
+
+    //Aggregates can't be split into smaller intervals
+    if let Some(interval) = request_unwrapped.interval {
+        if interval < resolution.period_seconds().unwrap_or(1) {
+            return HttpResponse::BadRequest().body("The interval can't be shorter than the period of the resolution");
+        }
+    }
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(DeviceHistoryResponse { status: 401, resolution: None, series: None });
+    }
+
+    let device_id = device_id.into_inner();
+    let device = crate::common::device::get_device(data.database.clone(), device_id.clone());
+    if device.is_err() {
+        eprintln!("An error occurred: {:?}", device.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let device = device.unwrap();
+    if device.is_none() || device.unwrap().user_id != user_option.unwrap().user_id {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let properties = request_unwrapped.properties.unwrap_or_else(|| DeviceProperty::ALL.to_vec());
+    let query = HistoryQuery {
+        from,
+        to,
+        resolution,
+        aggregation: request_unwrapped.aggregation.unwrap_or(Aggregation::AVG),
+        interval: request_unwrapped.interval
+    };
+
+    let mut series: Vec<HistorySeries> = vec![];
+    for property in properties {
+        let points = crate::common::history::get_history(data.database.clone(), device_id.clone(), property, &query);
+        if points.is_err() {
+            eprintln!("An error occurred: {:?}", points.err());
+            return HttpResponse::InternalServerError().finish();
+        }
+
+        let points = points.unwrap();
+        if !points.is_empty() {
+            series.push(HistorySeries { property, points });
+        }
+    }
+
+    HttpResponse::Ok().json(DeviceHistoryResponse { status: 200, resolution: Some(resolution), series: Some(series) })
+}
@@ -0,0 +1,4 @@
+pub mod get;
+pub mod update;
+pub mod expose;
+pub mod history;
@@ -0,0 +1,92 @@
+use crate::appdata::AppData;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct UpdateDeviceRequest {
+    session_id:     String,
+    device_id:      String,
+    /// None to use the name reported by the Service
+    name:           Option<String>,
+    #[serde(default)]
+    nicknames:      Vec<String>,
+    /// The ID of the room the Device is in, None if it isn't in a room
+    room_id:        Option<String>
+}
+
+#[derive(Serialize)]
+pub struct UpdateDeviceResponse {
+    status:         i16
+}
+
+/**
+Set the name, nicknames and room of a registered Device. These are kept when the Device is discovered again
+
+## Endpoint
+Path:   /devices/update
+Method: POST
+*/
+#[post("/devices/update")]
+pub async fn post_update(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<UpdateDeviceRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(UpdateDeviceResponse { status: 401 });
+    }
+
+    //Users may only change their own Devices
+    let device = crate::common::device::get_device(data.database.clone(), request_unwrapped.device_id.clone());
+    if device.is_err() {
+        eprintln!("An error occurred: {:?}", device.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user = user_option.unwrap();
+    let device_unwrapped = device.unwrap();
+    if device_unwrapped.is_none() || device_unwrapped.unwrap().user_id != user.user_id {
+        return HttpResponse::NotFound().finish();
+    }
+
+    //The room must be one of the User's rooms
+    if let Some(room_id) = &request_unwrapped.room_id {
+        let rooms = crate::common::room::get_rooms(data.database.clone(), user.user_id.clone());
+        if rooms.is_err() {
+            eprintln!("An error occurred: {:?}", rooms.err());
+            return HttpResponse::InternalServerError().finish();
+        }
+
+        if !rooms.unwrap().iter().any(|room| room.room_id == *room_id) {
+            return HttpResponse::BadRequest().body("Unknown room_id");
+        }
+    }
+
+    //Empty strings mean 'not set'
+    let name = request_unwrapped.name.filter(|name| !name.trim().is_empty());
+    let nicknames: Vec<String> = request_unwrapped.nicknames.into_iter().filter(|nickname| !nickname.trim().is_empty()).collect();
+
+    let update_result = crate::common::device::update_device(data.database.clone(), request_unwrapped.device_id, name, &nicknames, request_unwrapped.room_id);
+    if update_result.is_err() {
+        eprintln!("An error occurred: {:?}", update_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    //Names and rooms are part of what Google knows about the Device
+    crate::common::homegraph::request_sync_in_background(user.user_id);
+    HttpResponse::Ok().json(UpdateDeviceResponse { status: 200 })
+}
@@ -0,0 +1,2 @@
+pub mod save;
+pub mod remove;
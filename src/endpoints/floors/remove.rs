@@ -0,0 +1,64 @@
+use crate::appdata::AppData;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct RemoveFloorRequest {
+    session_id:     String,
+    floor_id:       String
+}
+
+#[derive(Serialize)]
+pub struct RemoveFloorResponse {
+    status:         i16
+}
+
+/**
+Remove a floor of the User. The rooms on it are kept
+
+## Endpoint
+Path:   /floors/remove
+Method: POST
+*/
+#[post("/floors/remove")]
+pub async fn post_remove(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<RemoveFloorRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(RemoveFloorResponse { status: 401 });
+    }
+
+    let floors = crate::common::room::get_floors(data.database.clone(), user_option.unwrap().user_id);
+    if floors.is_err() {
+        eprintln!("An error occurred: {:?}", floors.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    if !floors.unwrap().iter().any(|floor| floor.floor_id == request_unwrapped.floor_id) {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let delete_result = crate::common::room::delete_floor(data.database.clone(), request_unwrapped.floor_id);
+    if delete_result.is_err() {
+        eprintln!("An error occurred: {:?}", delete_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(RemoveFloorResponse { status: 200 })
+}
@@ -0,0 +1,82 @@
+use crate::appdata::AppData;
+use crate::types::room::Floor;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+use rand::Rng;
+
+#[derive(Deserialize)]
+pub struct SaveFloorRequest {
+    session_id:     String,
+    /// None to create a new floor
+    floor_id:       Option<String>,
+    name:           String,
+    #[serde(default)]
+    level:          i32
+}
+
+#[derive(Serialize)]
+pub struct SaveFloorResponse {
+    status:         i16,
+    floor_id:       Option<String>
+}
+
+/**
+Create a floor, or update an existing floor of the User
+
+## Endpoint
+Path:   /floors/save
+Method: POST
+*/
+#[post("/floors/save")]
+pub async fn post_save(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<SaveFloorRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+    if request_unwrapped.name.trim().is_empty() || request_unwrapped.name.len() > 64 {
+        return HttpResponse::BadRequest().body("A name must be between 1 and 64 characters");
+    }
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(SaveFloorResponse { status: 401, floor_id: None });
+    }
+
+    let user = user_option.unwrap();
+
+    //An existing floor must belong to the User
+    if let Some(floor_id) = &request_unwrapped.floor_id {
+        let floors = crate::common::room::get_floors(data.database.clone(), user.user_id.clone());
+        if floors.is_err() {
+            eprintln!("An error occurred: {:?}", floors.err());
+            return HttpResponse::InternalServerError().finish();
+        }
+
+        if !floors.unwrap().iter().any(|floor| floor.floor_id == *floor_id) {
+            return HttpResponse::NotFound().finish();
+        }
+    }
+
+    let floor_id = request_unwrapped.floor_id.unwrap_or_else(|| rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(32).map(char::from).collect());
+    let floor = Floor { floor_id: floor_id.clone(), name: request_unwrapped.name.trim().to_string(), level: request_unwrapped.level };
+
+    let set_result = crate::common::room::set_floor(data.database.clone(), user.user_id, &floor);
+    if set_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(SaveFloorResponse { status: 200, floor_id: Some(floor_id) })
+}
@@ -1,4 +1,16 @@
 pub mod oauth;
 pub mod auth;
 pub mod assistant;
-pub mod services;
\ No newline at end of file
+pub mod services;
+pub mod devices;
+pub mod rooms;
+pub mod floors;
+pub mod scenes;
+pub mod schedules;
+pub mod rules;
+pub mod stream;
+
+pub mod notifications;
+pub mod webhooks;
+pub mod presence;pub mod vacation;
+pub mod open_window;
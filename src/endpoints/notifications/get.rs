@@ -0,0 +1,55 @@
+use crate::appdata::AppData;
+use crate::types::notification::NotificationChannel;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct GetChannelsRequest {
+    session_id:     String
+}
+
+#[derive(Serialize)]
+pub struct GetChannelsResponse {
+    status:         i16,
+    channels:       Option<Vec<NotificationChannel>>
+}
+
+/**
+Get all notification channels of the User
+
+## Endpoint
+Path:   /notifications/get
+Method: POST
+*/
+#[post("/notifications/get")]
+pub async fn post_get(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<GetChannelsRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(GetChannelsResponse { status: 401, channels: None });
+    }
+
+    let channels = crate::common::notification::get_channels(data.database.clone(), user_option.unwrap().user_id);
+    if channels.is_err() {
+        eprintln!("An error occurred: {:?}", channels.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(GetChannelsResponse { status: 200, channels: Some(channels.unwrap()) })
+}
@@ -0,0 +1,4 @@
+pub mod get;
+pub mod save;
+pub mod remove;
+pub mod test;
@@ -0,0 +1,64 @@
+use crate::appdata::AppData;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct RemoveChannelRequest {
+    session_id:     String,
+    channel_id:     String
+}
+
+#[derive(Serialize)]
+pub struct RemoveChannelResponse {
+    status:         i16
+}
+
+/**
+Remove a notification channel of the User
+
+## Endpoint
+Path:   /notifications/remove
+Method: POST
+*/
+#[post("/notifications/remove")]
+pub async fn post_remove(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<RemoveChannelRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(RemoveChannelResponse { status: 401 });
+    }
+
+    let channels = crate::common::notification::get_channels(data.database.clone(), user_option.unwrap().user_id);
+    if channels.is_err() {
+        eprintln!("An error occurred: {:?}", channels.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    if !channels.unwrap().iter().any(|channel| channel.channel_id == request_unwrapped.channel_id) {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let delete_result = crate::common::notification::delete_channel(data.database.clone(), request_unwrapped.channel_id);
+    if delete_result.is_err() {
+        eprintln!("An error occurred: {:?}", delete_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(RemoveChannelResponse { status: 200 })
+}
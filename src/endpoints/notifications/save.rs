@@ -0,0 +1,79 @@
+use crate::appdata::AppData;
+use crate::types::notification::NotificationChannel;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+use rand::Rng;
+
+#[derive(Deserialize)]
+pub struct SaveChannelRequest {
+    session_id:     String,
+    /// channel_id is empty to create a new channel
+    channel:        NotificationChannel
+}
+
+#[derive(Serialize)]
+pub struct SaveChannelResponse {
+    status:         i16,
+    /// The channel as saved, with its ID
+    channel:        Option<NotificationChannel>
+}
+
+/**
+Create a notification channel, or update an existing channel of the User
+
+## Endpoint
+Path:   /notifications/save
+Method: POST
+*/
+#[post("/notifications/save")]
+pub async fn post_save(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<SaveChannelRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+    let validate_result = request_unwrapped.channel.validate();
+    if validate_result.is_err() {
+        return HttpResponse::BadRequest().body(validate_result.err().unwrap());
+    }
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(SaveChannelResponse { status: 401, channel: None });
+    }
+
+    let user = user_option.unwrap();
+    let mut channel = request_unwrapped.channel;
+    if channel.channel_id.is_empty() {
+        channel.channel_id = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(32).map(char::from).collect();
+    } else {
+        let channels = crate::common::notification::get_channels(data.database.clone(), user.user_id.clone());
+        if channels.is_err() {
+            eprintln!("An error occurred: {:?}", channels.err());
+            return HttpResponse::InternalServerError().finish();
+        }
+
+        if !channels.unwrap().iter().any(|existing| existing.channel_id == channel.channel_id) {
+            return HttpResponse::NotFound().finish();
+        }
+    }
+
+    let set_result = crate::common::notification::set_channel(data.database.clone(), user.user_id, &channel);
+    if set_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(SaveChannelResponse { status: 200, channel: Some(channel) })
+}
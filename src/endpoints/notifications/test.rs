@@ -0,0 +1,71 @@
+use crate::appdata::AppData;
+use crate::types::notification::Notification;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct TestChannelRequest {
+    session_id:     String,
+    channel_id:     String
+}
+
+#[derive(Serialize)]
+pub struct TestChannelResponse {
+    status:         i16,
+    /// If the notification was sent
+    sent:           Option<bool>
+}
+
+/**
+Send a test notification through a notification channel of the User
+
+## Endpoint
+Path:   /notifications/test
+Method: POST
+*/
+#[post("/notifications/test")]
+pub async fn post_test(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<TestChannelRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(TestChannelResponse { status: 401, sent: None });
+    }
+
+    let channels = crate::common::notification::get_channels(data.database.clone(), user_option.unwrap().user_id);
+    if channels.is_err() {
+        eprintln!("An error occurred: {:?}", channels.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let channel = channels.unwrap().into_iter().find(|channel| channel.channel_id == request_unwrapped.channel_id);
+    if channel.is_none() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let notification = Notification {
+        title: "Test notification".to_string(),
+        body: "Notifications sent through this channel will arrive here.".to_string(),
+        alert: None,
+        device_id: None
+    };
+
+    let sent = crate::common::notification::send(&channel.unwrap().target, &notification).is_ok();
+
+    HttpResponse::Ok().json(TestChannelResponse { status: 200, sent: Some(sent) })
+}
@@ -5,6 +5,7 @@ use rand::Rng;
 use serde::Serialize;
 use crate::appdata::AppData;
 use crate::environment::Environment;
+use crate::types::event::Event;
 
 #[derive(Serialize)]
 struct TokenResponse {
@@ -125,6 +126,9 @@ pub async fn post_token(data: web::Data<AppData>, req: HttpRequest) -> HttpRespo
             return HttpResponse::InternalServerError().finish();
         }
 
+        //Google exchanges an authorization code when the User links their account
+        data.events.publish(Event::USERLINKEDASSISTANT { user_id });
+
         let token_response = TokenResponse {
             token_type: "Bearer".to_string(),
             access_token,
@@ -0,0 +1,59 @@
+use crate::appdata::AppData;
+use crate::types::open_window::{OpenWindow, OpenWindowDetection};
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct GetOpenWindowRequest {
+    session_id:     String
+}
+
+#[derive(Serialize)]
+pub struct GetOpenWindowResponse {
+    status:         i16,
+    detections:     Option<Vec<OpenWindowDetection>>,
+    /// The windows currently detected as open
+    open_windows:   Option<Vec<OpenWindow>>
+}
+
+/**
+Get the open window detection of all Devices of the User, and the windows currently detected as open
+
+## Endpoint
+Path:   /open_window/get
+Method: POST
+*/
+#[post("/open_window/get")]
+pub async fn post_get(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<GetOpenWindowRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(GetOpenWindowResponse { status: 401, detections: None, open_windows: None });
+    }
+
+    let user = user_option.unwrap();
+    let detections = crate::common::open_window::get_detections(data.database.clone(), user.user_id.clone());
+    let open_windows = crate::common::open_window::get_user_open_windows(data.database.clone(), user.user_id);
+    if detections.is_err() || open_windows.is_err() {
+        eprintln!("An error occurred: {:?} {:?}", detections.err(), open_windows.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(GetOpenWindowResponse { status: 200, detections: Some(detections.unwrap()), open_windows: Some(open_windows.unwrap()) })
+}
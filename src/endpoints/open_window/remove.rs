@@ -0,0 +1,64 @@
+use crate::appdata::AppData;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct RemoveOpenWindowRequest {
+    session_id:     String,
+    device_id:      String
+}
+
+#[derive(Serialize)]
+pub struct RemoveOpenWindowResponse {
+    status:         i16
+}
+
+/**
+Remove the open window detection of a Device of the User. If a window is currently detected as open, its setpoint is restored shortly
+
+## Endpoint
+Path:   /open_window/remove
+Method: POST
+*/
+#[post("/open_window/remove")]
+pub async fn post_remove(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<RemoveOpenWindowRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(RemoveOpenWindowResponse { status: 401 });
+    }
+
+    let detections = crate::common::open_window::get_detections(data.database.clone(), user_option.unwrap().user_id);
+    if detections.is_err() {
+        eprintln!("An error occurred: {:?}", detections.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    if !detections.unwrap().iter().any(|detection| detection.device_id == request_unwrapped.device_id) {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let delete_result = crate::common::open_window::delete_detection(data.database.clone(), request_unwrapped.device_id);
+    if delete_result.is_err() {
+        eprintln!("An error occurred: {:?}", delete_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(RemoveOpenWindowResponse { status: 200 })
+}
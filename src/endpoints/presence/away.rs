@@ -0,0 +1,87 @@
+use crate::appdata::AppData;
+use crate::types::presence::{AwayAction, AwayMode};
+use crate::types::service::ServiceType;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct SaveAwayModeRequest {
+    session_id:     String,
+    away_mode:      AwayMode
+}
+
+#[derive(Serialize)]
+pub struct SaveAwayModeResponse {
+    status:         i16
+}
+
+/**
+Configure what happens when the last person leaves the home, and is undone when the first person returns.
+All Services and Scenes used must belong to the User
+
+## Endpoint
+Path:   /presence/away
+Method: POST
+*/
+#[post("/presence/away")]
+pub async fn post_away(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<SaveAwayModeRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+    let validate_result = request_unwrapped.away_mode.validate();
+    if validate_result.is_err() {
+        return HttpResponse::BadRequest().body(validate_result.err().unwrap());
+    }
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(SaveAwayModeResponse { status: 401 });
+    }
+
+    let user = user_option.unwrap();
+    let services = crate::common::service::get_services(data.database.clone(), user.user_id.clone());
+    let scenes = crate::common::scene::get_scenes(data.database.clone(), user.user_id.clone());
+    if services.is_err() || scenes.is_err() {
+        eprintln!("An error occurred: {:?} {:?}", services.err(), scenes.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let (services, scenes) = (services.unwrap(), scenes.unwrap());
+    for action in &request_unwrapped.away_mode.actions {
+        match action {
+            AwayAction::HONEYWELL { service_id, .. } => {
+                if !services.iter().any(|(id, service_type)| id == service_id && *service_type == ServiceType::HONEYWELL) {
+                    return HttpResponse::BadRequest().body(format!("Unknown Honeywell service_id '{}'", service_id));
+                }
+            },
+            AwayAction::SCENE { away_scene_id, home_scene_id } => {
+                for scene_id in std::iter::once(away_scene_id).chain(home_scene_id.iter()) {
+                    if !scenes.iter().any(|scene| scene.scene_id == *scene_id) {
+                        return HttpResponse::BadRequest().body(format!("Unknown scene_id '{}'", scene_id));
+                    }
+                }
+            }
+        }
+    }
+
+    let set_result = crate::common::presence::set_away_mode(data.database.clone(), user.user_id, &request_unwrapped.away_mode);
+    if set_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(SaveAwayModeResponse { status: 200 })
+}
@@ -0,0 +1,60 @@
+use crate::appdata::AppData;
+use crate::types::presence::{GeofenceTransition, PresenceSource};
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct GeofenceRequest {
+    session_id:     String,
+    /// The person carrying the phone
+    person:         String,
+    transition:     GeofenceTransition
+}
+
+#[derive(Serialize)]
+pub struct GeofenceResponse {
+    status:         i16
+}
+
+/**
+Called by the phone app when it enters or leaves the home zone. The person is added to the household if they're not known yet
+
+## Endpoint
+Path:   /presence/geofence
+Method: POST
+*/
+#[post("/presence/geofence")]
+pub async fn post_geofence(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<GeofenceRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+    if request_unwrapped.person.trim().is_empty() || request_unwrapped.person.len() > 64 {
+        return HttpResponse::BadRequest().body("A person must be between 1 and 64 characters");
+    }
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(GeofenceResponse { status: 401 });
+    }
+
+    let home = request_unwrapped.transition == GeofenceTransition::ENTER;
+    let update_result = crate::common::presence::update_presence(&data, user_option.unwrap().user_id, request_unwrapped.person, home, PresenceSource::GEOFENCE);
+    if update_result.is_err() {
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(GeofenceResponse { status: 200 })
+}
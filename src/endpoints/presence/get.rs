@@ -0,0 +1,59 @@
+use crate::appdata::AppData;
+use crate::types::presence::{AwayMode, Presence};
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct GetPresenceRequest {
+    session_id:     String
+}
+
+#[derive(Serialize)]
+pub struct GetPresenceResponse {
+    status:         i16,
+    presence:       Option<Vec<Presence>>,
+    /// None if the User has not configured away mode
+    away_mode:      Option<AwayMode>
+}
+
+/**
+Get the presence of everyone in the household of the User, and the away mode
+
+## Endpoint
+Path:   /presence/get
+Method: POST
+*/
+#[post("/presence/get")]
+pub async fn post_get(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<GetPresenceRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(GetPresenceResponse { status: 401, presence: None, away_mode: None });
+    }
+
+    let user = user_option.unwrap();
+    let presence = crate::common::presence::get_presence(data.database.clone(), user.user_id.clone());
+    let away_mode = crate::common::presence::get_away_mode(data.database.clone(), user.user_id);
+    if presence.is_err() || away_mode.is_err() {
+        eprintln!("An error occurred: {:?} {:?}", presence.err(), away_mode.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(GetPresenceResponse { status: 200, presence: Some(presence.unwrap()), away_mode: away_mode.unwrap() })
+}
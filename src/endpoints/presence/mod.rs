@@ -0,0 +1,5 @@
+pub mod get;
+pub mod geofence;
+pub mod set;
+pub mod remove;
+pub mod away;
@@ -0,0 +1,72 @@
+use crate::appdata::AppData;
+use crate::types::event::Event;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct RemovePersonRequest {
+    session_id:     String,
+    person:         String
+}
+
+#[derive(Serialize)]
+pub struct RemovePersonResponse {
+    status:         i16
+}
+
+/**
+Remove a person from the household of the User
+
+## Endpoint
+Path:   /presence/remove
+Method: POST
+*/
+#[post("/presence/remove")]
+pub async fn post_remove(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<RemovePersonRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(RemovePersonResponse { status: 401 });
+    }
+
+    let user = user_option.unwrap();
+    let presence = crate::common::presence::get_presence(data.database.clone(), user.user_id.clone());
+    if presence.is_err() {
+        eprintln!("An error occurred: {:?}", presence.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let presence = presence.unwrap().into_iter().find(|presence| presence.person == request_unwrapped.person);
+    if presence.is_none() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let delete_result = crate::common::presence::delete_person(data.database.clone(), user.user_id.clone(), request_unwrapped.person.clone());
+    if delete_result.is_err() {
+        eprintln!("An error occurred: {:?}", delete_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    //Someone who was home is gone now, which could leave the home empty
+    if presence.unwrap().home {
+        data.events.publish(Event::PRESENCECHANGED { user_id: user.user_id, person: request_unwrapped.person, home: false });
+    }
+
+    HttpResponse::Ok().json(RemovePersonResponse { status: 200 })
+}
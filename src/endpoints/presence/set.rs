@@ -0,0 +1,58 @@
+use crate::appdata::AppData;
+use crate::types::presence::PresenceSource;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct SetPresenceRequest {
+    session_id:     String,
+    person:         String,
+    home:           bool
+}
+
+#[derive(Serialize)]
+pub struct SetPresenceResponse {
+    status:         i16
+}
+
+/**
+Set by hand whether a person is home. The person is added to the household if they're not known yet
+
+## Endpoint
+Path:   /presence/set
+Method: POST
+*/
+#[post("/presence/set")]
+pub async fn post_set(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<SetPresenceRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+    if request_unwrapped.person.trim().is_empty() || request_unwrapped.person.len() > 64 {
+        return HttpResponse::BadRequest().body("A person must be between 1 and 64 characters");
+    }
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(SetPresenceResponse { status: 401 });
+    }
+
+    let update_result = crate::common::presence::update_presence(&data, user_option.unwrap().user_id, request_unwrapped.person, request_unwrapped.home, PresenceSource::MANUAL);
+    if update_result.is_err() {
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(SetPresenceResponse { status: 200 })
+}
@@ -0,0 +1,77 @@
+use crate::appdata::AppData;
+use crate::types::room::{Floor, Room};
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct GetRoomsRequest {
+    session_id:     String
+}
+
+#[derive(Serialize)]
+pub struct GetRoomsResponse {
+    status:         i16,
+    floors:         Option<Vec<Floor>>,
+    rooms:          Option<Vec<RoomEntry>>
+}
+
+#[derive(Serialize)]
+pub struct RoomEntry {
+    #[serde(flatten)]
+    room:           Room,
+    /// The internal ID's of the Devices in the room
+    device_ids:     Vec<String>
+}
+
+/**
+Get all floors and rooms of the User, along with the Devices in every room
+
+## Endpoint
+Path:   /rooms/get
+Method: POST
+*/
+#[post("/rooms/get")]
+pub async fn post_get(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<GetRoomsRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let user_result = crate::common::user::get_user(&request.unwrap().session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(GetRoomsResponse { status: 401, floors: None, rooms: None });
+    }
+
+    let user = user_option.unwrap();
+    let floors = crate::common::room::get_floors(data.database.clone(), user.user_id.clone());
+    let rooms = crate::common::room::get_rooms(data.database.clone(), user.user_id.clone());
+    let devices = crate::common::device::get_devices(data.database.clone(), user.user_id);
+    if floors.is_err() || rooms.is_err() || devices.is_err() {
+        eprintln!("An error occurred: {:?} {:?} {:?}", floors.err(), rooms.err(), devices.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let devices = devices.unwrap();
+    let rooms = rooms.unwrap().into_iter()
+        .map(|room| {
+            let device_ids = devices.iter()
+                .filter(|device| device.room_id.as_ref() == Some(&room.room_id))
+                .map(|device| device.device_id.clone())
+                .collect();
+
+            RoomEntry { room, device_ids }
+        })
+        .collect();
+
+    HttpResponse::Ok().json(GetRoomsResponse { status: 200, floors: Some(floors.unwrap()), rooms: Some(rooms) })
+}
@@ -0,0 +1,64 @@
+use crate::appdata::AppData;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct RemoveRoomRequest {
+    session_id:     String,
+    room_id:        String
+}
+
+#[derive(Serialize)]
+pub struct RemoveRoomResponse {
+    status:         i16
+}
+
+/**
+Remove a room of the User. The Devices in it are kept
+
+## Endpoint
+Path:   /rooms/remove
+Method: POST
+*/
+#[post("/rooms/remove")]
+pub async fn post_remove(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<RemoveRoomRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(RemoveRoomResponse { status: 401 });
+    }
+
+    let rooms = crate::common::room::get_rooms(data.database.clone(), user_option.unwrap().user_id);
+    if rooms.is_err() {
+        eprintln!("An error occurred: {:?}", rooms.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    if !rooms.unwrap().iter().any(|room| room.room_id == request_unwrapped.room_id) {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let delete_result = crate::common::room::delete_room(data.database.clone(), request_unwrapped.room_id);
+    if delete_result.is_err() {
+        eprintln!("An error occurred: {:?}", delete_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(RemoveRoomResponse { status: 200 })
+}
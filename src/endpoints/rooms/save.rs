@@ -0,0 +1,87 @@
+use crate::appdata::AppData;
+use crate::types::room::Room;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+use rand::Rng;
+
+#[derive(Deserialize)]
+pub struct SaveRoomRequest {
+    session_id:     String,
+    /// None to create a new room
+    room_id:        Option<String>,
+    name:           String,
+    floor_id:       Option<String>
+}
+
+#[derive(Serialize)]
+pub struct SaveRoomResponse {
+    status:         i16,
+    room_id:        Option<String>
+}
+
+/**
+Create a room, or update an existing room of the User
+
+## Endpoint
+Path:   /rooms/save
+Method: POST
+*/
+#[post("/rooms/save")]
+pub async fn post_save(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<SaveRoomRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+    if request_unwrapped.name.trim().is_empty() || request_unwrapped.name.len() > 64 {
+        return HttpResponse::BadRequest().body("A name must be between 1 and 64 characters");
+    }
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(SaveRoomResponse { status: 401, room_id: None });
+    }
+
+    let user = user_option.unwrap();
+    let rooms = crate::common::room::get_rooms(data.database.clone(), user.user_id.clone());
+    let floors = crate::common::room::get_floors(data.database.clone(), user.user_id.clone());
+    if rooms.is_err() || floors.is_err() {
+        eprintln!("An error occurred: {:?} {:?}", rooms.err(), floors.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    //Existing rooms and floors must belong to the User
+    if let Some(room_id) = &request_unwrapped.room_id {
+        if !rooms.unwrap().iter().any(|room| room.room_id == *room_id) {
+            return HttpResponse::NotFound().finish();
+        }
+    }
+
+    if let Some(floor_id) = &request_unwrapped.floor_id {
+        if !floors.unwrap().iter().any(|floor| floor.floor_id == *floor_id) {
+            return HttpResponse::BadRequest().body("Unknown floor_id");
+        }
+    }
+
+    let room_id = request_unwrapped.room_id.unwrap_or_else(|| rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(32).map(char::from).collect());
+    let room = Room { room_id: room_id.clone(), name: request_unwrapped.name.trim().to_string(), floor_id: request_unwrapped.floor_id };
+
+    let set_result = crate::common::room::set_room(data.database.clone(), user.user_id, &room);
+    if set_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(SaveRoomResponse { status: 200, room_id: Some(room_id) })
+}
@@ -0,0 +1,55 @@
+use crate::appdata::AppData;
+use crate::types::rule::Rule;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct GetRulesRequest {
+    session_id:     String
+}
+
+#[derive(Serialize)]
+pub struct GetRulesResponse {
+    status:         i16,
+    rules:          Option<Vec<Rule>>
+}
+
+/**
+Get all automation Rules of the User
+
+## Endpoint
+Path:   /rules/get
+Method: POST
+*/
+#[post("/rules/get")]
+pub async fn post_get(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<GetRulesRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(GetRulesResponse { status: 401, rules: None });
+    }
+
+    let rules = crate::common::rule::get_rules(data.database.clone(), user_option.unwrap().user_id);
+    if rules.is_err() {
+        eprintln!("An error occurred: {:?}", rules.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(GetRulesResponse { status: 200, rules: Some(rules.unwrap()) })
+}
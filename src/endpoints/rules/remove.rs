@@ -0,0 +1,64 @@
+use crate::appdata::AppData;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct RemoveRuleRequest {
+    session_id:     String,
+    rule_id:        String
+}
+
+#[derive(Serialize)]
+pub struct RemoveRuleResponse {
+    status:         i16
+}
+
+/**
+Remove an automation Rule of the User
+
+## Endpoint
+Path:   /rules/remove
+Method: POST
+*/
+#[post("/rules/remove")]
+pub async fn post_remove(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<RemoveRuleRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(RemoveRuleResponse { status: 401 });
+    }
+
+    let rules = crate::common::rule::get_rules(data.database.clone(), user_option.unwrap().user_id);
+    if rules.is_err() {
+        eprintln!("An error occurred: {:?}", rules.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    if !rules.unwrap().iter().any(|rule| rule.rule_id == request_unwrapped.rule_id) {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let delete_result = crate::common::rule::delete_rule(data.database.clone(), request_unwrapped.rule_id);
+    if delete_result.is_err() {
+        eprintln!("An error occurred: {:?}", delete_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(RemoveRuleResponse { status: 200 })
+}
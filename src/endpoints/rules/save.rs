@@ -0,0 +1,144 @@
+use crate::appdata::AppData;
+use crate::types::rule::{Action, Condition, Rule, Trigger};
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+use rand::Rng;
+
+#[derive(Deserialize)]
+pub struct SaveRuleRequest {
+    session_id:     String,
+    /// rule_id is empty to create a new Rule
+    rule:           Rule
+}
+
+#[derive(Serialize)]
+pub struct SaveRuleResponse {
+    status:         i16,
+    /// The Rule as saved, with its ID and webhook tokens
+    rule:           Option<Rule>
+}
+
+/**
+Create an automation Rule, or update an existing Rule of the User.
+All Devices and Scenes used in the Rule must belong to the User. Webhook triggers keep their token when it is unchanged,
+otherwise a new token is generated
+
+## Endpoint
+Path:   /rules/save
+Method: POST
+*/
+#[post("/rules/save")]
+pub async fn post_save(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<SaveRuleRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+    let validate_result = request_unwrapped.rule.validate();
+    if validate_result.is_err() {
+        return HttpResponse::BadRequest().body(validate_result.err().unwrap());
+    }
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(SaveRuleResponse { status: 401, rule: None });
+    }
+
+    let user = user_option.unwrap();
+    let rules = crate::common::rule::get_rules(data.database.clone(), user.user_id.clone());
+    let devices = crate::common::device::get_devices(data.database.clone(), user.user_id.clone());
+    let scenes = crate::common::scene::get_scenes(data.database.clone(), user.user_id.clone());
+    if rules.is_err() || devices.is_err() || scenes.is_err() {
+        eprintln!("An error occurred: {:?} {:?} {:?}", rules.err(), devices.err(), scenes.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let mut rule = request_unwrapped.rule;
+    let existing = if rule.rule_id.is_empty() {
+        rule.rule_id = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(32).map(char::from).collect();
+        None
+    } else {
+        let existing = rules.unwrap().into_iter().find(|existing| existing.rule_id == rule.rule_id);
+        if existing.is_none() {
+            return HttpResponse::NotFound().finish();
+        }
+
+        existing
+    };
+
+    //Every Device and Scene used must belong to the User
+    let (devices, scenes) = (devices.unwrap(), scenes.unwrap());
+    let mut device_ids: Vec<&String> = vec![];
+    for trigger in &rule.triggers {
+        if let Trigger::DEVICESTATE { device_id, .. } | Trigger::THRESHOLD { device_id, .. } = trigger {
+            device_ids.push(device_id);
+        }
+    }
+
+    for condition in &rule.conditions {
+        if let Condition::DEVICESTATE { device_id, .. } = condition {
+            device_ids.push(device_id);
+        }
+    }
+
+    for device_id in device_ids {
+        if !devices.iter().any(|device| device.device_id == *device_id) {
+            return HttpResponse::BadRequest().body(format!("Unknown device_id '{}'", device_id));
+        }
+    }
+
+    for action in &rule.actions {
+        match action {
+            Action::SETDEVICE { device_id, command } => {
+                let device = devices.iter().find(|device| device.device_id == *device_id);
+                if device.is_none() {
+                    return HttpResponse::BadRequest().body(format!("Unknown device_id '{}'", device_id));
+                }
+
+                if !command.is_supported(&device.unwrap().capabilities) {
+                    return HttpResponse::BadRequest().body(format!("{:?} is not supported by Device '{}'", command, device_id));
+                }
+            },
+            Action::ACTIVATESCENE { scene_id } if !scenes.iter().any(|scene| scene.scene_id == *scene_id) => {
+                return HttpResponse::BadRequest().body(format!("Unknown scene_id '{}'", scene_id));
+            },
+            _ => {}
+        }
+    }
+
+    //Tokens can't be chosen by the client, only kept
+    let existing_tokens: Vec<String> = existing.map(|existing| existing.triggers.into_iter()
+        .filter_map(|trigger| match trigger {
+            Trigger::WEBHOOK { token } => Some(token),
+            _ => None
+        })
+        .collect())
+        .unwrap_or_default();
+
+    for trigger in rule.triggers.iter_mut() {
+        if let Trigger::WEBHOOK { token } = trigger {
+            if !existing_tokens.contains(token) {
+                *token = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(64).map(char::from).collect();
+            }
+        }
+    }
+
+    let set_result = crate::common::rule::set_rule(data.database.clone(), user.user_id, &rule);
+    if set_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(SaveRuleResponse { status: 200, rule: Some(rule) })
+}
@@ -0,0 +1,44 @@
+use crate::appdata::AppData;
+use crate::types::rule::Trigger;
+
+use actix_web::{post, web, HttpResponse};
+use serde::Serialize;
+use std::thread;
+
+#[derive(Serialize)]
+pub struct RuleWebhookResponse {
+    status:         i16
+}
+
+/**
+Fire the webhook trigger of a Rule. No session is needed, the token identifies the Rule.
+The Rule is run in the background, its conditions are checked as usual
+
+## Endpoint
+Path:   /rules/webhook/{token}
+Method: POST
+*/
+#[post("/rules/webhook/{token}")]
+pub async fn post_webhook(data: web::Data<AppData>, token: web::Path<String>) -> HttpResponse {
+    let rules = crate::common::rule::get_enabled_rules(data.database.clone());
+    if rules.is_err() {
+        eprintln!("An error occurred: {:?}", rules.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let token = token.into_inner();
+    let rule = rules.unwrap().into_iter().find(|(_, rule)| rule.triggers.iter().any(|trigger| matches!(trigger, Trigger::WEBHOOK { token: rule_token } if *rule_token == token)));
+    if rule.is_none() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let (user_id, rule) = rule.unwrap();
+    let data = data.get_ref().clone();
+    thread::spawn(move || {
+        if crate::common::rule::run(&data, user_id, &rule, None).is_err() {
+            eprintln!("Unable to run Rule '{}'", rule.rule_id);
+        }
+    });
+
+    HttpResponse::Ok().json(RuleWebhookResponse { status: 200 })
+}
@@ -0,0 +1,68 @@
+use crate::appdata::AppData;
+use crate::types::scene::SceneActionResult;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct ActivateSceneRequest {
+    session_id:     String,
+    scene_id:       String
+}
+
+#[derive(Serialize)]
+pub struct ActivateSceneResponse {
+    status:         i16,
+    results:        Option<Vec<SceneActionResult>>
+}
+
+/**
+Activate a Scene of the User. Returns the result for every Device in the Scene
+
+## Endpoint
+Path:   /scenes/activate
+Method: POST
+*/
+#[post("/scenes/activate")]
+pub async fn post_activate(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<ActivateSceneRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(ActivateSceneResponse { status: 401, results: None });
+    }
+
+    let user = user_option.unwrap();
+    let scenes = crate::common::scene::get_scenes(data.database.clone(), user.user_id.clone());
+    if scenes.is_err() {
+        eprintln!("An error occurred: {:?}", scenes.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let scenes = scenes.unwrap();
+    let scene = scenes.iter().find(|scene| scene.scene_id == request_unwrapped.scene_id);
+    if scene.is_none() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let results = crate::common::scene::activate(&data, user.user_id, scene.unwrap());
+    if results.is_err() {
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(ActivateSceneResponse { status: 200, results: Some(results.unwrap()) })
+}
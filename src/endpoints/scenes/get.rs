@@ -0,0 +1,55 @@
+use crate::appdata::AppData;
+use crate::types::scene::Scene;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct GetScenesRequest {
+    session_id:     String
+}
+
+#[derive(Serialize)]
+pub struct GetScenesResponse {
+    status:         i16,
+    scenes:         Option<Vec<Scene>>
+}
+
+/**
+Get all Scenes of the User
+
+## Endpoint
+Path:   /scenes/get
+Method: POST
+*/
+#[post("/scenes/get")]
+pub async fn post_get(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<GetScenesRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(GetScenesResponse { status: 401, scenes: None });
+    }
+
+    let scenes = crate::common::scene::get_scenes(data.database.clone(), user_option.unwrap().user_id);
+    if scenes.is_err() {
+        eprintln!("An error occurred: {:?}", scenes.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(GetScenesResponse { status: 200, scenes: Some(scenes.unwrap()) })
+}
@@ -0,0 +1,67 @@
+use crate::appdata::AppData;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct RemoveSceneRequest {
+    session_id:     String,
+    scene_id:       String
+}
+
+#[derive(Serialize)]
+pub struct RemoveSceneResponse {
+    status:         i16
+}
+
+/**
+Remove a Scene of the User
+
+## Endpoint
+Path:   /scenes/remove
+Method: POST
+*/
+#[post("/scenes/remove")]
+pub async fn post_remove(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<RemoveSceneRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(RemoveSceneResponse { status: 401 });
+    }
+
+    let user = user_option.unwrap();
+    let scenes = crate::common::scene::get_scenes(data.database.clone(), user.user_id.clone());
+    if scenes.is_err() {
+        eprintln!("An error occurred: {:?}", scenes.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    if !scenes.unwrap().iter().any(|scene| scene.scene_id == request_unwrapped.scene_id) {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let delete_result = crate::common::scene::delete_scene(data.database.clone(), request_unwrapped.scene_id);
+    if delete_result.is_err() {
+        eprintln!("An error occurred: {:?}", delete_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    crate::common::homegraph::request_sync_in_background(user.user_id);
+
+    HttpResponse::Ok().json(RemoveSceneResponse { status: 200 })
+}
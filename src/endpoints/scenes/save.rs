@@ -0,0 +1,101 @@
+use crate::appdata::AppData;
+use crate::types::scene::{Scene, SceneAction};
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+use rand::Rng;
+
+#[derive(Deserialize)]
+pub struct SaveSceneRequest {
+    session_id:     String,
+    /// None to create a new Scene
+    scene_id:       Option<String>,
+    name:           String,
+    actions:        Vec<SceneAction>
+}
+
+#[derive(Serialize)]
+pub struct SaveSceneResponse {
+    status:         i16,
+    scene_id:       Option<String>
+}
+
+/**
+Create a Scene, or update an existing Scene of the User.
+Every action must target a Device of the User which supports the command
+
+## Endpoint
+Path:   /scenes/save
+Method: POST
+*/
+#[post("/scenes/save")]
+pub async fn post_save(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<SaveSceneRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+    if request_unwrapped.name.trim().is_empty() || request_unwrapped.name.len() > 64 {
+        return HttpResponse::BadRequest().body("A name must be between 1 and 64 characters");
+    }
+
+    if request_unwrapped.actions.is_empty() {
+        return HttpResponse::BadRequest().body("A Scene must have at least one action");
+    }
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(SaveSceneResponse { status: 401, scene_id: None });
+    }
+
+    let user = user_option.unwrap();
+    let scenes = crate::common::scene::get_scenes(data.database.clone(), user.user_id.clone());
+    let devices = crate::common::device::get_devices(data.database.clone(), user.user_id.clone());
+    if scenes.is_err() || devices.is_err() {
+        eprintln!("An error occurred: {:?} {:?}", scenes.err(), devices.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    //An existing Scene must belong to the User
+    if let Some(scene_id) = &request_unwrapped.scene_id {
+        if !scenes.unwrap().iter().any(|scene| scene.scene_id == *scene_id) {
+            return HttpResponse::NotFound().finish();
+        }
+    }
+
+    let devices = devices.unwrap();
+    for action in &request_unwrapped.actions {
+        let device = devices.iter().find(|device| device.device_id == action.device_id);
+        if device.is_none() {
+            return HttpResponse::BadRequest().body(format!("Unknown device_id '{}'", action.device_id));
+        }
+
+        if !action.command.is_supported(&device.unwrap().capabilities) {
+            return HttpResponse::BadRequest().body(format!("{:?} is not supported by Device '{}'", action.command, action.device_id));
+        }
+    }
+
+    let scene_id = request_unwrapped.scene_id.unwrap_or_else(|| rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(32).map(char::from).collect());
+    let scene = Scene { scene_id: scene_id.clone(), name: request_unwrapped.name.trim().to_string(), actions: request_unwrapped.actions };
+
+    let set_result = crate::common::scene::set_scene(data.database.clone(), user.user_id.clone(), &scene);
+    if set_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    //Scenes are devices to Google
+    crate::common::homegraph::request_sync_in_background(user.user_id);
+
+    HttpResponse::Ok().json(SaveSceneResponse { status: 200, scene_id: Some(scene_id) })
+}
@@ -0,0 +1,80 @@
+use crate::appdata::AppData;
+use crate::types::service::ServiceType;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct ExportScheduleRequest {
+    session_id:     String,
+    device_id:      String
+}
+
+#[derive(Serialize)]
+pub struct ExportScheduleResponse {
+    status:         i16
+}
+
+/**
+Replace the EvoHome schedule of a Honeywell Zone with the weekly schedule stored for it.
+EvoHome has no exceptions, these are not exported
+
+## Endpoint
+Path:   /schedules/export
+Method: POST
+*/
+#[post("/schedules/export")]
+pub async fn post_export(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<ExportScheduleRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(ExportScheduleResponse { status: 401 });
+    }
+
+    let user = user_option.unwrap();
+    let device = crate::common::device::get_device(data.database.clone(), request_unwrapped.device_id.clone());
+    let schedules = crate::common::schedule::get_schedules(data.database.clone(), user.user_id.clone());
+    if device.is_err() || schedules.is_err() {
+        eprintln!("An error occurred: {:?} {:?}", device.err(), schedules.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let device = device.unwrap();
+    let schedule = schedules.unwrap().into_iter().find(|schedule| schedule.device_id == request_unwrapped.device_id);
+    if device.is_none() || schedule.is_none() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let device = device.unwrap();
+    let service = crate::common::service::get_service(data.database.clone(), device.service_id.clone());
+    if service.is_err() {
+        eprintln!("An error occurred: {:?}", service.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    if !matches!(service.unwrap(), Some((_, ServiceType::HONEYWELL))) {
+        return HttpResponse::BadRequest().body("Schedules can only be exported to Honeywell Devices");
+    }
+
+    let set_result = crate::services::honeywell::set_zone_schedule(data.database.clone(), device.service_id, device.provider_device_id, &schedule.unwrap().days);
+    if set_result.is_err() {
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(ExportScheduleResponse { status: 200 })
+}
@@ -0,0 +1,55 @@
+use crate::appdata::AppData;
+use crate::types::schedule::Schedule;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct GetSchedulesRequest {
+    session_id:     String
+}
+
+#[derive(Serialize)]
+pub struct GetSchedulesResponse {
+    status:         i16,
+    schedules:      Option<Vec<Schedule>>
+}
+
+/**
+Get the heating schedules of all Devices of the User
+
+## Endpoint
+Path:   /schedules/get
+Method: POST
+*/
+#[post("/schedules/get")]
+pub async fn post_get(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<GetSchedulesRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(GetSchedulesResponse { status: 401, schedules: None });
+    }
+
+    let schedules = crate::common::schedule::get_schedules(data.database.clone(), user_option.unwrap().user_id);
+    if schedules.is_err() {
+        eprintln!("An error occurred: {:?}", schedules.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(GetSchedulesResponse { status: 200, schedules: Some(schedules.unwrap()) })
+}
@@ -0,0 +1,95 @@
+use crate::appdata::AppData;
+use crate::types::schedule::Schedule;
+use crate::types::service::ServiceType;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct ImportScheduleRequest {
+    session_id:     String,
+    device_id:      String
+}
+
+#[derive(Serialize)]
+pub struct ImportScheduleResponse {
+    status:         i16,
+    schedule:       Option<Schedule>
+}
+
+/**
+Import the weekly schedule of a Honeywell Zone from EvoHome. Exceptions of an existing schedule are kept.
+A newly imported schedule is disabled, as EvoHome keeps running its own copy until it is enabled
+
+## Endpoint
+Path:   /schedules/import
+Method: POST
+*/
+#[post("/schedules/import")]
+pub async fn post_import(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<ImportScheduleRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(ImportScheduleResponse { status: 401, schedule: None });
+    }
+
+    let user = user_option.unwrap();
+    let device = crate::common::device::get_device(data.database.clone(), request_unwrapped.device_id.clone());
+    let schedules = crate::common::schedule::get_schedules(data.database.clone(), user.user_id.clone());
+    if device.is_err() || schedules.is_err() {
+        eprintln!("An error occurred: {:?} {:?}", device.err(), schedules.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let device = device.unwrap();
+    if device.is_none() || device.as_ref().unwrap().user_id != user.user_id {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let device = device.unwrap();
+    let service = crate::common::service::get_service(data.database.clone(), device.service_id.clone());
+    if service.is_err() {
+        eprintln!("An error occurred: {:?}", service.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    if !matches!(service.unwrap(), Some((_, ServiceType::HONEYWELL))) {
+        return HttpResponse::BadRequest().body("Schedules can only be imported from Honeywell Devices");
+    }
+
+    let days = crate::services::honeywell::get_zone_schedule(data.database.clone(), device.service_id, device.provider_device_id);
+    if days.is_err() {
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let existing = schedules.unwrap().into_iter().find(|schedule| schedule.device_id == request_unwrapped.device_id);
+    let schedule = Schedule {
+        device_id: request_unwrapped.device_id,
+        enabled: existing.as_ref().map(|existing| existing.enabled).unwrap_or(false),
+        days: days.unwrap(),
+        exceptions: existing.map(|existing| existing.exceptions).unwrap_or_default()
+    };
+
+    let set_result = crate::common::schedule::set_schedule(data.database.clone(), user.user_id, &schedule);
+    if set_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(ImportScheduleResponse { status: 200, schedule: Some(schedule) })
+}
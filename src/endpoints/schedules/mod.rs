@@ -0,0 +1,6 @@
+pub mod get;
+pub mod save;
+pub mod remove;
+pub mod import;
+pub mod export;
+pub mod optimal_start;
@@ -0,0 +1,71 @@
+use crate::appdata::AppData;
+use crate::types::optimal_start::HeatingModel;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct GetOptimalStartRequest {
+    session_id:     String
+}
+
+#[derive(Serialize)]
+pub struct GetOptimalStartResponse {
+    status:                 i16,
+    /// The current outdoor temperature the predictions are made for, None if the User has no weather station
+    outdoor_temperature:    Option<f32>,
+    models:                 Option<Vec<OptimalStartModel>>
+}
+
+#[derive(Serialize)]
+pub struct OptimalStartModel {
+    #[serde(flatten)]
+    model:                  HeatingModel,
+    /// How many minutes it currently takes to heat the Zone by one degree, according to the model
+    minutes_per_degree:     f32
+}
+
+/**
+Get what the scheduled Zones of the User learned about how fast they heat up. Zones which have not learned enough yet are left out
+
+## Endpoint
+Path:   /schedules/optimal_start
+Method: POST
+*/
+#[post("/schedules/optimal_start")]
+pub async fn post_optimal_start(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<GetOptimalStartRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(GetOptimalStartResponse { status: 401, outdoor_temperature: None, models: None });
+    }
+
+    let user = user_option.unwrap();
+    let models = crate::common::optimal_start::get_models(data.database.clone(), user.user_id.clone());
+    if models.is_err() {
+        eprintln!("An error occurred: {:?}", models.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let outdoor_temperature = crate::services::get_outdoor_temperature(&data, user.user_id);
+    let models = models.unwrap().into_iter()
+        .map(|model| OptimalStartModel { minutes_per_degree: model.minutes_per_degree(outdoor_temperature), model })
+        .collect();
+
+    HttpResponse::Ok().json(GetOptimalStartResponse { status: 200, outdoor_temperature, models: Some(models) })
+}
@@ -0,0 +1,64 @@
+use crate::appdata::AppData;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct RemoveScheduleRequest {
+    session_id:     String,
+    device_id:      String
+}
+
+#[derive(Serialize)]
+pub struct RemoveScheduleResponse {
+    status:         i16
+}
+
+/**
+Remove the heating schedule of a Device of the User. The current setpoint of the Device is kept
+
+## Endpoint
+Path:   /schedules/remove
+Method: POST
+*/
+#[post("/schedules/remove")]
+pub async fn post_remove(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<RemoveScheduleRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(RemoveScheduleResponse { status: 401 });
+    }
+
+    let schedules = crate::common::schedule::get_schedules(data.database.clone(), user_option.unwrap().user_id);
+    if schedules.is_err() {
+        eprintln!("An error occurred: {:?}", schedules.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    if !schedules.unwrap().iter().any(|schedule| schedule.device_id == request_unwrapped.device_id) {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let delete_result = crate::common::schedule::delete_schedule(data.database.clone(), request_unwrapped.device_id);
+    if delete_result.is_err() {
+        eprintln!("An error occurred: {:?}", delete_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(RemoveScheduleResponse { status: 200 })
+}
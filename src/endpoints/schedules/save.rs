@@ -0,0 +1,76 @@
+use crate::appdata::AppData;
+use crate::types::schedule::Schedule;
+use crate::types::device::DeviceCapability;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct SaveScheduleRequest {
+    session_id:     String,
+    schedule:       Schedule
+}
+
+#[derive(Serialize)]
+pub struct SaveScheduleResponse {
+    status:         i16
+}
+
+/**
+Create or replace the heating schedule of a Device of the User. The Device must have a temperature setpoint
+
+## Endpoint
+Path:   /schedules/save
+Method: POST
+*/
+#[post("/schedules/save")]
+pub async fn post_save(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<SaveScheduleRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+    let validate_result = request_unwrapped.schedule.validate();
+    if validate_result.is_err() {
+        return HttpResponse::BadRequest().body(validate_result.err().unwrap());
+    }
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(SaveScheduleResponse { status: 401 });
+    }
+
+    let user = user_option.unwrap();
+    let device = crate::common::device::get_device(data.database.clone(), request_unwrapped.schedule.device_id.clone());
+    if device.is_err() {
+        eprintln!("An error occurred: {:?}", device.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let device = device.unwrap();
+    if device.is_none() || device.as_ref().unwrap().user_id != user.user_id {
+        return HttpResponse::NotFound().finish();
+    }
+
+    if !device.unwrap().capabilities.contains(&DeviceCapability::TEMPERATURESETTING) {
+        return HttpResponse::BadRequest().body("The Device does not have a temperature setpoint");
+    }
+
+    let set_result = crate::common::schedule::set_schedule(data.database.clone(), user.user_id, &request_unwrapped.schedule);
+    if set_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(SaveScheduleResponse { status: 200 })
+}
@@ -1,20 +1,35 @@
 use actix_web::{web, post, HttpResponse};
 use crate::appdata::AppData;
-use crate::types::service::{Service, ServiceType, PasswordProtectedService};
+use crate::types::service::{Service, ServiceType, ServiceAppearance, PasswordProtectedService};
 use serde::{Serialize, Deserialize};
-use crate::common::service::Credentials;
+use crate::types::home_assistant::{HomeAssistantService, HomeAssistantSettings};
+use crate::types::modbus::ModbusService;
+use crate::types::knx::KnxService;
+use crate::types::http::HttpService;
+use crate::types::virtual_devices::VirtualService;
+use crate::common::service::{Credentials, TokenCredentials};
+use crate::types::event::Event;
 use rand::Rng;
 
 #[derive(Serialize)]
 pub struct AddServiceResponse {
-    status:         i16,
-    service_id:     Option<String>
+    status:             i16,
+    service_id:         Option<String>,
+
+    /// For Services using the OAUTH login method, the URL the user should be sent to.
+    /// The Service is created once the user has authorized us
+    #[serde(skip_serializing_if = "Option::is_none")]
+    authorization_url:  Option<String>
 }
 
 #[derive(Deserialize)]
 pub struct AddServiceRequest<T> {
     session_id:     String,
-    service:        T
+    service:        T,
+
+    /// Label, icon and colour of the Service. For OAUTH Services these are set with /services/update once the Service is created
+    #[serde(default)]
+    appearance:     ServiceAppearance
 }
 
 #[post("/services/add")]
@@ -45,13 +60,17 @@ pub async fn post_add(data: web::Data<AppData>, bytes: web::Bytes) -> HttpRespon
 
     //If user_option is None, the user doesn't exist, so we return a status 401
     if user_option.is_none() {
-        let response = AddServiceResponse { status: 401, service_id: None };
+        let response = AddServiceResponse { status: 401, service_id: None, authorization_url: None };
         return HttpResponse::Ok().json(response);
     }
 
     //Unwrap the Option<> into a User
     let user = user_option.unwrap();
 
+    if let Err(message) = req_unwrapped.appearance.validate() {
+        return HttpResponse::BadRequest().body(message);
+    }
+
     //Get the Service object from the request payload
     let service = req_unwrapped.service.clone();
 
@@ -76,14 +95,14 @@ pub async fn post_add(data: web::Data<AppData>, bytes: web::Bytes) -> HttpRespon
 
             //Check if any errors occurred
             if login_response.is_err() {
-                let response = AddServiceResponse { status: 600, service_id: None };
+                let response = AddServiceResponse { status: 600, service_id: None, authorization_url: None };
                 return HttpResponse::Ok().json(response);
             }
 
             //Check if the login was successful
             //If the value is None, it wasn't
             if login_response.unwrap().is_none() {
-                let response = AddServiceResponse { status: 700, service_id: None };
+                let response = AddServiceResponse { status: 700, service_id: None, authorization_url: None };
                 return HttpResponse::Ok().json(response);
             }
 
@@ -104,9 +123,257 @@ pub async fn post_add(data: web::Data<AppData>, bytes: web::Bytes) -> HttpRespon
                 return HttpResponse::InternalServerError().finish();
             }
 
-            //Finally, formulate a response
-            let response = AddServiceResponse { status: 200, service_id: Some(service_id) };
-            return HttpResponse::Ok().json(response);
+            //Finally, store the label, icon and colour and formulate a response
+            service_created(&data, user.user_id, service_id, ServiceType::HONEYWELL, &req_unwrapped.appearance)
+        },
+        ServiceType::HOME_ASSISTANT => {
+
+            //Deserialize the payload again, this time into a HomeAssistantService,
+            //since Home Assistant takes a host and a long-lived access token
+            let add_home_assistant_service = serde_json::from_slice::<AddServiceRequest<HomeAssistantService>>(body_unwrapped.as_bytes());
+            if add_home_assistant_service.is_err() {
+                eprintln!("Unable to deserialize request payload as HOME_ASSISTANT");
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            let service = add_home_assistant_service.unwrap().service.clone();
+            let host = service.host.trim_end_matches('/').to_string();
+
+            //Validate the host and token
+            let login_response = crate::services::home_assistant::do_test_login(host.clone(), service.token.clone());
+            if login_response.is_err() {
+                let response = AddServiceResponse { status: 600, service_id: None, authorization_url: None };
+                return HttpResponse::Ok().json(response);
+            }
+
+            if login_response.unwrap().is_none() {
+                let response = AddServiceResponse { status: 700, service_id: None, authorization_url: None };
+                return HttpResponse::Ok().json(response);
+            }
+
+            let service_id: String = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(64).map(char::from).collect();
+
+            let add_service_result = crate::common::service::create_service(data.database.clone(), user.user_id.clone(), service_id.clone(), ServiceType::HOME_ASSISTANT);
+            if add_service_result.is_err() {
+                eprintln!("An error occurred: {:?}", add_service_result.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            let set_credentials_response = crate::common::service::set_token_credentials(data.database.clone(), service_id.clone(), TokenCredentials { host, token: service.token.clone() });
+            if set_credentials_response.is_err() {
+                eprintln!("An error occurred: {:?}", set_credentials_response.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            //Store which entities should be imported
+            let settings = HomeAssistantSettings { entities: service.entities.unwrap_or_default() };
+            let set_settings_response = crate::common::service::set_settings(data.database.clone(), service_id.clone(), &settings);
+            if set_settings_response.is_err() {
+                eprintln!("An error occurred: {:?}", set_settings_response.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            service_created(&data, user.user_id, service_id, ServiceType::HOME_ASSISTANT, &req_unwrapped.appearance)
+        },
+        ServiceType::TADO | ServiceType::NETATMO => {
+            start_oauth_flow(&data, user.user_id, service.service_type)
+        },
+        ServiceType::MODBUS => {
+
+            //Deserialize the payload again, this time into a ModbusService,
+            //since Modbus devices are described by a register map
+            let add_modbus_service = serde_json::from_slice::<AddServiceRequest<ModbusService>>(body_unwrapped.as_bytes());
+            if let Err(e) = &add_modbus_service {
+                eprintln!("Unable to deserialize request payload as MODBUS: {:?}", e);
+                return HttpResponse::BadRequest().body(format!("Invalid MODBUS service: {}", e));
+            }
+
+            let settings = add_modbus_service.unwrap().service.settings;
+
+            if let Err(message) = crate::services::modbus::validate(&settings) {
+                return HttpResponse::BadRequest().body(message);
+            }
+
+            //Check that the Modbus host is reachable
+            if crate::services::modbus::do_test_connection(&settings).is_err() {
+                let response = AddServiceResponse { status: 600, service_id: None, authorization_url: None };
+                return HttpResponse::Ok().json(response);
+            }
+
+            let service_id: String = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(64).map(char::from).collect();
+
+            let add_service_result = crate::common::service::create_service(data.database.clone(), user.user_id.clone(), service_id.clone(), ServiceType::MODBUS);
+            if add_service_result.is_err() {
+                eprintln!("An error occurred: {:?}", add_service_result.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            let set_settings_response = crate::common::service::set_settings(data.database.clone(), service_id.clone(), &settings);
+            if set_settings_response.is_err() {
+                eprintln!("An error occurred: {:?}", set_settings_response.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            service_created(&data, user.user_id, service_id, ServiceType::MODBUS, &req_unwrapped.appearance)
+        },
+        ServiceType::KNX => {
+
+            //Deserialize the payload again, this time into a KnxService,
+            //since KNX devices are described by their group addresses
+            let add_knx_service = serde_json::from_slice::<AddServiceRequest<KnxService>>(body_unwrapped.as_bytes());
+            if let Err(e) = &add_knx_service {
+                eprintln!("Unable to deserialize request payload as KNX: {:?}", e);
+                return HttpResponse::BadRequest().body(format!("Invalid KNX service: {}", e));
+            }
+
+            let settings = add_knx_service.unwrap().service.settings;
+
+            if let Err(message) = crate::services::knx::validate(&settings) {
+                return HttpResponse::BadRequest().body(message);
+            }
+
+            let service_id: String = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(64).map(char::from).collect();
+
+            let add_service_result = crate::common::service::create_service(data.database.clone(), user.user_id.clone(), service_id.clone(), ServiceType::KNX);
+            if add_service_result.is_err() {
+                eprintln!("An error occurred: {:?}", add_service_result.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            let set_settings_response = crate::common::service::set_settings(data.database.clone(), service_id.clone(), &settings);
+            if set_settings_response.is_err() {
+                eprintln!("An error occurred: {:?}", set_settings_response.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            //Open the tunnel right away, so the state is known by the time the user syncs
+            let _ = crate::services::knx::ensure_tunnel(&data.knx_tunnels, data.database.clone(), service_id.clone());
+
+            service_created(&data, user.user_id, service_id, ServiceType::KNX, &req_unwrapped.appearance)
+        },
+        ServiceType::HTTP => {
+
+            //Deserialize the payload again, this time into a HttpService,
+            //since HTTP devices refer to a definition in services.jsonc
+            let add_http_service = serde_json::from_slice::<AddServiceRequest<HttpService>>(body_unwrapped.as_bytes());
+            if add_http_service.is_err() {
+                eprintln!("Unable to deserialize request payload as HTTP: {:?}", add_http_service.err());
+                return HttpResponse::BadRequest().finish();
+            }
+
+            let settings = add_http_service.unwrap().service.settings;
+
+            let definitions = crate::config::get_service_config(&data.get_services_configs(), &ServiceType::HTTP)
+                .and_then(|config| config.http_definitions.clone())
+                .unwrap_or_default();
+
+            if let Err(message) = crate::services::http::validate(&settings, &definitions) {
+                return HttpResponse::BadRequest().body(message);
+            }
+
+            let service_id: String = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(64).map(char::from).collect();
+
+            let add_service_result = crate::common::service::create_service(data.database.clone(), user.user_id.clone(), service_id.clone(), ServiceType::HTTP);
+            if add_service_result.is_err() {
+                eprintln!("An error occurred: {:?}", add_service_result.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            let set_settings_response = crate::common::service::set_settings(data.database.clone(), service_id.clone(), &settings);
+            if set_settings_response.is_err() {
+                eprintln!("An error occurred: {:?}", set_settings_response.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            service_created(&data, user.user_id, service_id, ServiceType::HTTP, &req_unwrapped.appearance)
+        },
+        ServiceType::VIRTUAL => {
+
+            //Deserialize the payload again, this time into a VirtualService,
+            //since the user chooses which devices should be simulated
+            let add_virtual_service = serde_json::from_slice::<AddServiceRequest<VirtualService>>(body_unwrapped.as_bytes());
+            if add_virtual_service.is_err() {
+                eprintln!("Unable to deserialize request payload as VIRTUAL: {:?}", add_virtual_service.err());
+                return HttpResponse::BadRequest().finish();
+            }
+
+            let mut settings = add_virtual_service.unwrap().service.settings;
+
+            //Device ID's must be unique
+            let mut device_ids: Vec<&String> = settings.devices.iter().map(|device| &device.id).collect();
+            device_ids.sort();
+            device_ids.dedup();
+
+            if device_ids.len() != settings.devices.len() {
+                return HttpResponse::BadRequest().body("Duplicate device id");
+            }
+
+            //Every simulated device starts in its initial state
+            let now = chrono::Utc::now().timestamp();
+            for device in settings.devices.iter_mut() {
+                device.state = crate::services::virtual_devices::initial_state(&device.kind);
+                device.updated_at = now;
+            }
+
+            let service_id: String = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(64).map(char::from).collect();
+
+            let add_service_result = crate::common::service::create_service(data.database.clone(), user.user_id.clone(), service_id.clone(), ServiceType::VIRTUAL);
+            if add_service_result.is_err() {
+                eprintln!("An error occurred: {:?}", add_service_result.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            let set_settings_response = crate::common::service::set_settings(data.database.clone(), service_id.clone(), &settings);
+            if set_settings_response.is_err() {
+                eprintln!("An error occurred: {:?}", set_settings_response.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            service_created(&data, user.user_id, service_id, ServiceType::VIRTUAL, &req_unwrapped.appearance)
         }
     }
+}
+
+/**
+Store the appearance of a newly created Service, announce it and respond with its ID
+*/
+fn service_created(data: &AppData, user_id: String, service_id: String, service_type: ServiceType, appearance: &ServiceAppearance) -> HttpResponse {
+    let set_appearance_result = crate::common::service::set_appearance(data.database.clone(), service_id.clone(), appearance);
+    if set_appearance_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_appearance_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    data.events.publish(Event::SERVICEADDED { user_id, service_id: service_id.clone(), service_type });
+
+    let response = AddServiceResponse { status: 200, service_id: Some(service_id), authorization_url: None };
+    HttpResponse::Ok().json(response)
+}
+
+/**
+Start the OAuth authorization flow for a Service using the OAUTH login method.
+The Service is created in the callback, once the user has authorized us
+*/
+fn start_oauth_flow(data: &AppData, user_id: String, service_type: ServiceType) -> HttpResponse {
+    let services_configs = data.get_services_configs();
+    let config = crate::config::get_service_config(&services_configs, &service_type);
+    if config.is_none() || config.unwrap().oauth.is_none() {
+        eprintln!("No OAuth configuration found for {}", service_type);
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let state: String = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(64).map(char::from).collect();
+    let authorization_url = crate::common::oauth::get_authorization_url(config.unwrap().oauth.as_ref().unwrap(), &state);
+    if authorization_url.is_err() {
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let create_state_result = crate::common::oauth::create_state(data.database.clone(), state, user_id, service_type.to_string());
+    if create_state_result.is_err() {
+        eprintln!("An error occurred: {:?}", create_state_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let response = AddServiceResponse { status: 200, service_id: None, authorization_url: Some(authorization_url.unwrap()) };
+    HttpResponse::Ok().json(response)
 }
\ No newline at end of file
@@ -0,0 +1,69 @@
+use crate::appdata::AppData;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct ExposeServiceRequest {
+    session_id:     String,
+    service_id:     String,
+    exposed:        bool
+}
+
+#[derive(Serialize)]
+pub struct ExposeServiceResponse {
+    status:         i16
+}
+
+/**
+Set if the Devices of a Service are exposed to Google Assistant. Google is asked to re-sync the User's Devices
+
+## Endpoint
+Path:   /services/expose
+Method: POST
+*/
+#[post("/services/expose")]
+pub async fn post_expose(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<ExposeServiceRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(ExposeServiceResponse { status: 401 });
+    }
+
+    //Users may only change their own Services
+    let user = user_option.unwrap();
+    let service = crate::common::service::get_service(data.database.clone(), request_unwrapped.service_id.clone());
+    if service.is_err() {
+        eprintln!("An error occurred: {:?}", service.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let service_unwrapped = service.unwrap();
+    if service_unwrapped.is_none() || service_unwrapped.unwrap().0 != user.user_id {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let set_result = crate::common::service::set_exposed(data.database.clone(), request_unwrapped.service_id, request_unwrapped.exposed);
+    if set_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    crate::common::homegraph::request_sync_in_background(user.user_id);
+    HttpResponse::Ok().json(ExposeServiceResponse { status: 200 })
+}
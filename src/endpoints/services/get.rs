@@ -1,5 +1,6 @@
 use crate::appdata::AppData;
 use crate::config::ServicesConfig;
+use crate::types::service::ServiceAppearance;
 
 use actix_web::{post, web, HttpResponse};
 use serde::{Serialize, Deserialize};
@@ -20,6 +21,9 @@ pub struct GetUserServiceResponse {
 pub struct UserService {
     service_id:     String,
     config:         ServicesConfig,
+    appearance:     ServiceAppearance,
+    /// If the Devices of the Service are exposed to Google Assistant
+    exposed:        bool
 }
 
 #[derive(Deserialize)]
@@ -60,7 +64,7 @@ pub async fn post_get(data: web::Data<AppData>, bytes: web::Bytes) -> HttpRespon
     if request_unwrapped.only_owned {
         let user = user_option.unwrap();
 
-        let all_user_services_result = crate::common::service::get_services(data.database.clone(), user.user_id);
+        let all_user_services_result = crate::common::service::get_services(data.database.clone(), user.user_id.clone());
         if all_user_services_result.is_err() {
             eprintln!("An error occurred: {:?}", all_user_services_result.err());
             return HttpResponse::InternalServerError().finish();
@@ -68,19 +72,36 @@ pub async fn post_get(data: web::Data<AppData>, bytes: web::Bytes) -> HttpRespon
 
         let services = all_user_services_result.unwrap();
 
+        let unexposed_services = crate::common::service::get_unexposed_services(data.database.clone(), user.user_id.clone());
+        if unexposed_services.is_err() {
+            eprintln!("An error occurred: {:?}", unexposed_services.err());
+            return HttpResponse::InternalServerError().finish();
+        }
+
+        let unexposed_services = unexposed_services.unwrap();
+
         let mut result: Vec<UserService> = vec![];
         for item in services {
             let service_id = item.0.as_str();
             let service_type = item.1;
 
-            let service_configs = data.services_configs.clone();
-            for config in service_configs {
+            let appearance = crate::common::service::get_appearance(data.database.clone(), service_id.to_string());
+            if appearance.is_err() {
+                eprintln!("An error occurred: {:?}", appearance.err());
+                return HttpResponse::InternalServerError().finish();
+            }
+
+            let appearance = appearance.unwrap();
+            let service_configs = data.get_services_configs();
+            for config in service_configs.iter().cloned() {
                 if config.identifier == service_type {
                     let service_id_clone = service_id.to_string().clone();
 
                     result.push(UserService {
                         service_id: service_id_clone,
-                        config
+                        config,
+                        appearance: appearance.clone(),
+                        exposed: !unexposed_services.iter().any(|unexposed| unexposed == service_id)
                     })
                 }
             }
@@ -95,7 +116,7 @@ pub async fn post_get(data: web::Data<AppData>, bytes: web::Bytes) -> HttpRespon
     } else {
         let response = GetServicesResponse {
             status: 200,
-            services: Some(data.services_configs.clone())
+            services: Some(data.get_services_configs().to_vec())
         };
 
         return HttpResponse::Ok().json(response);
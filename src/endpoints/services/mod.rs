@@ -1,2 +1,7 @@
 pub mod add;
-pub mod get;
\ No newline at end of file
+pub mod get;
+pub mod oauth_callback;
+pub mod outdoor_temperature;
+pub mod update;
+pub mod expose;
+pub mod remove;
@@ -0,0 +1,105 @@
+use crate::appdata::AppData;
+use crate::environment::Environment;
+use crate::types::service::ServiceType;
+use crate::types::event::Event;
+
+use actix_web::{get, web, HttpResponse, HttpRequest};
+use qstring::QString;
+use rand::Rng;
+use std::str::FromStr;
+use tera::Context;
+
+/**
+Endpoint OAuth providers redirect to after the user authorized us to access a Service.
+Exchanges the authorization code for tokens, creates the Service and redirects the user back to the frontend
+
+## Endpoint
+Path:   /services/oauth/callback
+Method: GET
+
+## Parameters
+| Name  | Type   | Description                                                   |
+|-------|--------|---------------------------------------------------------------|
+| code  | String | The authorization code                                        |
+| state | String | The state we passed to the provider when starting the flow    |
+| error | String | Set by the provider if the user denied access                 |
+*/
+#[get("/services/oauth/callback")]
+pub async fn get_oauth_callback(data: web::Data<AppData>, req: HttpRequest) -> HttpResponse {
+    let qstring = QString::from(req.query_string());
+
+    let state_param = qstring.get("state");
+    if state_param.is_none() {
+        return HttpResponse::BadRequest().body("Missing parameter 'state'");
+    }
+
+    //Look up, and remove, the pending authorization
+    let pending = crate::common::oauth::take_state(data.database.clone(), state_param.unwrap().to_string());
+    if pending.is_err() {
+        eprintln!("An error occurred: {:?}", pending.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let pending_unwrapped = pending.unwrap();
+    if pending_unwrapped.is_none() {
+        return HttpResponse::BadRequest().body("Value of parameter 'state' is invalid.");
+    }
+
+    let (user_id, identifier) = pending_unwrapped.unwrap();
+
+    //The user denied us access
+    if let Some(error) = qstring.get("error") {
+        return redirect_to_frontend(&data, &QString::new(vec![("error", error)]).to_string());
+    }
+
+    let code_param = qstring.get("code");
+    if code_param.is_none() {
+        return HttpResponse::BadRequest().body("Missing parameter 'code'");
+    }
+
+    let service_type = ServiceType::from_str(&identifier).unwrap();
+    let services_configs = data.get_services_configs();
+    let config = crate::config::get_service_config(&services_configs, &service_type);
+    if config.is_none() || config.unwrap().oauth.is_none() {
+        eprintln!("No OAuth configuration found for {}", service_type);
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    //Exchange the authorization code for tokens
+    let tokens = crate::common::oauth::exchange_code(config.unwrap().oauth.as_ref().unwrap(), code_param.unwrap());
+    if tokens.is_err() {
+        return redirect_to_frontend(&data, "error=token_exchange_failed");
+    }
+
+    let service_id: String = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(64).map(char::from).collect();
+
+    let create_service_result = crate::common::service::create_service(data.database.clone(), user_id.clone(), service_id.clone(), service_type.clone());
+    if create_service_result.is_err() {
+        eprintln!("An error occurred: {:?}", create_service_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let set_tokens_result = crate::common::oauth::set_tokens(data.database.clone(), service_id.clone(), tokens.unwrap());
+    if set_tokens_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_tokens_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    data.events.publish(Event::SERVICEADDED { user_id, service_id: service_id.clone(), service_type });
+
+    redirect_to_frontend(&data, &format!("service_id={}", service_id))
+}
+
+fn redirect_to_frontend(data: &AppData, query: &str) -> HttpResponse {
+    let env = Environment::new();
+    let redirect_uri = format!("{host}/static/services/services.html?{query}",
+        host    = env.host,
+        query   = query
+    );
+
+    let mut ctx = Context::new();
+    ctx.insert("redirect_uri", &redirect_uri);
+
+    let rendered = data.tera.render("redirect.html", &ctx).unwrap();
+    HttpResponse::Ok().body(rendered)
+}
@@ -0,0 +1,44 @@
+use crate::appdata::AppData;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct OutdoorTemperatureRequest {
+    session_id:     String
+}
+
+#[derive(Serialize)]
+pub struct OutdoorTemperatureResponse {
+    status:         i16,
+
+    /// The outdoor temperature in °C, null if the User has no weather station with an outdoor module
+    temperature:    Option<f32>
+}
+
+#[post("/services/outdoor_temperature")]
+pub async fn post_outdoor_temperature(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<OutdoorTemperatureRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(OutdoorTemperatureResponse { status: 401, temperature: None });
+    }
+
+    let temperature = crate::services::get_outdoor_temperature(&data, user_option.unwrap().user_id);
+    HttpResponse::Ok().json(OutdoorTemperatureResponse { status: 200, temperature })
+}
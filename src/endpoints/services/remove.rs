@@ -0,0 +1,74 @@
+use crate::appdata::AppData;
+use crate::types::event::Event;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct RemoveServiceRequest {
+    session_id:     String,
+    service_id:     String
+}
+
+#[derive(Serialize)]
+pub struct RemoveServiceResponse {
+    status:         i16
+}
+
+/**
+Remove a Service of the User, along with its credentials and Devices
+
+## Endpoint
+Path:   /services/remove
+Method: POST
+*/
+#[post("/services/remove")]
+pub async fn post_remove(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<RemoveServiceRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(RemoveServiceResponse { status: 401 });
+    }
+
+    //Users may only remove their own Services
+    let user = user_option.unwrap();
+    let service = crate::common::service::get_service(data.database.clone(), request_unwrapped.service_id.clone());
+    if service.is_err() {
+        eprintln!("An error occurred: {:?}", service.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let service_unwrapped = service.unwrap();
+    if service_unwrapped.is_none() || service_unwrapped.unwrap().0 != user.user_id {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let delete_result = crate::common::service::delete_service(data.database.clone(), request_unwrapped.service_id.clone());
+    if delete_result.is_err() {
+        eprintln!("An error occurred: {:?}", delete_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let device_ids = delete_result.unwrap();
+
+    crate::services::knx::stop_tunnel(&data.knx_tunnels, &request_unwrapped.service_id);
+
+    data.events.publish(Event::SERVICEREMOVED { user_id: user.user_id, service_id: request_unwrapped.service_id, device_ids });
+
+    HttpResponse::Ok().json(RemoveServiceResponse { status: 200 })
+}
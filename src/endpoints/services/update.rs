@@ -0,0 +1,71 @@
+use crate::appdata::AppData;
+use crate::types::service::ServiceAppearance;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct UpdateServiceRequest {
+    session_id:     String,
+    service_id:     String,
+    appearance:     ServiceAppearance
+}
+
+#[derive(Serialize)]
+pub struct UpdateServiceResponse {
+    status:         i16
+}
+
+/**
+Change the label, icon and colour of a Service owned by the User
+
+## Endpoint
+Path:   /services/update
+Method: POST
+*/
+#[post("/services/update")]
+pub async fn post_update(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<UpdateServiceRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+    if let Err(message) = request_unwrapped.appearance.validate() {
+        return HttpResponse::BadRequest().body(message);
+    }
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(UpdateServiceResponse { status: 401 });
+    }
+
+    //Users may only change their own Services
+    let service = crate::common::service::get_service(data.database.clone(), request_unwrapped.service_id.clone());
+    if service.is_err() {
+        eprintln!("An error occurred: {:?}", service.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let service_unwrapped = service.unwrap();
+    if service_unwrapped.is_none() || service_unwrapped.unwrap().0 != user_option.unwrap().user_id {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let set_appearance_result = crate::common::service::set_appearance(data.database.clone(), request_unwrapped.service_id, &request_unwrapped.appearance);
+    if set_appearance_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_appearance_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(UpdateServiceResponse { status: 200 })
+}
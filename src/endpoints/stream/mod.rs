@@ -0,0 +1,2 @@
+pub mod ws;
+pub mod sse;
@@ -0,0 +1,60 @@
+use crate::appdata::AppData;
+use crate::types::stream::StreamMessage;
+
+use actix_web::{get, web, HttpResponse, HttpRequest, Error};
+use futures::stream::{self, StreamExt};
+use std::time::Duration;
+
+/// Comments are sent this often, so proxies don't close an idle stream
+const KEEPALIVE_SECONDS: u64 = 30;
+
+/**
+Stream live events of the User as Server-Sent Events, for clients which can't use the WebSocket.
+The session is passed as query parameter 'session_id'. A stream is resumed from the 'Last-Event-ID' header,
+which browsers send when reconnecting, or from the query parameter 'last_event_id'
+
+## Endpoint
+Path:   /stream/sse
+Method: GET
+*/
+#[get("/stream/sse")]
+pub async fn get_sse(data: web::Data<AppData>, req: HttpRequest) -> HttpResponse {
+    let qstring = qstring::QString::from(req.query_string());
+
+    let session_id = qstring.get("session_id");
+    if session_id.is_none() {
+        return HttpResponse::BadRequest().body("Missing query parameter 'session_id'");
+    }
+
+    let user_result = crate::common::user::get_user(session_id.unwrap(), &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    let last_event_id = req.headers().get("Last-Event-ID")
+        .and_then(|header| header.to_str().ok())
+        .or_else(|| qstring.get("last_event_id"))
+        .and_then(|id| id.parse::<u64>().ok());
+
+    let (missed, receiver) = data.stream.subscribe(&user_option.unwrap().user_id, last_event_id);
+
+    let events = stream::iter(missed).chain(receiver).map(|message| to_event(&message));
+    let keepalive = actix_rt::time::interval(Duration::from_secs(KEEPALIVE_SECONDS)).map(|_| ": keepalive\n\n".to_string());
+
+    let body = stream::select(events, keepalive).map(|chunk| Ok::<web::Bytes, Error>(web::Bytes::from(chunk)));
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .header("Cache-Control", "no-cache")
+        .streaming(body)
+}
+
+fn to_event(message: &StreamMessage) -> String {
+    format!("id: {}\nevent: {}\ndata: {}\n\n", message.id, message.event.name(), serde_json::to_string(&message.event).unwrap())
+}
@@ -0,0 +1,96 @@
+use crate::appdata::AppData;
+
+use actix_codec::{Decoder, Encoder};
+use actix_http::ws::{self, Codec, Frame, Message};
+use actix_web::{get, web, HttpResponse, HttpRequest, Error};
+use futures::channel::mpsc::unbounded;
+use futures::stream::{self, StreamExt};
+use std::time::Duration;
+
+/// The client is pinged this often, so proxies don't close an idle connection
+const PING_SECONDS: u64 = 30;
+
+/**
+Stream live events of the User over a WebSocket. Every event is sent as a JSON text message with an 'id' and an 'event'.
+The session is passed as query parameter 'session_id', since browsers can't set headers on a WebSocket.
+A stream is resumed by passing the last received id as query parameter 'last_event_id'
+
+## Endpoint
+Path:   /stream/ws
+Method: GET
+*/
+#[get("/stream/ws")]
+pub async fn get_ws(data: web::Data<AppData>, req: HttpRequest, mut payload: web::Payload) -> HttpResponse {
+    let qstring = qstring::QString::from(req.query_string());
+
+    let session_id = qstring.get("session_id");
+    if session_id.is_none() {
+        return HttpResponse::BadRequest().body("Missing query parameter 'session_id'");
+    }
+
+    let user_result = crate::common::user::get_user(session_id.unwrap(), &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    let response = ws::handshake(req.head());
+    if response.is_err() {
+        return HttpResponse::BadRequest().body(format!("{}", response.err().unwrap()));
+    }
+
+    let last_event_id = qstring.get("last_event_id").and_then(|id| id.parse::<u64>().ok());
+    let (missed, receiver) = data.stream.subscribe(&user_option.unwrap().user_id, last_event_id);
+
+    //Messages from the client are only read to answer pings and close frames
+    let (control_sender, control_receiver) = unbounded::<Message>();
+    actix_rt::spawn(async move {
+        let mut codec = Codec::new();
+        let mut buffer = web::BytesMut::new();
+
+        while let Some(Ok(chunk)) = payload.next().await {
+            buffer.extend_from_slice(&chunk);
+
+            while let Ok(Some(frame)) = codec.decode(&mut buffer) {
+                match frame {
+                    Frame::Ping(ping) => {
+                        let _ = control_sender.unbounded_send(Message::Pong(ping));
+                    },
+                    Frame::Close(reason) => {
+                        let _ = control_sender.unbounded_send(Message::Close(reason));
+                        return;
+                    },
+                    _ => {}
+                }
+            }
+        }
+    });
+
+    let events = stream::iter(missed).chain(receiver).map(|message| Message::Text(serde_json::to_string(&message).unwrap()));
+    let pings = actix_rt::time::interval(Duration::from_secs(PING_SECONDS)).map(|_| Message::Ping(web::Bytes::new()));
+    let messages = stream::select(control_receiver, stream::select(events, pings));
+
+    //The stream ends after the close frame, which closes the connection
+    let mut codec = Codec::new();
+    let body = messages
+        .scan(false, |closed, message| {
+            if *closed {
+                return futures::future::ready(None);
+            }
+
+            *closed = matches!(message, Message::Close(_));
+            futures::future::ready(Some(message))
+        })
+        .map(move |message| {
+            let mut buffer = web::BytesMut::new();
+            codec.encode(message, &mut buffer)?;
+            Ok::<web::Bytes, Error>(buffer.freeze())
+        });
+
+    response.unwrap().streaming(body)
+}
@@ -0,0 +1,56 @@
+use crate::appdata::AppData;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct CancelVacationRequest {
+    session_id:     String
+}
+
+#[derive(Serialize)]
+pub struct CancelVacationResponse {
+    status:         i16
+}
+
+/**
+Cancel the vacation of the User. A vacation which has started ends right away, restoring all held Devices
+
+## Endpoint
+Path:   /vacation/cancel
+Method: POST
+*/
+#[post("/vacation/cancel")]
+pub async fn post_cancel(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<CancelVacationRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(CancelVacationResponse { status: 401 });
+    }
+
+    let cancel_result = crate::common::vacation::cancel(&data, user_option.unwrap().user_id);
+    if cancel_result.is_err() {
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    if !cancel_result.unwrap() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    HttpResponse::Ok().json(CancelVacationResponse { status: 200 })
+}
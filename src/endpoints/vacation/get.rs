@@ -0,0 +1,56 @@
+use crate::appdata::AppData;
+use crate::types::vacation::Vacation;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct GetVacationRequest {
+    session_id:     String
+}
+
+#[derive(Serialize)]
+pub struct GetVacationResponse {
+    status:         i16,
+    /// None if the User has no vacation planned
+    vacation:       Option<Vacation>
+}
+
+/**
+Get the vacation of the User, along with the Devices it holds and when they are restored
+
+## Endpoint
+Path:   /vacation/get
+Method: POST
+*/
+#[post("/vacation/get")]
+pub async fn post_get(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<GetVacationRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(GetVacationResponse { status: 401, vacation: None });
+    }
+
+    let vacation = crate::common::vacation::get_vacation(data.database.clone(), user_option.unwrap().user_id);
+    if vacation.is_err() {
+        eprintln!("An error occurred: {:?}", vacation.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(GetVacationResponse { status: 200, vacation: vacation.unwrap() })
+}
@@ -0,0 +1,85 @@
+use crate::appdata::AppData;
+use crate::types::vacation::{Vacation, VacationStatus};
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct SaveVacationRequest {
+    session_id:     String,
+    vacation:       Vacation
+}
+
+#[derive(Serialize)]
+pub struct SaveVacationResponse {
+    status:         i16
+}
+
+/**
+Plan a vacation, or change a planned vacation. Of a vacation which has already started only the end can be changed
+
+## Endpoint
+Path:   /vacation/save
+Method: POST
+*/
+#[post("/vacation/save")]
+pub async fn post_save(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<SaveVacationRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+    let validate_result = request_unwrapped.vacation.validate();
+    if validate_result.is_err() {
+        return HttpResponse::BadRequest().body(validate_result.err().unwrap());
+    }
+
+    if request_unwrapped.vacation.end_timestamp().unwrap() <= chrono::Utc::now().timestamp() {
+        return HttpResponse::BadRequest().body("A vacation must end in the future");
+    }
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(SaveVacationResponse { status: 401 });
+    }
+
+    let user = user_option.unwrap();
+    let current = crate::common::vacation::get_vacation(data.database.clone(), user.user_id.clone());
+    if current.is_err() {
+        eprintln!("An error occurred: {:?}", current.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    //The fields set by the server are never taken from the request
+    let vacation = match current.unwrap() {
+        Some(current) if current.status == VacationStatus::ACTIVE => {
+            if current.start != request_unwrapped.vacation.start || current.frost_setpoint != request_unwrapped.vacation.frost_setpoint {
+                return HttpResponse::BadRequest().body("The vacation has started, only its end can be changed");
+            }
+
+            Vacation { end: request_unwrapped.vacation.end, ..current }
+        },
+        _ => Vacation { status: VacationStatus::SCHEDULED, held: vec![], hot_water_services: vec![], ..request_unwrapped.vacation }
+    };
+
+    let set_result = crate::common::vacation::set_vacation(data.database.clone(), user.user_id.clone(), &vacation);
+    if set_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    //The vacation mode is synced to Google, so it can be cancelled from the Assistant
+    crate::common::homegraph::request_sync_in_background(user.user_id);
+
+    HttpResponse::Ok().json(SaveVacationResponse { status: 200 })
+}
@@ -0,0 +1,65 @@
+use crate::appdata::AppData;
+use crate::types::webhook::WebhookDelivery;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+/// The number of deliveries returned when the client doesn't ask for a number
+const DEFAULT_LIMIT: u32 = 50;
+/// The most deliveries returned at once
+const MAX_LIMIT: u32 = 500;
+
+#[derive(Deserialize)]
+pub struct GetDeliveriesRequest {
+    session_id:         String,
+    /// Only get the deliveries of this subscription
+    subscription_id:    Option<String>,
+    limit:              Option<u32>
+}
+
+#[derive(Serialize)]
+pub struct GetDeliveriesResponse {
+    status:             i16,
+    deliveries:         Option<Vec<WebhookDelivery>>
+}
+
+/**
+Get the most recent webhook deliveries of the User, with the response to every attempt.
+Deliveries are kept for a week
+
+## Endpoint
+Path:   /webhooks/deliveries
+Method: POST
+*/
+#[post("/webhooks/deliveries")]
+pub async fn post_deliveries(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<GetDeliveriesRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(GetDeliveriesResponse { status: 401, deliveries: None });
+    }
+
+    let limit = request_unwrapped.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let deliveries = crate::common::webhook::get_deliveries(data.database.clone(), user_option.unwrap().user_id, request_unwrapped.subscription_id, limit);
+    if deliveries.is_err() {
+        eprintln!("An error occurred: {:?}", deliveries.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(GetDeliveriesResponse { status: 200, deliveries: Some(deliveries.unwrap()) })
+}
@@ -0,0 +1,55 @@
+use crate::appdata::AppData;
+use crate::types::webhook::WebhookSubscription;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct GetSubscriptionsRequest {
+    session_id:     String
+}
+
+#[derive(Serialize)]
+pub struct GetSubscriptionsResponse {
+    status:         i16,
+    subscriptions:  Option<Vec<WebhookSubscription>>
+}
+
+/**
+Get all webhook subscriptions of the User
+
+## Endpoint
+Path:   /webhooks/get
+Method: POST
+*/
+#[post("/webhooks/get")]
+pub async fn post_get(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<GetSubscriptionsRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(GetSubscriptionsResponse { status: 401, subscriptions: None });
+    }
+
+    let subscriptions = crate::common::webhook::get_subscriptions(data.database.clone(), user_option.unwrap().user_id);
+    if subscriptions.is_err() {
+        eprintln!("An error occurred: {:?}", subscriptions.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(GetSubscriptionsResponse { status: 200, subscriptions: Some(subscriptions.unwrap()) })
+}
@@ -0,0 +1,64 @@
+use crate::appdata::AppData;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+pub struct RemoveSubscriptionRequest {
+    session_id:         String,
+    subscription_id:    String
+}
+
+#[derive(Serialize)]
+pub struct RemoveSubscriptionResponse {
+    status:             i16
+}
+
+/**
+Remove a webhook subscription of the User, along with its delivery log
+
+## Endpoint
+Path:   /webhooks/remove
+Method: POST
+*/
+#[post("/webhooks/remove")]
+pub async fn post_remove(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<RemoveSubscriptionRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(RemoveSubscriptionResponse { status: 401 });
+    }
+
+    let subscriptions = crate::common::webhook::get_subscriptions(data.database.clone(), user_option.unwrap().user_id);
+    if subscriptions.is_err() {
+        eprintln!("An error occurred: {:?}", subscriptions.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    if !subscriptions.unwrap().iter().any(|subscription| subscription.subscription_id == request_unwrapped.subscription_id) {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let delete_result = crate::common::webhook::delete_subscription(data.database.clone(), request_unwrapped.subscription_id);
+    if delete_result.is_err() {
+        eprintln!("An error occurred: {:?}", delete_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(RemoveSubscriptionResponse { status: 200 })
+}
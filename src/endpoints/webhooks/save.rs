@@ -0,0 +1,98 @@
+use crate::appdata::AppData;
+use crate::types::webhook::WebhookSubscription;
+
+use actix_web::{post, web, HttpResponse};
+use serde::{Serialize, Deserialize};
+use rand::Rng;
+
+#[derive(Deserialize)]
+pub struct SaveSubscriptionRequest {
+    session_id:     String,
+    /// subscription_id is empty to create a new subscription
+    subscription:   WebhookSubscription
+}
+
+#[derive(Serialize)]
+pub struct SaveSubscriptionResponse {
+    status:         i16,
+    /// The subscription as saved, with its ID and secret
+    subscription:   Option<WebhookSubscription>
+}
+
+/**
+Create a webhook subscription, or update an existing subscription of the User.
+A secret is generated for new subscriptions, existing subscriptions keep theirs
+
+## Endpoint
+Path:   /webhooks/save
+Method: POST
+*/
+#[post("/webhooks/save")]
+pub async fn post_save(data: web::Data<AppData>, bytes: web::Bytes) -> HttpResponse {
+    let body = String::from_utf8(bytes.to_vec());
+    let body_unwrapped = body.unwrap();
+
+    let request = serde_json::from_str::<SaveSubscriptionRequest>(&body_unwrapped);
+    if request.is_err() {
+        return HttpResponse::BadRequest().body(request.err().unwrap().to_string());
+    }
+
+    let request_unwrapped = request.unwrap();
+    let validate_result = request_unwrapped.subscription.validate();
+    if validate_result.is_err() {
+        return HttpResponse::BadRequest().body(validate_result.err().unwrap());
+    }
+
+    let user_result = crate::common::user::get_user(&request_unwrapped.session_id, &data);
+    if user_result.is_err() {
+        eprintln!("An error occurred: {:?}", user_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let user_option = user_result.unwrap();
+    if user_option.is_none() {
+        return HttpResponse::Ok().json(SaveSubscriptionResponse { status: 401, subscription: None });
+    }
+
+    let user = user_option.unwrap();
+    let devices = crate::common::device::get_devices(data.database.clone(), user.user_id.clone());
+    if devices.is_err() {
+        eprintln!("An error occurred: {:?}", devices.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let mut subscription = request_unwrapped.subscription;
+    let devices = devices.unwrap();
+    for device_id in &subscription.device_ids {
+        if !devices.iter().any(|device| device.device_id == *device_id) {
+            return HttpResponse::BadRequest().body(format!("Unknown device_id '{}'", device_id));
+        }
+    }
+
+    if subscription.subscription_id.is_empty() {
+        subscription.subscription_id = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(32).map(char::from).collect();
+        subscription.secret = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(64).map(char::from).collect();
+    } else {
+        let subscriptions = crate::common::webhook::get_subscriptions(data.database.clone(), user.user_id.clone());
+        if subscriptions.is_err() {
+            eprintln!("An error occurred: {:?}", subscriptions.err());
+            return HttpResponse::InternalServerError().finish();
+        }
+
+        let existing = subscriptions.unwrap().into_iter().find(|existing| existing.subscription_id == subscription.subscription_id);
+        if existing.is_none() {
+            return HttpResponse::NotFound().finish();
+        }
+
+        //Secrets can't be chosen by the client, only kept
+        subscription.secret = existing.unwrap().secret;
+    }
+
+    let set_result = crate::common::webhook::set_subscription(data.database.clone(), user.user_id, &subscription);
+    if set_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_result.err());
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok().json(SaveSubscriptionResponse { status: 200, subscription: Some(subscription) })
+}
@@ -23,7 +23,20 @@ pub struct Environment {
     /// Google things
     //pub google_client_id:           String,
     //pub google_client_secret:       String,
-    pub assistant_project_id:       String
+    #[allow(dead_code)]
+    pub assistant_project_id:       String,
+    /// Path to the JSON key of the Google service account used for the HomeGraph API. Optional,
+    /// without it Google is not asked to re-sync when the User's devices change
+    pub google_service_account_key: Option<String>,
+
+    /// The SMTP server used to send email notifications. Optional, without it email notifications are not sent
+    pub smtp_host:                  Option<String>,
+    /// Defaults to 587, with STARTTLS
+    pub smtp_port:                  Option<u16>,
+    pub smtp_username:              Option<String>,
+    pub smtp_password:              Option<String>,
+    /// The address email notifications are sent from
+    pub smtp_from:                  Option<String>
 }
 
 impl Environment {
@@ -86,7 +99,14 @@ impl Environment {
             host:                       host.unwrap(),
 
 
-            assistant_project_id:       assistant_project_id.unwrap()
+            assistant_project_id:       assistant_project_id.unwrap(),
+            google_service_account_key: env::var("GOOGLE_SERVICE_ACCOUNT_KEY").ok(),
+
+            smtp_host:                  env::var("SMTP_HOST").ok(),
+            smtp_port:                  env::var("SMTP_PORT").ok().and_then(|port| port.parse::<u16>().ok()),
+            smtp_username:              env::var("SMTP_USERNAME").ok(),
+            smtp_password:              env::var("SMTP_PASSWORD").ok(),
+            smtp_from:                  env::var("SMTP_FROM").ok()
             //google_client_id:           google_client_id.unwrap()
         }
     }
@@ -0,0 +1,107 @@
+use crate::types::device::DeviceState;
+use crate::types::event::Event;
+use crate::types::service::ServiceType;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+/**
+In-process publish/subscribe for Events. Every subscriber receives every Event, in the order they were published
+*/
+#[derive(Clone, Default)]
+pub struct EventBus {
+    inner:          Arc<Mutex<BusState>>
+}
+
+#[derive(Default)]
+struct BusState {
+    subscribers:    Vec<Sender<Event>>,
+    /// Last known state per Device, to only publish changes
+    device_states:  HashMap<String, DeviceState>,
+    /// Last known health per Service, to only publish changes
+    service_health: HashMap<String, bool>,
+    /// Services of which the credentials were rejected, to publish this once until they work again
+    auth_failed:    HashSet<String>
+}
+
+impl EventBus {
+
+    /**
+    Subscribe to all Events published from now on. Subscribers are expected to consume their receiver in a thread of their own
+
+    ## Returns
+        The receiving end. Dropping it unsubscribes
+    */
+    pub fn subscribe(&self) -> Receiver<Event> {
+        let (sender, receiver) = channel();
+        self.inner.lock().unwrap().subscribers.push(sender);
+
+        receiver
+    }
+
+    /**
+    Publish an Event to all subscribers. The last known state of a removed Service and its Devices is forgotten
+    */
+    pub fn publish(&self, event: Event) {
+        let mut state = self.inner.lock().unwrap();
+        if let Event::SERVICEREMOVED { service_id, device_ids, .. } = &event {
+            state.service_health.remove(service_id);
+            state.auth_failed.remove(service_id);
+            for device_id in device_ids {
+                state.device_states.remove(device_id);
+            }
+        }
+
+        state.subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+
+    /**
+    Publish DEVICESTATECHANGED if the state of a Device differs from when it was last seen
+    */
+    pub fn publish_device_state(&self, user_id: &str, device_id: &str, device_state: &DeviceState) {
+        let previous = {
+            let mut state = self.inner.lock().unwrap();
+            let previous = state.device_states.get(device_id).cloned();
+            if previous.as_ref() == Some(device_state) {
+                return;
+            }
+
+            state.device_states.insert(device_id.to_string(), device_state.clone());
+            previous
+        };
+
+        self.publish(Event::DEVICESTATECHANGED { user_id: user_id.to_string(), device_id: device_id.to_string(), previous, state: device_state.clone() });
+    }
+
+    /**
+    Publish SERVICEHEALTHCHANGED if the health of a Service differs from when it was last seen
+    */
+    pub fn publish_service_health(&self, user_id: &str, service_id: &str, healthy: bool) {
+        {
+            let mut state = self.inner.lock().unwrap();
+            if healthy {
+                state.auth_failed.remove(service_id);
+            }
+
+            if state.service_health.get(service_id) == Some(&healthy) {
+                return;
+            }
+
+            state.service_health.insert(service_id.to_string(), healthy);
+        }
+
+        self.publish(Event::SERVICEHEALTHCHANGED { user_id: user_id.to_string(), service_id: service_id.to_string(), healthy });
+    }
+
+    /**
+    Publish SERVICEAUTHFAILED, unless it was already published since the Service last worked
+    */
+    pub fn publish_service_auth_failed(&self, user_id: &str, service_id: &str, service_type: &ServiceType) {
+        if !self.inner.lock().unwrap().auth_failed.insert(service_id.to_string()) {
+            return;
+        }
+
+        self.publish(Event::SERVICEAUTHFAILED { user_id: user_id.to_string(), service_id: service_id.to_string(), service_type: service_type.clone() });
+    }
+}
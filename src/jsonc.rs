@@ -0,0 +1,411 @@
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use std::fmt;
+
+/**
+A position in a JSONC document. Lines and columns start at 1
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub line:       usize,
+    pub column:     usize
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    pub position:   Position,
+    pub message:    String
+}
+
+/**
+A parsed JSONC document, along with the position of every value in the source
+*/
+pub struct Document {
+    pub value:      Value,
+    positions:      HashMap<String, Position>
+}
+
+impl Document {
+
+    /**
+    Get the position of a value in the source
+
+    ## Parameters
+        path: Path of the value, e.g. '[2].oauth.client_id'. The root is ''
+
+    ## Returns
+        None: If there is no value at the path
+        Some: The position of the value. For object members, the position of the key
+    */
+    pub fn position(&self, path: &str) -> Option<Position> {
+        self.positions.get(path).copied()
+    }
+}
+
+/**
+Parse a JSONC document: JSON with line (//) and block (/* */) comments, and trailing commas in objects and arrays
+
+## Parameters
+    input: The document
+
+## Returns
+    Err: The first syntax error in the document
+    Ok: The parsed document
+*/
+pub fn parse(input: &str) -> Result<Document, ParseError> {
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        index: 0,
+        position: Position { line: 1, column: 1 },
+        positions: HashMap::new()
+    };
+
+    parser.skip_whitespace()?;
+    let value = parser.parse_value(String::new())?;
+
+    parser.skip_whitespace()?;
+    if parser.peek().is_some() {
+        return Err(parser.error("Unexpected content after the end of the document"));
+    }
+
+    Ok(Document { value, positions: parser.positions })
+}
+
+struct Parser {
+    chars:          Vec<char>,
+    index:          usize,
+    position:       Position,
+    positions:      HashMap<String, Position>
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.index += 1;
+
+        if c == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        }
+
+        Some(c)
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError { position: self.position, message: message.to_string() }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.next();
+                Ok(())
+            },
+            Some(c) => Err(self.error(&format!("Expected '{}', found '{}'", expected, c))),
+            None => Err(self.error(&format!("Expected '{}', found the end of the document", expected)))
+        }
+    }
+
+    /**
+    Skip whitespace and comments
+    */
+    fn skip_whitespace(&mut self) -> Result<(), ParseError> {
+        loop {
+            match (self.peek(), self.chars.get(self.index + 1).copied()) {
+                (Some(c), _) if c.is_whitespace() => {
+                    self.next();
+                },
+                (Some('/'), Some('/')) => {
+                    while let Some(c) = self.next() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                },
+                (Some('/'), Some('*')) => {
+                    let start = self.position;
+                    self.next();
+                    self.next();
+
+                    loop {
+                        match self.next() {
+                            Some('*') if self.peek() == Some('/') => {
+                                self.next();
+                                break;
+                            },
+                            Some(_) => {},
+                            None => return Err(ParseError { position: start, message: "Unterminated block comment".to_string() })
+                        }
+                    }
+                },
+                _ => return Ok(())
+            }
+        }
+    }
+
+    fn parse_value(&mut self, path: String) -> Result<Value, ParseError> {
+        self.positions.entry(path.clone()).or_insert(self.position);
+
+        match self.peek() {
+            Some('{') => self.parse_object(path),
+            Some('[') => self.parse_array(path),
+            Some('"') => self.parse_string().map(Value::String),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(c) if c.is_alphabetic() => self.parse_literal(),
+            Some(c) => Err(self.error(&format!("Unexpected character '{}'", c))),
+            None => Err(self.error("Unexpected end of the document"))
+        }
+    }
+
+    fn parse_object(&mut self, path: String) -> Result<Value, ParseError> {
+        self.expect('{')?;
+        let mut map = Map::new();
+
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some('}') {
+                self.next();
+                return Ok(Value::Object(map));
+            }
+
+            if self.peek() != Some('"') {
+                return Err(self.error("Expected a member name"));
+            }
+
+            let key_position = self.position;
+            let key = self.parse_string()?;
+            if map.contains_key(&key) {
+                return Err(ParseError { position: key_position, message: format!("Duplicate member '{}'", key) });
+            }
+
+            let member_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+            self.positions.insert(member_path.clone(), key_position);
+
+            self.skip_whitespace()?;
+            self.expect(':')?;
+            self.skip_whitespace()?;
+
+            let value = self.parse_value(member_path)?;
+            map.insert(key, value);
+
+            //Either the next member, or the end of the object. A trailing comma is allowed
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(',') => {
+                    self.next();
+                },
+                Some('}') => {},
+                _ => return Err(self.error("Expected ',' or '}'"))
+            }
+        }
+    }
+
+    fn parse_array(&mut self, path: String) -> Result<Value, ParseError> {
+        self.expect('[')?;
+        let mut values: Vec<Value> = vec![];
+
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some(']') {
+                self.next();
+                return Ok(Value::Array(values));
+            }
+
+            let value = self.parse_value(format!("{}[{}]", path, values.len()))?;
+            values.push(value);
+
+            //Either the next element, or the end of the array. A trailing comma is allowed
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(',') => {
+                    self.next();
+                },
+                Some(']') => {},
+                _ => return Err(self.error("Expected ',' or ']'"))
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        let start = self.position;
+        self.expect('"')?;
+
+        let mut string = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => {
+                    let escaped = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.parse_unicode_escape()?,
+                        _ => return Err(self.error("Invalid escape sequence"))
+                    };
+
+                    string.push(escaped);
+                },
+                Some(c) if c.is_control() => return Err(self.error("Control characters must be escaped in strings")),
+                Some(c) => string.push(c),
+                None => return Err(ParseError { position: start, message: "Unterminated string".to_string() })
+            }
+        }
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, ParseError> {
+        let high = self.parse_hex4()?;
+
+        //Characters outside the BMP are escaped as a surrogate pair
+        if (0xD800..0xDC00).contains(&high) {
+            if self.next() != Some('\\') || self.next() != Some('u') {
+                return Err(self.error("Expected a low surrogate"));
+            }
+
+            let low = self.parse_hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("Invalid low surrogate"));
+            }
+
+            let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+            return char::from_u32(code).ok_or_else(|| self.error("Invalid unicode escape"));
+        }
+
+        char::from_u32(high).ok_or_else(|| self.error("Invalid unicode escape"))
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, ParseError> {
+        let mut hex = String::new();
+        for _ in 0..4 {
+            match self.next() {
+                Some(c) if c.is_ascii_hexdigit() => hex.push(c),
+                _ => return Err(self.error("Expected 4 hexadecimal digits"))
+            }
+        }
+
+        Ok(u32::from_str_radix(&hex, 16).unwrap())
+    }
+
+    fn parse_number(&mut self) -> Result<Value, ParseError> {
+        let start = self.position;
+
+        let mut number = String::new();
+        while let Some(c) = self.peek() {
+            if !(c.is_ascii_digit() || c == '-' || c == '+' || c == '.' || c == 'e' || c == 'E') {
+                break;
+            }
+
+            number.push(c);
+            self.next();
+        }
+
+        //serde_json applies the JSON number grammar
+        let parsed = serde_json::from_str::<Number>(&number);
+        if parsed.is_err() {
+            return Err(ParseError { position: start, message: format!("Invalid number '{}'", number) });
+        }
+
+        Ok(Value::Number(parsed.unwrap()))
+    }
+
+    fn parse_literal(&mut self) -> Result<Value, ParseError> {
+        let start = self.position;
+
+        let mut literal = String::new();
+        while let Some(c) = self.peek() {
+            if !c.is_alphanumeric() {
+                break;
+            }
+
+            literal.push(c);
+            self.next();
+        }
+
+        match literal.as_str() {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            "null" => Ok(Value::Null),
+            _ => Err(ParseError { position: start, message: format!("Unexpected '{}'", literal) })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn error_at(input: &str) -> (Position, String) {
+        let error = parse(input).err().unwrap();
+        (error.position, error.message)
+    }
+
+    #[test]
+    fn comments_are_skipped() {
+        let input = "// Services\n{\n    \"a\": /* inline */ 1, // after a value\n    /* a block\n       over lines */\n    \"b\": [2 /* x */, 3]\n}\n// end";
+        assert_eq!(parse(input).unwrap().value, json!({ "a": 1, "b": [2, 3] }));
+    }
+
+    #[test]
+    fn comment_markers_in_strings_are_kept() {
+        let document = parse(r#"{"url": "https://example.com//path", "glob": "/* not a comment */"}"#).unwrap();
+        assert_eq!(document.value, json!({ "url": "https://example.com//path", "glob": "/* not a comment */" }));
+    }
+
+    #[test]
+    fn escaped_quotes_do_not_end_strings() {
+        let document = parse(r#"{"a": "say \"hi\" // still a string", "b": "\\", "c": "é😀"}"#).unwrap();
+        assert_eq!(document.value, json!({ "a": "say \"hi\" // still a string", "b": "\\", "c": "é😀" }));
+    }
+
+    #[test]
+    fn trailing_commas_are_allowed() {
+        assert_eq!(parse("[1, 2,]").unwrap().value, json!([1, 2]));
+        assert_eq!(parse("{\"a\": {\"b\": true,},}").unwrap().value, json!({ "a": { "b": true } }));
+        assert_eq!(error_at("[1,,2]"), (Position { line: 1, column: 4 }, "Unexpected character ','".to_string()));
+        assert_eq!(error_at("[,]"), (Position { line: 1, column: 2 }, "Unexpected character ','".to_string()));
+    }
+
+    #[test]
+    fn errors_report_their_position() {
+        assert_eq!(error_at("{\"a\": 1 \"b\": 2}"), (Position { line: 1, column: 9 }, "Expected ',' or '}'".to_string()));
+        assert_eq!(error_at("{\n  \"a\": tru\n}"), (Position { line: 2, column: 8 }, "Unexpected 'tru'".to_string()));
+        assert_eq!(error_at("{\"a\": 1, \"a\": 2}"), (Position { line: 1, column: 10 }, "Duplicate member 'a'".to_string()));
+        assert_eq!(error_at("{\"a\": 01}"), (Position { line: 1, column: 7 }, "Invalid number '01'".to_string()));
+        assert_eq!(error_at("{}\n{}"), (Position { line: 2, column: 1 }, "Unexpected content after the end of the document".to_string()));
+    }
+
+    #[test]
+    fn unterminated_tokens_report_where_they_start() {
+        assert_eq!(error_at("[1, /* never closed"), (Position { line: 1, column: 5 }, "Unterminated block comment".to_string()));
+        assert_eq!(error_at("{\n\"a\": \"abc"), (Position { line: 2, column: 6 }, "Unterminated string".to_string()));
+        assert_eq!(error_at("[1,"), (Position { line: 1, column: 4 }, "Unexpected end of the document".to_string()));
+    }
+
+    #[test]
+    fn positions_of_values_are_recorded() {
+        let document = parse("[\n  {\n    // comment\n    \"oauth\": { \"client_id\": \"x\" },\n    \"list\": [1, 2]\n  }\n]").unwrap();
+        assert_eq!(document.position(""), Some(Position { line: 1, column: 1 }));
+        assert_eq!(document.position("[0]"), Some(Position { line: 2, column: 3 }));
+        assert_eq!(document.position("[0].oauth"), Some(Position { line: 4, column: 5 }));
+        assert_eq!(document.position("[0].oauth.client_id"), Some(Position { line: 4, column: 16 }));
+        assert_eq!(document.position("[0].list[1]"), Some(Position { line: 5, column: 17 }));
+        assert_eq!(document.position("[1]"), None);
+    }
+}
@@ -1,3 +1,4 @@
+
 #[macro_use] extern crate magic_crypt;
 
 mod environment;
@@ -9,12 +10,19 @@ mod types;
 mod services;
 mod common;
 mod config;
+mod jsonc;
+mod stream;
+mod events;
 
 use crate::database::Database;
 use crate::appdata::AppData;
+use crate::stream::StreamHub;
+use crate::events::EventBus;
 
 use tera::Tera;
 use actix_web::{HttpServer, App};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()>{
@@ -24,11 +32,43 @@ async fn main() -> std::io::Result<()>{
 
     let oauth_credentials = config::get_oauth_credentials(&database);
     let services_configs = config::read_services_conf();
+    if services_configs.is_err() {
+        for error in services_configs.err().unwrap() {
+            eprintln!("{}", error);
+        }
+
+        std::process::exit(1);
+    }
+
+    let services_configs = Arc::new(RwLock::new(Arc::new(services_configs.unwrap())));
 
     let mut tera = Tera::new("templates/**/*").expect("Unable to initialize Tera!");
     tera.autoescape_on(vec![]);
 
-    let appdata = AppData { database, tera, oauth_credentials, services_configs};
+    let knx_tunnels = Arc::new(Mutex::new(HashMap::new()));
+
+    let stream = StreamHub::default();
+    let events = EventBus::default();
+
+    let appdata = AppData { database, tera, oauth_credentials, services_configs, knx_tunnels, stream, events };
+
+    //Background workers
+    threads::service_oauth_refresh_token::start(appdata.clone());
+    threads::knx_tunnels::start(appdata.clone());
+    threads::services_config_watcher::start(appdata.clone());
+    threads::services_config_watcher::listen_for_sighup(appdata.clone());
+    threads::heating_schedules::start(appdata.clone());
+    threads::optimal_start::start(appdata.clone());
+    threads::rules::start(appdata.clone());
+    threads::history_recorder::start(appdata.clone());
+    threads::event_stream::start(appdata.clone());
+    threads::report_state::start(appdata.clone());
+    threads::notifier::start(appdata.clone());
+    threads::webhook_dispatcher::start(appdata.clone());
+    threads::away_mode::start(appdata.clone());
+    threads::vacations::start(appdata.clone());
+    threads::open_window::start(appdata.clone());
+    threads::device_poller::start(appdata.clone());
 
     HttpServer::new(move || {
 
@@ -50,9 +90,83 @@ async fn main() -> std::io::Result<()>{
             .service(endpoints::oauth::finish::post_finish)
             .service(endpoints::oauth::token::post_token)
 
+            //Assistant endpoints
+            .service(endpoints::assistant::webhook::post_webhook)
+
             //Service endpoints
             .service(endpoints::services::add::post_add)
             .service(endpoints::services::get::post_get)
+            .service(endpoints::services::update::post_update)
+            .service(endpoints::services::expose::post_expose)
+            .service(endpoints::services::remove::post_remove)
+            .service(endpoints::services::oauth_callback::get_oauth_callback)
+            .service(endpoints::services::outdoor_temperature::post_outdoor_temperature)
+
+            //Device endpoints
+            .service(endpoints::devices::get::post_get)
+            .service(endpoints::devices::update::post_update)
+            .service(endpoints::devices::expose::post_expose)
+            .service(endpoints::devices::history::post_history)
+
+            //Room and floor endpoints
+            .service(endpoints::rooms::get::post_get)
+            .service(endpoints::rooms::save::post_save)
+            .service(endpoints::rooms::remove::post_remove)
+            .service(endpoints::floors::save::post_save)
+            .service(endpoints::floors::remove::post_remove)
+
+            //Scene endpoints
+            .service(endpoints::scenes::get::post_get)
+            .service(endpoints::scenes::save::post_save)
+            .service(endpoints::scenes::remove::post_remove)
+            .service(endpoints::scenes::activate::post_activate)
+
+            //Heating schedule endpoints
+            .service(endpoints::schedules::get::post_get)
+            .service(endpoints::schedules::save::post_save)
+            .service(endpoints::schedules::remove::post_remove)
+            .service(endpoints::schedules::import::post_import)
+            .service(endpoints::schedules::export::post_export)
+            .service(endpoints::schedules::optimal_start::post_optimal_start)
+
+            //Automation rule endpoints
+            .service(endpoints::rules::get::post_get)
+            .service(endpoints::rules::save::post_save)
+            .service(endpoints::rules::remove::post_remove)
+            .service(endpoints::rules::webhook::post_webhook)
+
+            //Notification endpoints
+            .service(endpoints::notifications::get::post_get)
+            .service(endpoints::notifications::save::post_save)
+            .service(endpoints::notifications::remove::post_remove)
+            .service(endpoints::notifications::test::post_test)
+
+            //Presence endpoints
+            .service(endpoints::presence::get::post_get)
+            .service(endpoints::presence::geofence::post_geofence)
+            .service(endpoints::presence::set::post_set)
+            .service(endpoints::presence::remove::post_remove)
+            .service(endpoints::presence::away::post_away)
+
+            //Vacation endpoints
+            .service(endpoints::vacation::get::post_get)
+            .service(endpoints::vacation::save::post_save)
+            .service(endpoints::vacation::cancel::post_cancel)
+
+            //Open window endpoints
+            .service(endpoints::open_window::get::post_get)
+            .service(endpoints::open_window::save::post_save)
+            .service(endpoints::open_window::remove::post_remove)
+
+            //Webhook subscription endpoints
+            .service(endpoints::webhooks::get::post_get)
+            .service(endpoints::webhooks::save::post_save)
+            .service(endpoints::webhooks::remove::post_remove)
+            .service(endpoints::webhooks::deliveries::post_deliveries)
+
+            //Live event endpoints
+            .service(endpoints::stream::ws::get_ws)
+            .service(endpoints::stream::sse::get_sse)
     })
     .bind("0.0.0.0:8080")?
     .run()
@@ -0,0 +1,352 @@
+use crate::common::service::{TokenCredentials, get_token_credentials, get_settings};
+use crate::types::home_assistant::{EntityState, HomeAssistantSettings};
+use crate::types::device::{Device, DeviceKind, DeviceCapability, DeviceState, DeviceCommand};
+use crate::database::Database;
+
+use serde_json::{Value, json};
+
+/// The entity domains we know how to import
+const SUPPORTED_DOMAINS: [&str; 4] = ["climate", "light", "switch", "sensor"];
+
+/**
+Function to check the validity of a Home Assistant host and long-lived access token
+
+## Parameters
+    host: The base URL of the Home Assistant instance
+    token: The long-lived access token
+
+## Returns
+    Err: If an error occurred, e.g. the host is unreachable
+    None: If the token was rejected
+    Some: If the token is valid
+*/
+pub fn do_test_login(host: String, token: String) -> Result<Option<()>, ()> {
+    let request = reqwest::blocking::Client::new().get(format!("{}/api/", host.trim_end_matches('/')))
+        .bearer_auth(token)
+        .send();
+
+    if request.is_err() {
+        eprintln!("An error occurred: {:?}", request.err());
+        return Err(());
+    }
+
+    let status = request.unwrap().status();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Ok(None);
+    }
+
+    if !status.is_success() {
+        eprintln!("Home Assistant returned an unexpected status: {}", status);
+        return Err(());
+    }
+
+    Ok(Some(()))
+}
+
+/**
+Get all imported entities of a Service, as Devices
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service to get the Devices for
+
+## Returns
+    Err: If an error occurred
+    Ok:
+        Some: The Devices of the Service
+        None: The token was rejected, the User has to reconnect the Service
+*/
+pub fn get_devices(db: Database, service_id: String) -> Result<Option<Vec<Device>>, ()> {
+    let credentials = get_credentials(db.clone(), service_id.clone())?;
+
+    let settings = get_settings::<HomeAssistantSettings>(db, service_id);
+    if settings.is_err() {
+        eprintln!("An error occurred: {:?}", settings.err());
+        return Err(());
+    }
+
+    let selected_entities = match settings.unwrap() {
+        Some(settings) => settings.entities,
+        None => vec![]
+    };
+
+    let request = reqwest::blocking::Client::new().get(format!("{}/api/states", credentials.host.trim_end_matches('/')))
+        .bearer_auth(credentials.token)
+        .send();
+
+    if request.is_err() {
+        eprintln!("An error occurred: {:?}", request.err());
+        return Err(());
+    }
+
+    let response = request.unwrap();
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED || response.status() == reqwest::StatusCode::FORBIDDEN {
+        eprintln!("Home Assistant rejected the token");
+        return Ok(None);
+    }
+
+    let states = response.json::<Vec<EntityState>>();
+    if states.is_err() {
+        eprintln!("Unable to deserialize Home Assistant states: {:?}", states.err());
+        return Err(());
+    }
+
+    let devices: Vec<Device> = states.unwrap().iter()
+        .filter(|entity| selected_entities.is_empty() || selected_entities.contains(&entity.entity_id))
+        .filter_map(entity_to_device)
+        .collect();
+
+    Ok(Some(devices))
+}
+
+/**
+Execute a command on an entity by calling the matching Home Assistant service
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service the entity belongs to
+    entity_id: The entity_id of the entity, e.g. 'light.kitchen'
+    command: The command to execute
+
+## Returns
+    Err: If an error occurred, or if the command is not supported for the entity's domain
+    Ok: If the command was executed
+*/
+pub fn execute(db: Database, service_id: String, entity_id: String, command: DeviceCommand) -> Result<(), ()> {
+    let domain = entity_id.split('.').next().unwrap_or("").to_string();
+
+    let (service, data) = match (domain.as_str(), &command) {
+        ("light", DeviceCommand::BRIGHTNESS(0)) => ("turn_off", json!({ "entity_id": entity_id })),
+        ("light", DeviceCommand::BRIGHTNESS(brightness)) => ("turn_on", json!({ "entity_id": entity_id, "brightness_pct": brightness })),
+        ("light", DeviceCommand::ONOFF(on)) | ("switch", DeviceCommand::ONOFF(on)) | ("climate", DeviceCommand::ONOFF(on)) => {
+            (if *on { "turn_on" } else { "turn_off" }, json!({ "entity_id": entity_id }))
+        },
+        ("climate", DeviceCommand::SETPOINT(temperature)) => ("set_temperature", json!({ "entity_id": entity_id, "temperature": temperature })),
+        _ => {
+            eprintln!("Command {:?} is not supported for Home Assistant entity '{}'", command, entity_id);
+            return Err(());
+        }
+    };
+
+    call_service(db, service_id, &domain, service, data)
+}
+
+/**
+Call a Home Assistant service
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service to call the Home Assistant service with
+    domain: The domain of the Home Assistant service, e.g. 'light'
+    service: The name of the Home Assistant service, e.g. 'turn_on'
+    data: The service data
+
+## Returns
+    Err: If an error occurred
+    Ok: If Home Assistant accepted the call
+*/
+pub fn call_service(db: Database, service_id: String, domain: &str, service: &str, data: Value) -> Result<(), ()> {
+    let credentials = get_credentials(db, service_id)?;
+
+    let request = reqwest::blocking::Client::new().post(format!("{}/api/services/{}/{}", credentials.host.trim_end_matches('/'), domain, service))
+        .bearer_auth(credentials.token)
+        .json(&data)
+        .send();
+
+    if request.is_err() {
+        eprintln!("An error occurred: {:?}", request.err());
+        return Err(());
+    }
+
+    let status = request.unwrap().status();
+    if !status.is_success() {
+        eprintln!("Home Assistant returned status {} for service call {}.{}", status, domain, service);
+        return Err(());
+    }
+
+    Ok(())
+}
+
+fn get_credentials(db: Database, service_id: String) -> Result<TokenCredentials, ()> {
+    let credentials = get_token_credentials(db, service_id.clone());
+    if credentials.is_err() {
+        eprintln!("An error occurred: {:?}", credentials.err());
+        return Err(());
+    }
+
+    let credentials_unwrapped = credentials.unwrap();
+    if credentials_unwrapped.is_none() {
+        eprintln!("No Home Assistant credentials found for Service '{}'", service_id);
+        return Err(());
+    }
+
+    Ok(credentials_unwrapped.unwrap())
+}
+
+/**
+Convert a Home Assistant entity into a Device
+
+## Returns
+    None: If the entity's domain or device_class is not supported
+    Some: The Device
+*/
+fn entity_to_device(entity: &EntityState) -> Option<Device> {
+    let domain = entity.entity_id.split('.').next().unwrap_or("");
+    if !SUPPORTED_DOMAINS.contains(&domain) {
+        return None;
+    }
+
+    let name = entity.attributes.get("friendly_name")
+        .and_then(|name| name.as_str())
+        .unwrap_or(&entity.entity_id)
+        .to_string();
+
+    let online = entity.state != "unavailable" && entity.state != "unknown";
+    let attribute_f32 = |name: &str| entity.attributes.get(name).and_then(|value| value.as_f64()).map(|value| value as f32);
+
+    let mut state = DeviceState { online, ..DeviceState::default() };
+    let mut min_setpoint: Option<f32> = None;
+    let mut max_setpoint: Option<f32> = None;
+
+    let (kind, capabilities) = match domain {
+        "climate" => {
+            let mut capabilities = vec![DeviceCapability::TEMPERATURESETTING];
+
+            let hvac_modes = entity.attributes.get("hvac_modes").and_then(|modes| modes.as_array());
+            if hvac_modes.map(|modes| modes.contains(&json!("off"))).unwrap_or(false) {
+                capabilities.push(DeviceCapability::ONOFF);
+                state.on = Some(entity.state != "off");
+            }
+
+            state.ambient_temperature = attribute_f32("current_temperature");
+            state.setpoint_temperature = attribute_f32("temperature");
+            state.humidity = attribute_f32("current_humidity");
+            min_setpoint = attribute_f32("min_temp");
+            max_setpoint = attribute_f32("max_temp");
+
+            (DeviceKind::THERMOSTAT, capabilities)
+        },
+        "light" => {
+            state.on = Some(entity.state == "on");
+            state.brightness = attribute_f32("brightness").map(|brightness| (brightness / 255.0 * 100.0).round() as u8);
+
+            (DeviceKind::LIGHT, vec![DeviceCapability::ONOFF, DeviceCapability::BRIGHTNESS])
+        },
+        "switch" => {
+            state.on = Some(entity.state == "on");
+            (DeviceKind::SWITCH, vec![DeviceCapability::ONOFF])
+        },
+        "sensor" => {
+            let device_class = entity.attributes.get("device_class").and_then(|class| class.as_str()).unwrap_or("");
+            let unit = entity.attributes.get("unit_of_measurement").and_then(|unit| unit.as_str()).unwrap_or("");
+            let value = entity.state.parse::<f32>().ok();
+
+            let capability = match device_class {
+                "temperature" => {
+                    state.ambient_temperature = if unit == "°F" { value.map(|f| (f - 32.0) / 1.8) } else { value };
+                    DeviceCapability::TEMPERATUREREADING
+                },
+                "humidity" => {
+                    state.humidity = value;
+                    DeviceCapability::SENSORSTATE
+                },
+                "carbon_dioxide" => {
+                    state.co2 = value;
+                    DeviceCapability::SENSORSTATE
+                },
+                "pressure" | "atmospheric_pressure" => {
+                    state.pressure = value;
+                    DeviceCapability::SENSORSTATE
+                },
+                "power" => {
+                    state.power = if unit == "kW" { value.map(|kw| kw * 1000.0) } else { value };
+                    DeviceCapability::SENSORSTATE
+                },
+                _ => return None
+            };
+
+            (DeviceKind::SENSOR, vec![capability])
+        },
+        _ => return None
+    };
+
+    Some(Device {
+        id: entity.entity_id.clone(),
+        name,
+        kind,
+        capabilities,
+        manufacturer: "Home Assistant".to_string(),
+        model: domain.to_string(),
+        min_setpoint,
+        max_setpoint,
+        state
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(entity_id: &str, state: &str, attributes: Value) -> EntityState {
+        EntityState { entity_id: entity_id.to_string(), state: state.to_string(), attributes: attributes.as_object().unwrap().clone() }
+    }
+
+    #[test]
+    fn climate_entities_become_thermostats() {
+        let device = entity_to_device(&entity("climate.living_room", "off", json!({
+            "friendly_name": "Living room",
+            "hvac_modes": ["off", "heat"],
+            "current_temperature": 19.5,
+            "temperature": 21,
+            "min_temp": 7,
+            "max_temp": 35
+        }))).unwrap();
+
+        assert_eq!(device.id, "climate.living_room");
+        assert_eq!(device.name, "Living room");
+        assert_eq!(device.kind, DeviceKind::THERMOSTAT);
+        assert_eq!(device.capabilities, vec![DeviceCapability::TEMPERATURESETTING, DeviceCapability::ONOFF]);
+        assert_eq!(device.state.on, Some(false));
+        assert_eq!(device.state.ambient_temperature, Some(19.5));
+        assert_eq!(device.state.setpoint_temperature, Some(21.0));
+        assert_eq!((device.min_setpoint, device.max_setpoint), (Some(7.0), Some(35.0)));
+
+        //A climate entity which can't be turned off has no on/off state
+        let device = entity_to_device(&entity("climate.bedroom", "heat", json!({ "hvac_modes": ["heat"] }))).unwrap();
+        assert_eq!(device.name, "climate.bedroom");
+        assert_eq!(device.capabilities, vec![DeviceCapability::TEMPERATURESETTING]);
+        assert_eq!(device.state.on, None);
+    }
+
+    #[test]
+    fn light_brightness_is_scaled_to_a_percentage() {
+        let device = entity_to_device(&entity("light.hallway", "on", json!({ "brightness": 128 }))).unwrap();
+
+        assert_eq!(device.kind, DeviceKind::LIGHT);
+        assert_eq!(device.state.on, Some(true));
+        assert_eq!(device.state.brightness, Some(50));
+        assert!(device.state.online);
+    }
+
+    #[test]
+    fn sensor_values_are_converted() {
+        let fahrenheit = entity_to_device(&entity("sensor.outside", "68", json!({ "device_class": "temperature", "unit_of_measurement": "°F" }))).unwrap();
+        assert_eq!(fahrenheit.capabilities, vec![DeviceCapability::TEMPERATUREREADING]);
+        assert_eq!(fahrenheit.state.ambient_temperature, Some(20.0));
+
+        let kilowatts = entity_to_device(&entity("sensor.heat_pump", "1.5", json!({ "device_class": "power", "unit_of_measurement": "kW" }))).unwrap();
+        assert_eq!(kilowatts.capabilities, vec![DeviceCapability::SENSORSTATE]);
+        assert_eq!(kilowatts.state.power, Some(1500.0));
+
+        let unavailable = entity_to_device(&entity("sensor.humidity", "unavailable", json!({ "device_class": "humidity" }))).unwrap();
+        assert!(!unavailable.state.online);
+        assert_eq!(unavailable.state.humidity, None);
+    }
+
+    #[test]
+    fn unsupported_entities_are_skipped() {
+        assert!(entity_to_device(&entity("media_player.tv", "on", json!({}))).is_none());
+        assert!(entity_to_device(&entity("sensor.battery", "80", json!({ "device_class": "battery" }))).is_none());
+        assert!(entity_to_device(&entity("sensor.unknown", "1", json!({}))).is_none());
+    }
+}
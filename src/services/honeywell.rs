@@ -1,9 +1,25 @@
 use crate::common::service::{Credentials, get_password_credentials};
-use crate::types::honeywell::LoginResponse;
+use crate::types::honeywell::{LoginResponse, LocationsResponse, SetZoneTemperatureRequest, SetZoneTemperatureResponse, EvohomeTokenResponse, ZoneSchedule, ZoneDailySchedule, ZoneSwitchpoint, EvohomeUserAccount, EvohomeLocation, EvohomeControlSystem, EvohomeSystemStatus, SetSystemModeRequest, SetHotWaterRequest, SystemMode};
+use crate::types::device::{Device, DeviceAlert, DeviceKind, DeviceCapability, DeviceState};
+use crate::types::schedule::{DailySchedule, Day, Switchpoint};
 use crate::database::Database;
 
 const HONEYWELL_LOGIN_ENDPOINT: &str = "https://international.mytotalconnectcomfort.com/api/accountApi/login";
+const HONEYWELL_LOCATIONS_ENDPOINT: &str = "https://international.mytotalconnectcomfort.com/api/locationsapi/getlocations";
+const HONEYWELL_SET_ZONE_TEMPERATURE_ENDPOINT: &str = "https://international.mytotalconnectcomfort.com/api/ZonesApi/SetZoneTemperature";
 
+//Schedules are only available through the EvoHome API, which uses its own login
+const EVOHOME_TOKEN_ENDPOINT: &str = "https://tccna.honeywell.com/Auth/OAuth/Token";
+const EVOHOME_ZONE_SCHEDULE_ENDPOINT: &str = "https://tccna.honeywell.com/WebAPI/emea/api/v1/temperatureZone/{zone_id}/schedule";
+const EVOHOME_USER_ACCOUNT_ENDPOINT: &str = "https://tccna.honeywell.com/WebAPI/emea/api/v1/userAccount";
+const EVOHOME_INSTALLATION_INFO_ENDPOINT: &str = "https://tccna.honeywell.com/WebAPI/emea/api/v1/location/installationInfo?userId={user_id}&includeTemperatureControlSystems=True";
+const EVOHOME_SYSTEM_MODE_ENDPOINT: &str = "https://tccna.honeywell.com/WebAPI/emea/api/v1/temperatureControlSystem/{system_id}/mode";
+const EVOHOME_SYSTEM_STATUS_ENDPOINT: &str = "https://tccna.honeywell.com/WebAPI/emea/api/v1/temperatureControlSystem/{system_id}/status";
+const EVOHOME_HOT_WATER_STATE_ENDPOINT: &str = "https://tccna.honeywell.com/WebAPI/emea/api/v1/domesticHotWater/{dhw_id}/state";
+/// The application credentials of the EvoHome API, these are the same for every client
+const EVOHOME_APPLICATION_CREDENTIALS: &str = "4a231089-d2b6-41bd-a5eb-16a0a422b999:1a15cdb8-42de-407b-add0-059f92c530cb";
+
+#[allow(dead_code)]
 pub struct HoneywellUser {
     pub access_token:   String,
     pub email:          String,
@@ -43,8 +59,20 @@ pub fn do_test_login(username: String, password: String) -> Result<Option<()>, (
     Ok(Some(()))
 }
 
-pub fn do_login(db: Database, user_id: String) -> Result<Option<HoneywellUser>, ()> {
-    let credentials = get_password_credentials(db, user_id.clone());
+/**
+Log in to Honeywell with the credentials stored for a Service
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service to log in for
+
+## Returns
+    Err: If an error occurred
+    None: If the Service has no credentials, or the login failed
+    Some: If the login was successful
+*/
+pub fn do_login(db: Database, service_id: String) -> Result<Option<HoneywellUser>, ()> {
+    let credentials = get_password_credentials(db, service_id.clone());
 
     if credentials.is_err() {
         eprintln!("An error occurred: {:?}", credentials.err());
@@ -88,11 +116,8 @@ fn login(credentials: Credentials) -> Result<Option<HoneywellUser>, ()> {
     let cookies: Vec<reqwest::cookie::Cookie> = unwrapped_request.cookies().collect();
     let mut session: Option<String> = None;
     for cookie in cookies {
-        match cookie.name() {
-            "SessionCookie" => {
-                session = Some(cookie.value().to_string());
-            },
-            _ => {}
+        if cookie.name() == "SessionCookie" {
+            session = Some(cookie.value().to_string());
         }
     }
 
@@ -113,4 +138,485 @@ fn login(credentials: Credentials) -> Result<Option<HoneywellUser>, ()> {
     };
 
     Ok(Some(user))
-}
\ No newline at end of file
+}
+
+/**
+Get all Zones of all Locations for a Service, as Devices
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service to get the Devices for
+
+## Returns
+    Err: If an error occurred
+    Ok:
+        Some: The Zones of the Service
+        None: The login failed, the User has to reconnect the Service
+*/
+pub fn get_devices(db: Database, service_id: String) -> Result<Option<Vec<Device>>, ()> {
+    let user = do_login(db, service_id)?;
+    if user.is_none() {
+        eprintln!("Unable to log in to Honeywell");
+        return Ok(None);
+    }
+
+    let locations_request = reqwest::blocking::Client::new().get(HONEYWELL_LOCATIONS_ENDPOINT)
+        .header("Cookie", format!("SessionCookie={}", user.unwrap().access_token))
+        .send();
+
+    if locations_request.is_err() {
+        eprintln!("An error occurred: {:?}", locations_request.err());
+        return Err(());
+    }
+
+    let locations_response = locations_request.unwrap().json::<LocationsResponse>();
+    if locations_response.is_err() {
+        eprintln!("Unable to deserialize Honeywell locations: {:?}", locations_response.err());
+        return Err(());
+    }
+
+    let content = locations_response.unwrap().content;
+    if content.is_none() {
+        return Ok(Some(vec![]));
+    }
+
+    let mut devices: Vec<Device> = vec![];
+    for location in content.unwrap().locations {
+        //A Location has no Device of its own, alerts of the Location which don't belong to a Zone are reported on its first Zone
+        let zone_alerts = location.zones.iter()
+            .map(|zone| [zone.has_battery_low_alert, zone.has_comm_lost_alert, zone.has_sensor_failure_alert].iter().filter(|alert| **alert).count() as i32)
+            .sum::<i32>();
+        let mut location_alert = location.alert_count > zone_alerts;
+
+        for zone in location.zones {
+            let mut alerts: Vec<DeviceAlert> = vec![];
+            if zone.has_battery_low_alert {
+                alerts.push(DeviceAlert::BATTERYLOW);
+            }
+
+            if zone.has_comm_lost_alert {
+                alerts.push(DeviceAlert::COMMLOST);
+            }
+
+            if zone.has_sensor_failure_alert {
+                alerts.push(DeviceAlert::SENSORFAILURE);
+            }
+
+            if location_alert {
+                alerts.push(DeviceAlert::LOCATIONALERT);
+                location_alert = false;
+            }
+
+            devices.push(Device {
+                id: zone.id.clone(),
+                name: zone.name.clone(),
+                kind: DeviceKind::THERMOSTAT,
+                capabilities: vec![DeviceCapability::TEMPERATURESETTING],
+                manufacturer: "Honeywell".to_string(),
+                model: zone.thermostat_model_type.clone(),
+                min_setpoint: Some(zone.min_heat_setpoint),
+                max_setpoint: Some(zone.max_heat_setpoint),
+                state: DeviceState {
+                    online: zone.is_alive && !zone.has_comm_lost_alert,
+                    ambient_temperature: Some(zone.temperature),
+                    setpoint_temperature: Some(zone.target_heat_temperature),
+                    alerts,
+                    ..DeviceState::default()
+                }
+            });
+        }
+    }
+
+    Ok(Some(devices))
+}
+
+/**
+Permanently set the target temperature of a Zone
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service the Zone belongs to
+    zone_id: The ID of the Zone
+    temperature: The new target temperature in °C
+
+## Returns
+    Err: If an error occurred
+    Ok: If the temperature was set
+*/
+pub fn set_zone_temperature(db: Database, service_id: String, zone_id: String, temperature: f32) -> Result<(), ()> {
+    update_zone(db, service_id, zone_id, Some(temperature))
+}
+
+/**
+Cancel the override of a Zone, so it follows its schedule again
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service the Zone belongs to
+    zone_id: The ID of the Zone
+
+## Returns
+    Err: If an error occurred
+    Ok: If the Zone follows its schedule
+*/
+pub fn follow_zone_schedule(db: Database, service_id: String, zone_id: String) -> Result<(), ()> {
+    update_zone(db, service_id, zone_id, None)
+}
+
+/**
+Get the weekly schedule of a Zone from EvoHome
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service the Zone belongs to
+    zone_id: The ID of the Zone
+
+## Returns
+    Err: If an error occurred
+    Ok: The schedule of the Zone
+*/
+pub fn get_zone_schedule(db: Database, service_id: String, zone_id: String) -> Result<Vec<DailySchedule>, ()> {
+    let access_token = evohome_login(db, service_id)?;
+
+    let request = reqwest::blocking::Client::new().get(EVOHOME_ZONE_SCHEDULE_ENDPOINT.replace("{zone_id}", &zone_id))
+        .bearer_auth(access_token)
+        .send();
+
+    if request.is_err() {
+        eprintln!("An error occurred: {:?}", request.err());
+        return Err(());
+    }
+
+    let response = request.unwrap().json::<ZoneSchedule>();
+    if response.is_err() {
+        eprintln!("Unable to deserialize EvoHome schedule: {:?}", response.err());
+        return Err(());
+    }
+
+    let mut days: Vec<DailySchedule> = vec![];
+    for daily in response.unwrap().daily_schedules {
+        let day = match daily.day_of_week.as_str() {
+            "Monday"    => Day::MONDAY,
+            "Tuesday"   => Day::TUESDAY,
+            "Wednesday" => Day::WEDNESDAY,
+            "Thursday"  => Day::THURSDAY,
+            "Friday"    => Day::FRIDAY,
+            "Saturday"  => Day::SATURDAY,
+            "Sunday"    => Day::SUNDAY,
+            _ => {
+                eprintln!("Unknown EvoHome day '{}'", daily.day_of_week);
+                return Err(());
+            }
+        };
+
+        let switchpoints = daily.switchpoints.iter()
+            .map(|switchpoint| Switchpoint {
+                //EvoHome uses HH:MM:SS, switchpoints are per minute
+                time: switchpoint.time_of_day.chars().take(5).collect(),
+                setpoint: switchpoint.heat_setpoint
+            })
+            .collect();
+
+        days.push(DailySchedule { day, switchpoints });
+    }
+
+    Ok(days)
+}
+
+/**
+Replace the weekly schedule of a Zone in EvoHome
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service the Zone belongs to
+    zone_id: The ID of the Zone
+    days: The schedule
+
+## Returns
+    Err: If an error occurred
+    Ok: If the schedule was replaced
+*/
+pub fn set_zone_schedule(db: Database, service_id: String, zone_id: String, days: &[DailySchedule]) -> Result<(), ()> {
+    let access_token = evohome_login(db, service_id)?;
+
+    let payload = ZoneSchedule {
+        daily_schedules: days.iter()
+            .map(|daily| ZoneDailySchedule {
+                day_of_week: match daily.day {
+                    Day::MONDAY     => "Monday",
+                    Day::TUESDAY    => "Tuesday",
+                    Day::WEDNESDAY  => "Wednesday",
+                    Day::THURSDAY   => "Thursday",
+                    Day::FRIDAY     => "Friday",
+                    Day::SATURDAY   => "Saturday",
+                    Day::SUNDAY     => "Sunday"
+                }.to_string(),
+                switchpoints: daily.switchpoints.iter()
+                    .map(|switchpoint| ZoneSwitchpoint { heat_setpoint: switchpoint.setpoint, time_of_day: format!("{}:00", switchpoint.time) })
+                    .collect()
+            })
+            .collect()
+    };
+
+    let request = reqwest::blocking::Client::new().put(EVOHOME_ZONE_SCHEDULE_ENDPOINT.replace("{zone_id}", &zone_id))
+        .bearer_auth(access_token)
+        .json(&payload)
+        .send();
+
+    if request.is_err() {
+        eprintln!("An error occurred: {:?}", request.err());
+        return Err(());
+    }
+
+    let response = request.unwrap();
+    if !response.status().is_success() {
+        eprintln!("EvoHome returned {} when setting a schedule: {:?}", response.status(), response.text());
+        return Err(());
+    }
+
+    Ok(())
+}
+
+/**
+Get the current mode of the EvoHome systems of a Service. The mode of the first system is used, as modes are set on all systems at once
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+
+## Returns
+    Err: If an error occurred
+    None: If the Service has no systems, or the mode is one we don't support
+    Some: The mode
+*/
+pub fn get_system_mode(db: Database, service_id: String) -> Result<Option<SystemMode>, ()> {
+    let access_token = evohome_login(db, service_id)?;
+    let client = reqwest::blocking::Client::new();
+
+    let system = get_control_systems(&client, &access_token)?.into_iter().next();
+    if system.is_none() {
+        return Ok(None);
+    }
+
+    let request = client.get(EVOHOME_SYSTEM_STATUS_ENDPOINT.replace("{system_id}", &system.unwrap().system_id)).bearer_auth(&access_token).send();
+    if request.is_err() {
+        eprintln!("An error occurred: {:?}", request.err());
+        return Err(());
+    }
+
+    let status = request.unwrap().json::<EvohomeSystemStatus>();
+    if status.is_err() {
+        eprintln!("Unable to deserialize EvoHome system status: {:?}", status.err());
+        return Err(());
+    }
+
+    Ok(SystemMode::from_evohome_name(&status.unwrap().system_mode_status.mode))
+}
+
+/**
+Permanently set the mode of every EvoHome system of a Service, e.g. the Away quick action
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+    mode: The mode
+
+## Returns
+    Err: If an error occurred
+    Ok: If the mode was set on every system
+*/
+pub fn set_system_mode(db: Database, service_id: String, mode: SystemMode) -> Result<(), ()> {
+    let access_token = evohome_login(db, service_id)?;
+    let client = reqwest::blocking::Client::new();
+
+    let payload = SetSystemModeRequest { system_mode: mode.evohome_name().to_string(), time_until: None, permanent: true };
+    for system in get_control_systems(&client, &access_token)? {
+        let request = client.put(EVOHOME_SYSTEM_MODE_ENDPOINT.replace("{system_id}", &system.system_id))
+            .bearer_auth(&access_token)
+            .json(&payload)
+            .send();
+
+        if request.is_err() {
+            eprintln!("An error occurred: {:?}", request.err());
+            return Err(());
+        }
+
+        let response = request.unwrap();
+        if !response.status().is_success() {
+            eprintln!("EvoHome returned {} when setting the system mode: {:?}", response.status(), response.text());
+            return Err(());
+        }
+    }
+
+    Ok(())
+}
+
+/**
+Switch the hot water of every EvoHome system of a Service on or off permanently, or let it follow its schedule again
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+    on: If the hot water should be on, None to follow the schedule
+
+## Returns
+    Err: If an error occurred
+    Ok: If the hot water of every system was set
+*/
+pub fn set_hot_water(db: Database, service_id: String, on: Option<bool>) -> Result<(), ()> {
+    let access_token = evohome_login(db, service_id)?;
+    let client = reqwest::blocking::Client::new();
+
+    let payload = match on {
+        Some(on) => SetHotWaterRequest { state: Some(if on { "On" } else { "Off" }.to_string()), mode: "PermanentOverride".to_string(), until_time: None },
+        None => SetHotWaterRequest { state: None, mode: "FollowSchedule".to_string(), until_time: None }
+    };
+
+    for dhw in get_control_systems(&client, &access_token)?.iter().filter_map(|system| system.dhw.as_ref()) {
+        let request = client.put(EVOHOME_HOT_WATER_STATE_ENDPOINT.replace("{dhw_id}", &dhw.dhw_id))
+            .bearer_auth(&access_token)
+            .json(&payload)
+            .send();
+
+        if request.is_err() {
+            eprintln!("An error occurred: {:?}", request.err());
+            return Err(());
+        }
+
+        let response = request.unwrap();
+        if !response.status().is_success() {
+            eprintln!("EvoHome returned {} when setting the hot water: {:?}", response.status(), response.text());
+            return Err(());
+        }
+    }
+
+    Ok(())
+}
+
+/**
+Override the target temperature of a Zone permanently, or cancel the override if temperature is None
+*/
+fn update_zone(db: Database, service_id: String, zone_id: String, temperature: Option<f32>) -> Result<(), ()> {
+    let user = do_login(db, service_id)?;
+    if user.is_none() {
+        eprintln!("Unable to log in to Honeywell");
+        return Err(());
+    }
+
+    let payload = SetZoneTemperatureRequest {
+        zone_id,
+        heat_temperature: temperature.map(|temperature| format!("{:.1}", temperature)),
+        hot_water_state_is_on: false,
+        is_permanent: temperature.is_some(),
+        set_until_hours: "00".to_string(),
+        set_until_minutes: "00".to_string(),
+        location_time_offset_minutes: 60,
+        is_following_schedule: temperature.is_none()
+    };
+
+    let request = reqwest::blocking::Client::new().post(HONEYWELL_SET_ZONE_TEMPERATURE_ENDPOINT)
+        .header("Cookie", format!("SessionCookie={}", user.unwrap().access_token))
+        .json(&payload)
+        .send();
+
+    if request.is_err() {
+        eprintln!("An error occurred: {:?}", request.err());
+        return Err(());
+    }
+
+    let response = request.unwrap().json::<SetZoneTemperatureResponse>();
+    if response.is_err() {
+        eprintln!("Unable to deserialize Honeywell response: {:?}", response.err());
+        return Err(());
+    }
+
+    if let Some(errors) = response.unwrap().errors {
+        if !errors.is_null() {
+            eprintln!("Honeywell returned errors: {:?}", errors);
+            return Err(());
+        }
+    }
+
+    Ok(())
+}
+
+/**
+Get the EvoHome systems of all locations of the logged in user
+*/
+fn get_control_systems(client: &reqwest::blocking::Client, access_token: &str) -> Result<Vec<EvohomeControlSystem>, ()> {
+    let account_request = client.get(EVOHOME_USER_ACCOUNT_ENDPOINT).bearer_auth(access_token).send();
+    if account_request.is_err() {
+        eprintln!("An error occurred: {:?}", account_request.err());
+        return Err(());
+    }
+
+    let account = account_request.unwrap().json::<EvohomeUserAccount>();
+    if account.is_err() {
+        eprintln!("Unable to deserialize EvoHome user account: {:?}", account.err());
+        return Err(());
+    }
+
+    let installation_request = client.get(EVOHOME_INSTALLATION_INFO_ENDPOINT.replace("{user_id}", &account.unwrap().user_id)).bearer_auth(access_token).send();
+    if installation_request.is_err() {
+        eprintln!("An error occurred: {:?}", installation_request.err());
+        return Err(());
+    }
+
+    let locations = installation_request.unwrap().json::<Vec<EvohomeLocation>>();
+    if locations.is_err() {
+        eprintln!("Unable to deserialize EvoHome installation info: {:?}", locations.err());
+        return Err(());
+    }
+
+    let systems = locations.unwrap().into_iter()
+        .flat_map(|location| location.gateways)
+        .flat_map(|gateway| gateway.temperature_control_systems)
+        .collect();
+
+    Ok(systems)
+}
+
+/**
+Log in to the EvoHome API with the credentials stored for a Service
+
+## Returns
+    Err: If an error occurred, or if the login failed
+    Ok: The access token
+*/
+fn evohome_login(db: Database, service_id: String) -> Result<String, ()> {
+    let credentials = get_password_credentials(db, service_id);
+    if credentials.is_err() {
+        eprintln!("An error occurred: {:?}", credentials.err());
+        return Err(());
+    }
+
+    let credentials = credentials.unwrap();
+    if credentials.is_none() {
+        eprintln!("No credentials stored for Honeywell Service");
+        return Err(());
+    }
+
+    let credentials = credentials.unwrap();
+    let request = reqwest::blocking::Client::new().post(EVOHOME_TOKEN_ENDPOINT)
+        .header("Authorization", format!("Basic {}", base64::encode(EVOHOME_APPLICATION_CREDENTIALS)))
+        .form(&[
+            ("grant_type", "password"),
+            ("scope", "EMEA-V1-Basic EMEA-V1-Anonymous"),
+            ("Username", &credentials.username),
+            ("Password", &credentials.password)
+        ])
+        .send();
+
+    if request.is_err() {
+        eprintln!("An error occurred: {:?}", request.err());
+        return Err(());
+    }
+
+    let response = request.unwrap().json::<EvohomeTokenResponse>();
+    if response.is_err() {
+        eprintln!("Unable to log in to EvoHome: {:?}", response.err());
+        return Err(());
+    }
+
+    Ok(response.unwrap().access_token)
+}
@@ -0,0 +1,484 @@
+use crate::common::service::get_settings;
+use crate::config::ServicesConfig;
+use crate::database::Database;
+use crate::types::http::{HttpSettings, HttpDevice, HttpDeviceDefinition, HttpRequestTemplate, HttpStateMapping, HttpStateField, HttpCommand};
+use crate::types::device::{Device, DeviceKind, DeviceCapability, DeviceState, DeviceCommand};
+
+use serde_json::{Value, json};
+use tera::{Tera, Context};
+use std::time::Duration;
+
+const TIMEOUT_SECONDS: u64 = 5;
+/// Characters a variable used in a URL may not contain, so it can't change more of the URL than its own part, e.g. the host
+const URL_RESERVED_CHARACTERS: [char; 4] = ['/', '?', '#', '@'];
+
+/**
+Check that every device refers to an existing definition and provides all variables the definition requires.
+Variables used in a URL may not contain '/', '?', '#' or '@'
+
+## Parameters
+    settings: The settings of the Service being added
+    definitions: The HTTP device definitions from services.jsonc
+
+## Returns
+    Err: A message describing what is wrong
+    Ok: If the settings are valid
+*/
+pub fn validate(settings: &HttpSettings, definitions: &[HttpDeviceDefinition]) -> Result<(), String> {
+    for (index, device) in settings.devices.iter().enumerate() {
+        if settings.devices.iter().skip(index + 1).any(|other| other.id == device.id) {
+            return Err(format!("Duplicate device id '{}'", device.id));
+        }
+
+        let definition = definitions.iter().find(|definition| definition.identifier == device.definition);
+        if definition.is_none() {
+            return Err(format!("Unknown definition '{}'", device.definition));
+        }
+
+        let definition = definition.unwrap();
+        for variable in &definition.variables {
+            let value = device.variables.get(variable);
+            if value.is_none() {
+                return Err(format!("Device '{}' is missing variable '{}'", device.id, variable));
+            }
+
+            if used_in_url(definition, variable) && value.unwrap().contains(URL_RESERVED_CHARACTERS) {
+                return Err(format!("Variable '{}' of device '{}' may not contain '/', '?', '#' or '@'", variable, device.id));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/**
+Query the state of all Devices of a Service
+
+## Parameters
+    db: An instance of Database
+    config: The configuration of the HTTP Service, holding the device definitions
+    service_id: The ID of the Service
+
+## Returns
+    Err: If the settings of the Service could not be loaded
+    Ok: The Devices. Devices of which the state could not be queried are reported as offline
+*/
+pub fn get_devices(db: Database, config: &ServicesConfig, service_id: String) -> Result<Vec<Device>, ()> {
+    let settings = load_settings(db, service_id)?;
+
+    let mut devices: Vec<Device> = vec![];
+    for http_device in &settings.devices {
+        let definition = get_definition(config, &http_device.definition)?;
+        let state = query_state(http_device, definition).unwrap_or_default();
+
+        devices.push(to_device(http_device, definition, state));
+    }
+
+    Ok(devices)
+}
+
+/**
+Execute a command on a Device by sending the request defined for it
+
+## Parameters
+    db: An instance of Database
+    config: The configuration of the HTTP Service, holding the device definitions
+    service_id: The ID of the Service
+    device_id: The ID of the Device
+    command: The command to execute
+
+## Returns
+    Err: If an error occurred, or if the definition has no request for the command
+    Ok: If the device accepted the request
+*/
+pub fn execute(db: Database, config: &ServicesConfig, service_id: String, device_id: String, command: DeviceCommand) -> Result<(), ()> {
+    let settings = load_settings(db, service_id)?;
+
+    let http_device = settings.devices.iter().find(|device| device.id == device_id);
+    if http_device.is_none() {
+        eprintln!("Unknown HTTP device '{}'", device_id);
+        return Err(());
+    }
+
+    let http_device = http_device.unwrap();
+    let definition = get_definition(config, &http_device.definition)?;
+
+    let (http_command, value) = match command {
+        DeviceCommand::ONOFF(on) => (HttpCommand::ONOFF, json!(on)),
+        DeviceCommand::BRIGHTNESS(brightness) => (HttpCommand::BRIGHTNESS, json!(brightness)),
+        DeviceCommand::SETPOINT(temperature) => (HttpCommand::SETPOINT, json!(temperature)),
+        DeviceCommand::FANSPEED(speed) => (HttpCommand::FANSPEED, json!(speed))
+    };
+
+    let template = definition.commands.get(&http_command);
+    if template.is_none() {
+        eprintln!("Definition '{}' has no request for {:?}", definition.identifier, http_command);
+        return Err(());
+    }
+
+    let mut context = variables_context(http_device);
+    context.insert("value", &value);
+
+    send(template.unwrap(), &context).map(|_| ())
+}
+
+fn load_settings(db: Database, service_id: String) -> Result<HttpSettings, ()> {
+    let settings = get_settings::<HttpSettings>(db, service_id.clone());
+    if settings.is_err() {
+        eprintln!("An error occurred: {:?}", settings.err());
+        return Err(());
+    }
+
+    let settings_unwrapped = settings.unwrap();
+    if settings_unwrapped.is_none() {
+        eprintln!("No HTTP settings found for Service '{}'", service_id);
+        return Err(());
+    }
+
+    Ok(settings_unwrapped.unwrap())
+}
+
+fn get_definition<'a>(config: &'a ServicesConfig, identifier: &str) -> Result<&'a HttpDeviceDefinition, ()> {
+    let definition = config.http_definitions.as_ref()
+        .and_then(|definitions| definitions.iter().find(|definition| definition.identifier == identifier));
+
+    if definition.is_none() {
+        eprintln!("HTTP device definition '{}' does not exist (anymore)", identifier);
+        return Err(());
+    }
+
+    Ok(definition.unwrap())
+}
+
+/**
+Check if a variable is used in the URL of any request of a definition, e.g. 'http://{{ host }}/status'
+*/
+fn used_in_url(definition: &HttpDeviceDefinition, variable: &str) -> bool {
+    let mut urls = definition.state.iter().map(|query| &query.request.url).chain(definition.commands.values().map(|template| &template.url));
+    urls.any(|url| url.split("{{").skip(1).any(|expression| {
+        let expression = expression.split("}}").next().unwrap_or("");
+        expression.split('|').next().unwrap_or("").trim() == variable
+    }))
+}
+
+fn variables_context(http_device: &HttpDevice) -> Context {
+    let mut context = Context::new();
+    for (name, value) in &http_device.variables {
+        context.insert(name.as_str(), value);
+    }
+
+    context
+}
+
+fn to_device(http_device: &HttpDevice, definition: &HttpDeviceDefinition, state: DeviceState) -> Device {
+    let mut capabilities: Vec<DeviceCapability> = vec![];
+    let has_field = |field: HttpStateField| definition.state.as_ref().map(|query| query.mappings.iter().any(|mapping| mapping.field == field)).unwrap_or(false);
+
+    if definition.commands.contains_key(&HttpCommand::ONOFF) {
+        capabilities.push(DeviceCapability::ONOFF);
+    }
+
+    if definition.commands.contains_key(&HttpCommand::BRIGHTNESS) {
+        capabilities.push(DeviceCapability::BRIGHTNESS);
+    }
+
+    if definition.commands.contains_key(&HttpCommand::SETPOINT) {
+        capabilities.push(DeviceCapability::TEMPERATURESETTING);
+    } else if has_field(HttpStateField::AMBIENTTEMPERATURE) {
+        capabilities.push(DeviceCapability::TEMPERATUREREADING);
+    }
+
+    if definition.commands.contains_key(&HttpCommand::FANSPEED) {
+        capabilities.push(DeviceCapability::FANSPEED);
+    }
+
+    if has_field(HttpStateField::HUMIDITY) || has_field(HttpStateField::CO2) || has_field(HttpStateField::POWER) {
+        capabilities.push(DeviceCapability::SENSORSTATE);
+    }
+
+    let (min_setpoint, max_setpoint) = if definition.kind == DeviceKind::THERMOSTAT { (Some(5.0), Some(30.0)) } else { (None, None) };
+
+    Device {
+        id: http_device.id.clone(),
+        name: http_device.name.clone(),
+        kind: definition.kind.clone(),
+        capabilities,
+        manufacturer: "HTTP".to_string(),
+        model: definition.name.clone(),
+        min_setpoint,
+        max_setpoint,
+        state
+    }
+}
+
+/**
+Query the state of a Device and apply the mappings of its definition to the response
+
+## Returns
+    Err: If the device could not be reached, or its response isn't JSON
+    Ok: The state
+*/
+fn query_state(http_device: &HttpDevice, definition: &HttpDeviceDefinition) -> Result<DeviceState, ()> {
+    //Devices without a state query are assumed to be reachable
+    if definition.state.is_none() {
+        return Ok(DeviceState { online: true, ..DeviceState::default() });
+    }
+
+    let query = definition.state.as_ref().unwrap();
+    let response = send(&query.request, &variables_context(http_device))?;
+
+    let json = serde_json::from_str::<Value>(&response);
+    if json.is_err() {
+        eprintln!("Response of HTTP device '{}' is not valid JSON: {:?}", http_device.id, json.err());
+        return Err(());
+    }
+
+    let json = json.unwrap();
+    let mut state = DeviceState { online: true, ..DeviceState::default() };
+    for mapping in &query.mappings {
+        let value = json_path(&json, &mapping.path);
+        if value.is_none() {
+            continue;
+        }
+
+        apply_mapping(&mut state, mapping, value.unwrap());
+    }
+
+    Ok(state)
+}
+
+fn apply_mapping(state: &mut DeviceState, mapping: &HttpStateMapping, value: &Value) {
+    let number = match value {
+        Value::Number(number) => number.as_f64().map(|number| number as f32 * mapping.scale.unwrap_or(1.0)),
+        Value::String(string) => string.parse::<f32>().ok().map(|number| number * mapping.scale.unwrap_or(1.0)),
+        Value::Bool(boolean) => Some(if *boolean { 1.0 } else { 0.0 }),
+        _ => None
+    };
+
+    match mapping.field {
+        HttpStateField::ON => {
+            let on = match &mapping.on_value {
+                Some(on_value) => value == on_value,
+                None => matches!(value, Value::Bool(true)) || number.map(|number| number != 0.0).unwrap_or(false)
+                    || value.as_str().map(|string| string.eq_ignore_ascii_case("on") || string.eq_ignore_ascii_case("true")).unwrap_or(false)
+            };
+
+            state.on = Some(on);
+        },
+        HttpStateField::BRIGHTNESS => state.brightness = number.map(|number| number.round().clamp(0.0, 100.0) as u8),
+        HttpStateField::AMBIENTTEMPERATURE => state.ambient_temperature = number,
+        HttpStateField::SETPOINT => state.setpoint_temperature = number,
+        HttpStateField::HUMIDITY => state.humidity = number,
+        HttpStateField::CO2 => state.co2 = number,
+        HttpStateField::POWER => state.power = number,
+        HttpStateField::FANSPEED => state.fan_speed = number.map(|number| number.round().clamp(0.0, 100.0) as u8)
+    }
+}
+
+/**
+Resolve a JSON path. Supported are child members ('$.name'), quoted child members for names with dots or spaces ("$['name with spaces']")
+and array indices ('$[0]'). The leading '$' is optional, and so is the dot before the first member ('$relays[0]')
+
+## Returns
+    None: If the path does not exist in the value, or is invalid
+    Some: The value at the path
+*/
+pub fn json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let path = if path.is_empty() || path.starts_with('.') || path.starts_with('[') { path.to_string() } else { format!(".{}", path) };
+
+    let mut current = value;
+    let mut chars = path.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                let mut name = String::new();
+                while let Some(next) = chars.peek() {
+                    if *next == '.' || *next == '[' {
+                        break;
+                    }
+
+                    name.push(chars.next().unwrap());
+                }
+
+                current = current.get(name.as_str())?;
+            },
+            '[' => {
+                let mut index = String::new();
+                for next in chars.by_ref() {
+                    if next == ']' {
+                        break;
+                    }
+
+                    index.push(next);
+                }
+
+                //Quoted member names, e.g. ['name with spaces']
+                let quoted = index.trim_matches(|c| c == '\'' || c == '"');
+                current = match index.parse::<usize>() {
+                    Ok(index) => current.get(index)?,
+                    Err(_) => current.get(quoted)?
+                };
+            },
+            _ => return None
+        }
+    }
+
+    Some(current)
+}
+
+/**
+Render and send a request
+
+## Returns
+    Err: If a template could not be rendered, the request failed or the device returned an error status
+    Ok: The response body
+*/
+fn send(template: &HttpRequestTemplate, context: &Context) -> Result<String, ()> {
+    let render = |input: &str| Tera::one_off(input, context, false).map_err(|e| eprintln!("Unable to render HTTP template: {:?}", e));
+
+    let url = render(&template.url)?;
+    let method = reqwest::Method::from_bytes(template.method.as_deref().unwrap_or("GET").to_uppercase().as_bytes());
+    if method.is_err() {
+        eprintln!("Invalid HTTP method {:?}", template.method);
+        return Err(());
+    }
+
+    let client = reqwest::blocking::Client::builder().timeout(Duration::from_secs(TIMEOUT_SECONDS)).build().unwrap();
+    let mut request = client.request(method.unwrap(), &url);
+
+    if let Some(headers) = &template.headers {
+        for (name, value) in headers {
+            request = request.header(name.as_str(), render(value)?);
+        }
+    }
+
+    if let Some(body) = &template.body {
+        request = request.body(render(body)?);
+    }
+
+    let response = request.send();
+    if response.is_err() {
+        eprintln!("An error occurred: {:?}", response.err());
+        return Err(());
+    }
+
+    let response = response.unwrap();
+    if !response.status().is_success() {
+        eprintln!("HTTP device returned status {} for {}", response.status(), url);
+        return Err(());
+    }
+
+    response.text().map_err(|e| eprintln!("Unable to read HTTP device response: {:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::http::HttpStateQuery;
+    use std::collections::HashMap;
+
+    fn mapping(field: HttpStateField, scale: Option<f32>, on_value: Option<Value>) -> HttpStateMapping {
+        HttpStateMapping { field, path: "$.value".to_string(), scale, on_value }
+    }
+
+    fn request(url: &str) -> HttpRequestTemplate {
+        HttpRequestTemplate { method: None, url: url.to_string(), headers: None, body: None }
+    }
+
+    #[test]
+    fn json_paths_are_resolved() {
+        let value = json!({
+            "meters": { "power": 12.5 },
+            "relays": [{ "ison": true }, { "ison": false }],
+            "temperature.c": 21.5,
+            "status name": "ok"
+        });
+
+        assert_eq!(json_path(&value, "$.meters.power"), Some(&json!(12.5)));
+        assert_eq!(json_path(&value, "$.relays[1].ison"), Some(&json!(false)));
+        assert_eq!(json_path(&value, "$['temperature.c']"), Some(&json!(21.5)));
+        assert_eq!(json_path(&value, "$[\"status name\"]"), Some(&json!("ok")));
+        assert_eq!(json_path(&value, "$"), Some(&value));
+
+        //The dot before the first member and the '$' may be left out
+        assert_eq!(json_path(&value, "$meters.power"), Some(&json!(12.5)));
+        assert_eq!(json_path(&value, "relays[0].ison"), Some(&json!(true)));
+    }
+
+    #[test]
+    fn missing_json_paths_are_none() {
+        let value = json!({ "relays": [{ "ison": true }] });
+
+        assert_eq!(json_path(&value, "$.missing"), None);
+        assert_eq!(json_path(&value, "$.relays[1].ison"), None);
+        assert_eq!(json_path(&value, "$.relays.ison"), None);
+        assert_eq!(json_path(&value, "$['missing']"), None);
+    }
+
+    #[test]
+    fn mappings_are_applied_to_the_state() {
+        let mut state = DeviceState::default();
+
+        apply_mapping(&mut state, &mapping(HttpStateField::AMBIENTTEMPERATURE, Some(0.1), None), &json!(215));
+        assert_eq!(state.ambient_temperature, Some(21.5));
+
+        apply_mapping(&mut state, &mapping(HttpStateField::POWER, None, None), &json!("1500"));
+        assert_eq!(state.power, Some(1500.0));
+
+        apply_mapping(&mut state, &mapping(HttpStateField::BRIGHTNESS, None, None), &json!(140));
+        assert_eq!(state.brightness, Some(100));
+
+        apply_mapping(&mut state, &mapping(HttpStateField::HUMIDITY, None, None), &json!({ "not": "a number" }));
+        assert_eq!(state.humidity, None);
+    }
+
+    #[test]
+    fn on_is_mapped_from_common_values_or_on_value() {
+        let mut state = DeviceState::default();
+        let on = |state: &mut DeviceState, mapping: &HttpStateMapping, value: Value| {
+            apply_mapping(state, mapping, &value);
+            state.on
+        };
+
+        let default = mapping(HttpStateField::ON, None, None);
+        assert_eq!(on(&mut state, &default, json!(true)), Some(true));
+        assert_eq!(on(&mut state, &default, json!(1)), Some(true));
+        assert_eq!(on(&mut state, &default, json!("ON")), Some(true));
+        assert_eq!(on(&mut state, &default, json!(0)), Some(false));
+        assert_eq!(on(&mut state, &default, json!("off")), Some(false));
+
+        let running = mapping(HttpStateField::ON, None, Some(json!("running")));
+        assert_eq!(on(&mut state, &running, json!("running")), Some(true));
+        assert_eq!(on(&mut state, &running, json!(true)), Some(false));
+    }
+
+    #[test]
+    fn variables_in_urls_can_only_fill_in_their_part() {
+        let definition = HttpDeviceDefinition {
+            identifier: "relay".to_string(),
+            name: "Relay".to_string(),
+            kind: DeviceKind::SWITCH,
+            variables: vec!["host".to_string(), "token".to_string()],
+            state: Some(HttpStateQuery { request: request("http://{{ host }}/status"), mappings: vec![] }),
+            commands: HashMap::from([(HttpCommand::ONOFF, HttpRequestTemplate { body: Some("{{ token }}".to_string()), ..request("http://{{host|lower}}/relay?turn={{ value }}") })])
+        };
+
+        let settings = |host: &str, token: &str| HttpSettings {
+            devices: vec![HttpDevice {
+                id: "1".to_string(),
+                name: "Relay".to_string(),
+                definition: "relay".to_string(),
+                variables: HashMap::from([("host".to_string(), host.to_string()), ("token".to_string(), token.to_string())])
+            }]
+        };
+
+        let definitions = [definition];
+        assert!(validate(&settings("192.168.1.20", "a/b?c"), &definitions).is_ok());
+        assert!(validate(&settings("192.168.1.20:8080", "secret"), &definitions).is_ok());
+        assert!(validate(&settings("evil.example/x", "secret"), &definitions).is_err());
+        assert!(validate(&settings("user@evil.example", "secret"), &definitions).is_err());
+        assert!(validate(&settings("evil.example#", "secret"), &definitions).is_err());
+        assert!(validate(&settings("evil.example?", "secret"), &definitions).is_err());
+    }
+}
@@ -0,0 +1,759 @@
+use crate::common::service::get_settings;
+use crate::database::Database;
+use crate::types::knx::{KnxSettings, KnxDevice, KnxGroupAddress, GroupAddressRole, Dpt};
+use crate::types::device::{Device, DeviceKind, DeviceCapability, DeviceState, DeviceCommand};
+
+use std::collections::HashMap;
+use std::net::{UdpSocket, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Sender, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DEFAULT_PORT: u16 = 3671;
+
+const CONNECT_REQUEST: u16 = 0x0205;
+const CONNECT_RESPONSE: u16 = 0x0206;
+const CONNECTIONSTATE_REQUEST: u16 = 0x0207;
+const CONNECTIONSTATE_RESPONSE: u16 = 0x0208;
+const DISCONNECT_REQUEST: u16 = 0x0209;
+const DISCONNECT_RESPONSE: u16 = 0x020A;
+const TUNNELING_REQUEST: u16 = 0x0420;
+const TUNNELING_ACK: u16 = 0x0421;
+
+const CEMI_L_DATA_REQ: u8 = 0x11;
+const CEMI_L_DATA_CON: u8 = 0x2E;
+const CEMI_L_DATA_IND: u8 = 0x29;
+
+const APCI_GROUP_VALUE_READ: u8 = 0;
+const APCI_GROUP_VALUE_RESPONSE: u8 = 1;
+const APCI_GROUP_VALUE_WRITE: u8 = 2;
+
+/// KNXnet/IP requires a heartbeat at least every 120 seconds
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+const ACK_TIMEOUT: Duration = Duration::from_secs(1);
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+/**
+All running tunnels, by service_id
+*/
+pub type KnxTunnels = Arc<Mutex<HashMap<String, KnxTunnelHandle>>>;
+
+/**
+Handle to a running tunnel
+*/
+#[derive(Clone)]
+pub struct KnxTunnelHandle {
+    /// Telegrams to send on the bus
+    sender:     Sender<Telegram>,
+    /// The last value seen on the bus, by group address
+    values:     Arc<Mutex<HashMap<u16, Vec<u8>>>>,
+    connected:  Arc<AtomicBool>,
+    /// Set to stop the tunnel
+    stopped:    Arc<AtomicBool>
+}
+
+/**
+A group telegram to send on the bus
+*/
+#[allow(clippy::upper_case_acronyms)]
+enum Telegram {
+    READ(u16),
+    /// Group address, data and whether the data fits in the 6 bits of the APCI byte
+    WRITE(u16, Vec<u8>, bool)
+}
+
+/**
+Check that device ids are unique and that every group address can be parsed
+
+## Parameters
+    settings: The settings of the Service being added
+
+## Returns
+    Err: A message describing what is wrong
+    Ok: If the settings are valid
+*/
+pub fn validate(settings: &KnxSettings) -> Result<(), String> {
+    for (index, device) in settings.devices.iter().enumerate() {
+        if settings.devices.iter().skip(index + 1).any(|other| other.id == device.id) {
+            return Err(format!("Duplicate device id '{}'", device.id));
+        }
+
+        for group_address in &device.addresses {
+            if parse_group_address(&group_address.address).is_none() {
+                return Err(format!("Invalid group address '{}' of device '{}'", group_address.address, device.id));
+            }
+
+            if let Some(status_address) = &group_address.status_address {
+                if parse_group_address(status_address).is_none() {
+                    return Err(format!("Invalid status address '{}' of device '{}'", status_address, device.id));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/**
+Start the tunnel for a Service if it isn't running yet.
+The tunnel reconnects by itself when the connection to the gateway is lost
+
+## Parameters
+    tunnels: The running tunnels
+    db: An instance of Database
+    service_id: The ID of the Service
+
+## Returns
+    Err: If the settings of the Service could not be loaded, or are not valid
+    Ok: The handle of the running tunnel
+*/
+pub fn ensure_tunnel(tunnels: &KnxTunnels, db: Database, service_id: String) -> Result<KnxTunnelHandle, ()> {
+    if let Some(handle) = tunnels.lock().unwrap().get(&service_id) {
+        return Ok(handle.clone());
+    }
+
+    let settings = load_settings(db, service_id.clone())?.ok_or(())?;
+
+    let (sender, receiver) = channel::<Telegram>();
+    let handle = KnxTunnelHandle {
+        sender,
+        values: Arc::new(Mutex::new(HashMap::new())),
+        connected: Arc::new(AtomicBool::new(false)),
+        stopped: Arc::new(AtomicBool::new(false))
+    };
+
+    {
+        //Another thread might have started the tunnel while we were loading the settings
+        let mut tunnels = tunnels.lock().unwrap();
+        if let Some(handle) = tunnels.get(&service_id) {
+            return Ok(handle.clone());
+        }
+
+        tunnels.insert(service_id.clone(), handle.clone());
+    }
+
+    let tunnel_handle = handle.clone();
+    thread::spawn(move || {
+        run_tunnel(service_id, settings, tunnel_handle, receiver);
+    });
+
+    Ok(handle)
+}
+
+/**
+Stop the tunnel of a Service, if it is running
+
+## Parameters
+    tunnels: The running tunnels
+    service_id: The ID of the Service
+*/
+pub fn stop_tunnel(tunnels: &KnxTunnels, service_id: &str) {
+    if let Some(handle) = tunnels.lock().unwrap().remove(service_id) {
+        handle.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+/**
+Get the Devices of a Service, with their state as last seen on the bus
+
+## Parameters
+    tunnels: The running tunnels
+    db: An instance of Database
+    service_id: The ID of the Service
+
+## Returns
+    Err: If an error occurred
+    Ok:
+        Some: The Devices of the Service
+        None: The Service has no valid settings, the User has to add it again
+*/
+pub fn get_devices(tunnels: &KnxTunnels, db: Database, service_id: String) -> Result<Option<Vec<Device>>, ()> {
+    let settings = load_settings(db.clone(), service_id.clone())?;
+    if settings.is_none() {
+        return Ok(None);
+    }
+
+    let settings = settings.unwrap();
+    let handle = ensure_tunnel(tunnels, db, service_id)?;
+
+    let connected = handle.connected.load(Ordering::SeqCst);
+    let values = handle.values.lock().unwrap();
+
+    let devices = settings.devices.iter()
+        .map(|knx_device| to_device(knx_device, connected, &values))
+        .collect();
+
+    Ok(Some(devices))
+}
+
+/**
+Execute a command on a Device by writing to the matching group address
+
+## Parameters
+    tunnels: The running tunnels
+    db: An instance of Database
+    service_id: The ID of the Service
+    device_id: The ID of the Device
+    command: The command to execute
+
+## Returns
+    Err: If an error occurred, if the tunnel is not connected, or if the Device has no writable group address for the command
+    Ok: If the telegram was queued
+*/
+pub fn execute(tunnels: &KnxTunnels, db: Database, service_id: String, device_id: String, command: DeviceCommand) -> Result<(), ()> {
+    let handle = ensure_tunnel(tunnels, db.clone(), service_id.clone())?;
+    let settings = load_settings(db, service_id.clone())?.ok_or(())?;
+
+    let knx_device = settings.devices.iter().find(|device| device.id == device_id);
+    if knx_device.is_none() {
+        eprintln!("Unknown KNX device '{}'", device_id);
+        return Err(());
+    }
+
+    let role = match command {
+        DeviceCommand::ONOFF(_) => GroupAddressRole::ONOFF,
+        DeviceCommand::BRIGHTNESS(_) => GroupAddressRole::BRIGHTNESS,
+        DeviceCommand::SETPOINT(_) => GroupAddressRole::SETPOINT,
+        DeviceCommand::FANSPEED(_) => {
+            eprintln!("Command {:?} is not supported by KNX devices", command);
+            return Err(());
+        }
+    };
+
+    let group_address = knx_device.unwrap().addresses.iter().find(|address| address.role == role && address.writable);
+    if group_address.is_none() {
+        eprintln!("KNX device '{}' has no writable group address for {:?}", device_id, role);
+        return Err(());
+    }
+
+    let group_address = group_address.unwrap();
+    let address = parse_group_address(&group_address.address);
+    if address.is_none() {
+        eprintln!("Invalid group address '{}'", group_address.address);
+        return Err(());
+    }
+
+    let data = match (&group_address.dpt, &command) {
+        (Dpt::SWITCH, DeviceCommand::ONOFF(on)) => vec![*on as u8],
+        (Dpt::SCALING, DeviceCommand::BRIGHTNESS(brightness)) => vec![(*brightness.min(&100) as f32 * 255.0 / 100.0).round() as u8],
+        (Dpt::SCALING, DeviceCommand::ONOFF(on)) => vec![if *on { 255 } else { 0 }],
+        (Dpt::TEMPERATURE, DeviceCommand::SETPOINT(temperature)) => encode_dpt9(*temperature).to_vec(),
+        _ => {
+            eprintln!("Command {:?} does not match DPT {:?} of group address '{}'", command, group_address.dpt, group_address.address);
+            return Err(());
+        }
+    };
+
+    if !handle.connected.load(Ordering::SeqCst) {
+        eprintln!("KNX tunnel for Service '{}' is not connected", service_id);
+        return Err(());
+    }
+
+    let short = group_address.dpt == Dpt::SWITCH;
+    if handle.sender.send(Telegram::WRITE(address.unwrap(), data.clone(), short)).is_err() {
+        return Err(());
+    }
+
+    //Assume the actuator follows, it will report its actual state on the status address
+    let status_address = group_address.status_address.as_ref().and_then(|address| parse_group_address(address)).unwrap_or_else(|| address.unwrap());
+    handle.values.lock().unwrap().insert(status_address, data);
+
+    Ok(())
+}
+
+/**
+Load the settings of a Service
+
+## Returns
+    Err: If an error occurred
+    Ok:
+        Some: The settings
+        None: The Service has no settings, or they are not valid
+*/
+fn load_settings(db: Database, service_id: String) -> Result<Option<KnxSettings>, ()> {
+    let settings = get_settings::<KnxSettings>(db, service_id.clone());
+    if settings.is_err() {
+        eprintln!("An error occurred: {:?}", settings.err());
+        return Err(());
+    }
+
+    let settings_unwrapped = settings.unwrap();
+    if settings_unwrapped.is_none() {
+        eprintln!("No KNX settings found for Service '{}'", service_id);
+        return Ok(None);
+    }
+
+    let settings_unwrapped = settings_unwrapped.unwrap();
+    if let Err(message) = validate(&settings_unwrapped) {
+        eprintln!("Stored KNX settings for Service '{}' are invalid: {}", service_id, message);
+        return Ok(None);
+    }
+
+    Ok(Some(settings_unwrapped))
+}
+
+/**
+Parse a group address in 3-level (main/middle/sub) or 2-level (main/sub) notation
+*/
+pub fn parse_group_address(address: &str) -> Option<u16> {
+    let parts: Vec<u16> = address.split('/').map(|part| part.parse::<u16>().ok()).collect::<Option<Vec<u16>>>()?;
+    match parts.as_slice() {
+        [main, middle, sub] if *main < 32 && *middle < 8 && *sub < 256 => Some((main << 11) | (middle << 8) | sub),
+        [main, sub] if *main < 32 && *sub < 2048 => Some((main << 11) | sub),
+        _ => None
+    }
+}
+
+fn status_address_of(group_address: &KnxGroupAddress) -> Option<u16> {
+    parse_group_address(group_address.status_address.as_ref().unwrap_or(&group_address.address))
+}
+
+fn to_device(knx_device: &KnxDevice, connected: bool, values: &HashMap<u16, Vec<u8>>) -> Device {
+    let mut capabilities: Vec<DeviceCapability> = vec![];
+    let mut state = DeviceState { online: connected, ..DeviceState::default() };
+
+    for group_address in &knx_device.addresses {
+        let value = status_address_of(group_address).and_then(|address| values.get(&address));
+
+        match group_address.role {
+            GroupAddressRole::ONOFF => {
+                if group_address.writable {
+                    capabilities.push(DeviceCapability::ONOFF);
+                }
+
+                state.on = value.map(|data| data.first().unwrap_or(&0) & 0x01 == 1);
+            },
+            GroupAddressRole::BRIGHTNESS => {
+                if group_address.writable {
+                    capabilities.push(DeviceCapability::BRIGHTNESS);
+                }
+
+                state.brightness = value.and_then(|data| data.first()).map(|raw| (*raw as f32 * 100.0 / 255.0).round() as u8);
+            },
+            GroupAddressRole::SETPOINT => {
+                if group_address.writable {
+                    capabilities.push(DeviceCapability::TEMPERATURESETTING);
+                }
+
+                state.setpoint_temperature = value.and_then(|data| decode_dpt9(data));
+            },
+            GroupAddressRole::AMBIENTTEMPERATURE => {
+                state.ambient_temperature = value.and_then(|data| decode_dpt9(data));
+            }
+        }
+    }
+
+    let has_ambient = knx_device.addresses.iter().any(|address| address.role == GroupAddressRole::AMBIENTTEMPERATURE);
+    if has_ambient && !capabilities.contains(&DeviceCapability::TEMPERATURESETTING) {
+        capabilities.push(DeviceCapability::TEMPERATUREREADING);
+    }
+
+    let (min_setpoint, max_setpoint) = if knx_device.kind == DeviceKind::THERMOSTAT { (Some(5.0), Some(30.0)) } else { (None, None) };
+
+    Device {
+        id: knx_device.id.clone(),
+        name: knx_device.name.clone(),
+        kind: knx_device.kind.clone(),
+        capabilities,
+        manufacturer: "KNX".to_string(),
+        model: knx_device.kind.to_string(),
+        min_setpoint,
+        max_setpoint,
+        state
+    }
+}
+
+/**
+Decode a DPT 9 2-byte float. Bits: MEEEEMMM MMMMMMMM, value = 0.01 * M * 2^E with M in two's complement
+*/
+fn decode_dpt9(data: &[u8]) -> Option<f32> {
+    if data.len() < 2 {
+        return None;
+    }
+
+    let raw = u16::from_be_bytes([data[0], data[1]]);
+    if raw == 0x7FFF {
+        //Invalid data
+        return None;
+    }
+
+    let exponent = (raw >> 11) & 0x0F;
+    let mut mantissa = (raw & 0x07FF) as i32;
+    if raw & 0x8000 != 0 {
+        mantissa -= 2048;
+    }
+
+    Some(0.01 * mantissa as f32 * (1 << exponent) as f32)
+}
+
+fn encode_dpt9(value: f32) -> [u8; 2] {
+    let mut mantissa = value * 100.0;
+    let mut exponent: u16 = 0;
+    while !(-2048.0..=2047.0).contains(&mantissa) && exponent < 15 {
+        mantissa /= 2.0;
+        exponent += 1;
+    }
+
+    let mantissa = mantissa.round() as i32;
+    let sign: u16 = if mantissa < 0 { 0x8000 } else { 0 };
+    let raw = sign | (exponent << 11) | (mantissa as u16 & 0x07FF);
+
+    raw.to_be_bytes()
+}
+
+/**
+Keep a tunnel to the gateway open until it is stopped
+*/
+fn run_tunnel(service_id: String, settings: KnxSettings, handle: KnxTunnelHandle, receiver: Receiver<Telegram>) {
+    let status_addresses: Vec<u16> = settings.devices.iter()
+        .flat_map(|device| device.addresses.iter())
+        .filter_map(status_address_of)
+        .collect();
+
+    while !handle.stopped.load(Ordering::SeqCst) {
+        match Tunnel::connect(&settings.host, settings.port.unwrap_or(DEFAULT_PORT)) {
+            Ok(mut tunnel) => {
+                handle.connected.store(true, Ordering::SeqCst);
+
+                //Ask all actuators and sensors for their current state
+                for address in &status_addresses {
+                    let _ = handle.sender.send(Telegram::READ(*address));
+                }
+
+                tunnel.run(&receiver, &handle.values, &handle.stopped);
+                handle.connected.store(false, Ordering::SeqCst);
+                eprintln!("KNX tunnel for Service '{}' disconnected", service_id);
+            },
+            Err(_) => {
+                eprintln!("Unable to open KNX tunnel for Service '{}'", service_id);
+            }
+        }
+
+        //Telegrams queued while disconnected are dropped
+        while receiver.try_recv().is_ok() {}
+        thread::sleep(RECONNECT_DELAY);
+    }
+}
+
+struct Tunnel {
+    socket:         UdpSocket,
+    channel_id:     u8,
+    sequence:       u8
+}
+
+impl Tunnel {
+    fn connect(host: &str, port: u16) -> Result<Tunnel, ()> {
+        let address = (host, port).to_socket_addrs().ok().and_then(|mut addresses| addresses.next());
+        if address.is_none() {
+            eprintln!("Unable to resolve KNX gateway '{}'", host);
+            return Err(());
+        }
+
+        let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| eprintln!("Unable to bind UDP socket: {:?}", e))?;
+        socket.connect(address.unwrap()).map_err(|e| eprintln!("Unable to connect UDP socket: {:?}", e))?;
+        let _ = socket.set_read_timeout(Some(RESPONSE_TIMEOUT));
+
+        //Control and data endpoint, 0.0.0.0:0 tells the gateway to reply to the address it received the request from
+        let mut body: Vec<u8> = vec![];
+        body.extend_from_slice(&hpai());
+        body.extend_from_slice(&hpai());
+        //Connection request information: tunnel connection, link layer
+        body.extend_from_slice(&[0x04, 0x04, 0x02, 0x00]);
+
+        send_frame(&socket, CONNECT_REQUEST, &body)?;
+
+        let mut buf = [0u8; 512];
+        let received = socket.recv(&mut buf).map_err(|e| eprintln!("No CONNECT_RESPONSE from KNX gateway: {:?}", e))?;
+        let (service, body) = parse_frame(&buf[..received]).ok_or(())?;
+        if service != CONNECT_RESPONSE || body.len() < 2 {
+            eprintln!("Unexpected response from KNX gateway");
+            return Err(());
+        }
+
+        if body[1] != 0 {
+            eprintln!("KNX gateway refused the connection, status {:#04x}", body[1]);
+            return Err(());
+        }
+
+        Ok(Tunnel { socket, channel_id: body[0], sequence: 0 })
+    }
+
+    /**
+    Process telegrams until the connection is lost, or the tunnel is stopped
+    */
+    fn run(&mut self, receiver: &Receiver<Telegram>, values: &Arc<Mutex<HashMap<u16, Vec<u8>>>>, stopped: &AtomicBool) {
+        let _ = self.socket.set_read_timeout(Some(Duration::from_millis(250)));
+
+        let mut last_heartbeat = Instant::now();
+        let mut heartbeat_pending: Option<Instant> = None;
+
+        //The frame we're waiting on an ACK for: sequence, body, sent at, retried
+        let mut awaiting_ack: Option<(u8, Vec<u8>, Instant, bool)> = None;
+
+        let mut buf = [0u8; 512];
+        while !stopped.load(Ordering::SeqCst) {
+            //Only one tunneling request may be outstanding
+            if awaiting_ack.is_none() {
+                match receiver.try_recv() {
+                    Ok(telegram) => {
+                        let body = self.tunneling_request(&telegram);
+                        if send_frame(&self.socket, TUNNELING_REQUEST, &body).is_err() {
+                            return;
+                        }
+
+                        awaiting_ack = Some((self.sequence, body, Instant::now(), false));
+                    },
+                    Err(TryRecvError::Empty) => {},
+                    Err(TryRecvError::Disconnected) => return
+                }
+            }
+
+            if let Some((sequence, body, sent_at, retried)) = awaiting_ack.take() {
+                if sent_at.elapsed() < ACK_TIMEOUT {
+                    awaiting_ack = Some((sequence, body, sent_at, retried));
+                } else if !retried {
+                    if send_frame(&self.socket, TUNNELING_REQUEST, &body).is_err() {
+                        return;
+                    }
+
+                    awaiting_ack = Some((sequence, body, Instant::now(), true));
+                } else {
+                    eprintln!("KNX gateway did not acknowledge tunneling request");
+                    return;
+                }
+            }
+
+            if last_heartbeat.elapsed() >= HEARTBEAT_INTERVAL {
+                let mut body = vec![self.channel_id, 0x00];
+                body.extend_from_slice(&hpai());
+                if send_frame(&self.socket, CONNECTIONSTATE_REQUEST, &body).is_err() {
+                    return;
+                }
+
+                last_heartbeat = Instant::now();
+                heartbeat_pending = Some(Instant::now());
+            }
+
+            if heartbeat_pending.map(|sent_at| sent_at.elapsed() > RESPONSE_TIMEOUT).unwrap_or(false) {
+                eprintln!("KNX gateway did not respond to heartbeat");
+                return;
+            }
+
+            let received = match self.socket.recv(&mut buf) {
+                Ok(received) => received,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock || e.kind() == std::io::ErrorKind::TimedOut => continue,
+                Err(e) => {
+                    eprintln!("Unable to read from KNX gateway: {:?}", e);
+                    return;
+                }
+            };
+
+            let frame = parse_frame(&buf[..received]);
+            if frame.is_none() {
+                continue;
+            }
+
+            let (service, body) = frame.unwrap();
+            match service {
+                TUNNELING_REQUEST => {
+                    //Connection header: length, channel ID, sequence, reserved
+                    if body.len() < 4 || body[1] != self.channel_id {
+                        continue;
+                    }
+
+                    let ack = [0x04, self.channel_id, body[2], 0x00];
+                    if send_frame(&self.socket, TUNNELING_ACK, &ack).is_err() {
+                        return;
+                    }
+
+                    if let Some((address, data)) = parse_cemi(&body[4..]) {
+                        values.lock().unwrap().insert(address, data);
+                    }
+                },
+                TUNNELING_ACK if body.len() >= 4 && awaiting_ack.as_ref().map(|(sequence, _, _, _)| *sequence == body[2]).unwrap_or(false) => {
+                    awaiting_ack = None;
+                    self.sequence = self.sequence.wrapping_add(1);
+                },
+                CONNECTIONSTATE_RESPONSE => {
+                    if body.len() >= 2 && body[1] != 0 {
+                        eprintln!("KNX gateway reports connection error {:#04x}", body[1]);
+                        return;
+                    }
+
+                    heartbeat_pending = None;
+                },
+                DISCONNECT_REQUEST => {
+                    let _ = send_frame(&self.socket, DISCONNECT_RESPONSE, &[self.channel_id, 0x00]);
+                    return;
+                },
+                _ => {}
+            }
+        }
+
+        //Free the channel on the gateway, most only have a few
+        let mut body = vec![self.channel_id, 0x00];
+        body.extend_from_slice(&hpai());
+        let _ = send_frame(&self.socket, DISCONNECT_REQUEST, &body);
+    }
+
+    fn tunneling_request(&self, telegram: &Telegram) -> Vec<u8> {
+        let (address, apdu) = match telegram {
+            Telegram::READ(address) => (*address, vec![0x00, APCI_GROUP_VALUE_READ << 6]),
+            Telegram::WRITE(address, data, true) => (*address, vec![0x00, (APCI_GROUP_VALUE_WRITE << 6) | (data.first().unwrap_or(&0) & 0x3F)]),
+            Telegram::WRITE(address, data, false) => {
+                let mut apdu = vec![0x00, APCI_GROUP_VALUE_WRITE << 6];
+                apdu.extend_from_slice(data);
+                (*address, apdu)
+            }
+        };
+
+        let mut body = vec![0x04, self.channel_id, self.sequence, 0x00];
+
+        //cEMI L_Data.req: no additional info, standard frame, group address, hop count 6, source filled in by the gateway
+        body.extend_from_slice(&[CEMI_L_DATA_REQ, 0x00, 0xBC, 0xE0, 0x00, 0x00]);
+        body.extend_from_slice(&address.to_be_bytes());
+        body.push((apdu.len() - 1) as u8);
+        body.extend_from_slice(&apdu);
+
+        body
+    }
+}
+
+fn hpai() -> [u8; 8] {
+    [0x08, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+}
+
+fn send_frame(socket: &UdpSocket, service: u16, body: &[u8]) -> Result<(), ()> {
+    let mut frame: Vec<u8> = vec![0x06, 0x10];
+    frame.extend_from_slice(&service.to_be_bytes());
+    frame.extend_from_slice(&((body.len() + 6) as u16).to_be_bytes());
+    frame.extend_from_slice(body);
+
+    socket.send(&frame).map(|_| ()).map_err(|e| eprintln!("Unable to send to KNX gateway: {:?}", e))
+}
+
+fn parse_frame(frame: &[u8]) -> Option<(u16, &[u8])> {
+    if frame.len() < 6 || frame[0] != 0x06 || frame[1] != 0x10 {
+        return None;
+    }
+
+    let service = u16::from_be_bytes([frame[2], frame[3]]);
+    Some((service, &frame[6..]))
+}
+
+/**
+Parse a cEMI frame into the group address and value it carries
+
+## Returns
+    None: If the frame is not a group value write or response
+    Some: (group address, value)
+*/
+fn parse_cemi(cemi: &[u8]) -> Option<(u16, Vec<u8>)> {
+    let message_code = *cemi.first()?;
+    if message_code != CEMI_L_DATA_IND && message_code != CEMI_L_DATA_CON {
+        return None;
+    }
+
+    let frame = cemi.get(2 + *cemi.get(1)? as usize..)?;
+
+    //ctrl1, ctrl2, source (2), destination (2), length, TPCI/APCI, APCI/data
+    if frame.len() < 9 || frame[1] & 0x80 == 0 {
+        //Not addressed to a group
+        return None;
+    }
+
+    let address = u16::from_be_bytes([frame[4], frame[5]]);
+    let length = frame[6] as usize;
+    let apci = ((frame[7] & 0x03) << 2) | (frame[8] >> 6);
+    if apci != APCI_GROUP_VALUE_WRITE && apci != APCI_GROUP_VALUE_RESPONSE {
+        return None;
+    }
+
+    let value = if length == 1 {
+        vec![frame[8] & 0x3F]
+    } else {
+        frame.get(9..8 + length)?.to_vec()
+    };
+
+    Some((address, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        let settings = |id: &str, address: &str, status_address: Option<&str>| KnxSettings {
+            host: "localhost".to_string(),
+            port: None,
+            devices: vec![KnxDevice {
+                id: id.to_string(),
+                name: "Light".to_string(),
+                kind: DeviceKind::LIGHT,
+                addresses: vec![KnxGroupAddress { role: GroupAddressRole::ONOFF, address: address.to_string(), status_address: status_address.map(|address| address.to_string()), dpt: Dpt::SWITCH, writable: true }]
+            }]
+        };
+
+        assert!(validate(&settings("1", "1/2/3", Some("1/2/4"))).is_ok());
+        assert!(validate(&settings("1", "1/2047", None)).is_ok());
+        assert!(validate(&settings("1", "1/2048", None)).is_err());
+        assert!(validate(&settings("1", "1/8/3", None)).is_err());
+        assert!(validate(&settings("1", "1/2/3", Some("light"))).is_err());
+
+        let mut duplicate = settings("1", "1/2/3", None);
+        duplicate.devices.push(duplicate.devices[0].clone());
+        assert!(validate(&duplicate).is_err());
+    }
+
+    #[test]
+    fn dpt9_is_decoded() {
+        assert_eq!(decode_dpt9(&[0x0C, 0x1A]), Some(21.0));
+        assert_eq!(decode_dpt9(&[0x87, 0x9C]), Some(-1.0));
+        assert_eq!(decode_dpt9(&[0x00, 0x00]), Some(0.0));
+        assert_eq!(decode_dpt9(&[0x7F, 0xFF]), None);
+        assert_eq!(decode_dpt9(&[0x0C]), None);
+    }
+
+    #[test]
+    fn dpt9_is_encoded() {
+        assert_eq!(encode_dpt9(21.0), [0x0C, 0x1A]);
+        assert_eq!(encode_dpt9(-1.0), [0x87, 0x9C]);
+        assert_eq!(encode_dpt9(0.0), [0x00, 0x00]);
+
+        for value in [-30.0, -0.5, 5.5, 19.25, 20.5, 100.0] {
+            assert_eq!(decode_dpt9(&encode_dpt9(value)), Some(value));
+        }
+
+        //Large values lose precision to the exponent
+        let decoded = decode_dpt9(&encode_dpt9(1234.5)).unwrap();
+        assert!((decoded - 1234.5).abs() <= 0.32);
+    }
+
+    #[test]
+    fn group_writes_are_parsed_from_cemi() {
+        //Short value, carried in the APCI byte
+        let cemi = [CEMI_L_DATA_IND, 0x00, 0xBC, 0xE0, 0x11, 0x01, 0x08, 0x01, 0x01, 0x00, 0x81];
+        assert_eq!(parse_cemi(&cemi), Some((0x0801, vec![0x01])));
+
+        //Long value, with additional info which is skipped
+        let cemi = [CEMI_L_DATA_CON, 0x02, 0xAA, 0xBB, 0xBC, 0xE0, 0x11, 0x01, 0x10, 0x02, 0x03, 0x00, 0x40, 0x0C, 0x1A];
+        assert_eq!(parse_cemi(&cemi), Some((0x1002, vec![0x0C, 0x1A])));
+    }
+
+    #[test]
+    fn other_cemi_frames_are_ignored() {
+        //Group value read
+        assert_eq!(parse_cemi(&[CEMI_L_DATA_IND, 0x00, 0xBC, 0xE0, 0x11, 0x01, 0x08, 0x01, 0x01, 0x00, 0x00]), None);
+        //Addressed to a device instead of a group
+        assert_eq!(parse_cemi(&[CEMI_L_DATA_IND, 0x00, 0xBC, 0x60, 0x11, 0x01, 0x08, 0x01, 0x01, 0x00, 0x81]), None);
+        //A request of our own
+        assert_eq!(parse_cemi(&[CEMI_L_DATA_REQ, 0x00, 0xBC, 0xE0, 0x00, 0x00, 0x08, 0x01, 0x01, 0x00, 0x81]), None);
+        //Truncated
+        assert_eq!(parse_cemi(&[CEMI_L_DATA_IND, 0x00, 0xBC, 0xE0, 0x11, 0x01, 0x08, 0x01, 0x03, 0x00, 0x80, 0x0C]), None);
+        assert_eq!(parse_cemi(&[]), None);
+    }
+}
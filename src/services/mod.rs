@@ -1 +1,264 @@
-pub mod honeywell;
\ No newline at end of file
+pub mod honeywell;
+pub mod home_assistant;
+pub mod tado;
+pub mod modbus;
+pub mod knx;
+pub mod http;
+pub mod netatmo;
+pub mod virtual_devices;
+
+use crate::appdata::AppData;
+use crate::config::{ServicesConfig, get_service_config};
+use crate::types::service::ServiceType;
+use crate::types::device::{Device, DeviceCommand, RegisteredDevice};
+
+/**
+Get all Devices provided by a Service
+
+## Parameters
+    data: An instance of AppData
+    service_id: The ID of the Service
+    service_type: The type of the Service
+
+## Returns
+    Err: If an error occurred
+    Ok:
+        Some: The Devices provided by the Service
+        None: The Service rejected its credentials, or has no valid settings. The User has to reconnect it
+*/
+pub fn get_devices(data: &AppData, service_id: String, service_type: ServiceType) -> Result<Option<Vec<Device>>, ()> {
+    let db = data.database.clone();
+    match service_type {
+        ServiceType::HONEYWELL => honeywell::get_devices(db, service_id),
+        ServiceType::HOME_ASSISTANT => home_assistant::get_devices(db, service_id),
+        ServiceType::TADO => tado::get_devices(db, &get_config(data, &service_type)?, service_id).map(Some),
+        ServiceType::MODBUS => modbus::get_devices(db, service_id),
+        ServiceType::KNX => knx::get_devices(&data.knx_tunnels, db, service_id),
+        ServiceType::HTTP => http::get_devices(db, &get_config(data, &service_type)?, service_id).map(Some),
+        ServiceType::NETATMO => netatmo::get_devices(db, &get_config(data, &service_type)?, service_id).map(Some),
+        ServiceType::VIRTUAL => virtual_devices::get_devices(db, service_id).map(Some)
+    }
+}
+
+/**
+Execute a command on a Device provided by a Service
+
+## Parameters
+    data: An instance of AppData
+    service_id: The ID of the Service
+    service_type: The type of the Service
+    device_id: The ID of the Device, as known by the Service
+    command: The command to execute
+
+## Returns
+    Err: If an error occurred, or if the command isn't supported by the Device
+    Ok: If the command was executed
+*/
+pub fn execute(data: &AppData, service_id: String, service_type: ServiceType, device_id: String, command: DeviceCommand) -> Result<(), ()> {
+    let db = data.database.clone();
+    match service_type {
+        ServiceType::HONEYWELL => {
+            match command {
+                DeviceCommand::SETPOINT(temperature) => honeywell::set_zone_temperature(db, service_id, device_id, temperature),
+                _ => {
+                    eprintln!("Command {:?} is not supported by HONEYWELL", command);
+                    Err(())
+                }
+            }
+        },
+        ServiceType::HOME_ASSISTANT => home_assistant::execute(db, service_id, device_id, command),
+        ServiceType::TADO => tado::execute(db, &get_config(data, &service_type)?, service_id, device_id, command),
+        ServiceType::MODBUS => modbus::execute(db, service_id, device_id, command),
+        ServiceType::KNX => knx::execute(&data.knx_tunnels, db, service_id, device_id, command),
+        ServiceType::HTTP => http::execute(db, &get_config(data, &service_type)?, service_id, device_id, command),
+        ServiceType::NETATMO => {
+            eprintln!("Netatmo weather stations do not accept commands");
+            Err(())
+        },
+        ServiceType::VIRTUAL => virtual_devices::execute(db, service_id, device_id, command)
+    }
+}
+
+/**
+Execute a command on a Device from the device registry
+
+## Parameters
+    data: An instance of AppData
+    device_id: The internal ID of the Device
+    command: The command to execute
+
+## Returns
+    Err: If the Device or its Service is unknown, or the command failed
+    Ok: If the command was executed
+*/
+pub fn execute_registered(data: &AppData, device_id: String, command: DeviceCommand) -> Result<(), ()> {
+    let device = crate::common::device::get_device(data.database.clone(), device_id);
+    if device.is_err() {
+        eprintln!("An error occurred: {:?}", device.err());
+        return Err(());
+    }
+
+    let device = device.unwrap();
+    if device.is_none() {
+        return Err(());
+    }
+
+    let device = device.unwrap();
+    let service = crate::common::service::get_service(data.database.clone(), device.service_id.clone());
+    if service.is_err() {
+        eprintln!("An error occurred: {:?}", service.err());
+        return Err(());
+    }
+
+    let service = service.unwrap();
+    if service.is_none() {
+        return Err(());
+    }
+
+    let (_, service_type) = service.unwrap();
+    execute(data, device.service_id, service_type, device.provider_device_id, command)
+}
+
+/**
+A Device of a User, along with its Service and registry entry
+*/
+#[derive(Clone)]
+pub struct UserDevice {
+    pub service_id:     String,
+    pub service_type:   ServiceType,
+    pub device:         Device,
+    pub registered:     RegisteredDevice
+}
+
+/**
+Get all Devices of all Services owned by a User. Every discovered Device is registered in the device registry
+
+## Parameters
+    data: An instance of AppData
+    user_id: The ID of the User
+
+## Returns
+    Err: If the Services of the User could not be fetched
+    Ok: The Devices. Services which fail to provide their Devices are skipped
+*/
+pub fn get_user_devices(data: &AppData, user_id: String) -> Result<Vec<UserDevice>, ()> {
+    let services = crate::common::service::get_services(data.database.clone(), user_id.clone());
+    if services.is_err() {
+        eprintln!("An error occurred: {:?}", services.err());
+        return Err(());
+    }
+
+    let mut result: Vec<UserDevice> = vec![];
+    for (service_id, service_type) in services.unwrap() {
+        let devices = get_devices(data, service_id.clone(), service_type.clone());
+        data.events.publish_service_health(&user_id, &service_id, matches!(devices, Ok(Some(_))));
+        if devices.is_err() {
+            eprintln!("Unable to get Devices for Service '{}' ({})", service_id, service_type);
+            continue;
+        }
+
+        let devices = devices.unwrap();
+        if devices.is_none() {
+            data.events.publish_service_auth_failed(&user_id, &service_id, &service_type);
+            continue;
+        }
+
+        let registered = crate::common::device::register_devices(data.database.clone(), user_id.clone(), service_id.clone(), devices.unwrap());
+        if registered.is_err() {
+            eprintln!("Unable to register Devices of Service '{}': {:?}", service_id, registered.err());
+            continue;
+        }
+
+        for (device, registered) in registered.unwrap() {
+            data.events.publish_device_state(&user_id, &registered.device_id, &device.state);
+            result.push(UserDevice { service_id: service_id.clone(), service_type: service_type.clone(), device, registered });
+        }
+    }
+
+    Ok(result)
+}
+
+/**
+Get the Devices of a User which are exposed to Google Assistant.
+A Device is exposed if both the Device and its Service are exposed
+
+## Parameters
+    data: An instance of AppData
+    user_id: The ID of the User
+
+## Returns
+    Err: If an error occurred
+    Ok: The exposed Devices
+*/
+pub fn get_exposed_devices(data: &AppData, user_id: String) -> Result<Vec<UserDevice>, ()> {
+    let unexposed_services = crate::common::service::get_unexposed_services(data.database.clone(), user_id.clone());
+    if unexposed_services.is_err() {
+        eprintln!("An error occurred: {:?}", unexposed_services.err());
+        return Err(());
+    }
+
+    let unexposed_services = unexposed_services.unwrap();
+    let devices = get_user_devices(data, user_id)?.into_iter()
+        .filter(|user_device| user_device.registered.exposed && !unexposed_services.contains(&user_device.service_id))
+        .collect();
+
+    Ok(devices)
+}
+
+/**
+Find a Device by its internal ID
+
+## Parameters
+    devices: The Devices as returned by get_user_devices
+    device_id: The internal ID of the Device
+
+## Returns
+    None: If the Device isn't in devices
+    Some: The Device
+*/
+pub fn find_device<'a>(devices: &'a [UserDevice], device_id: &str) -> Option<&'a UserDevice> {
+    devices.iter().find(|user_device| user_device.registered.device_id == device_id)
+}
+
+/**
+Get the outdoor temperature for a User, as measured by one of their weather stations
+
+## Parameters
+    data: An instance of AppData
+    user_id: The ID of the User
+
+## Returns
+    None: If the User has no weather station with a reachable outdoor module, or if an error occurred
+    Some: The outdoor temperature in °C
+*/
+pub fn get_outdoor_temperature(data: &AppData, user_id: String) -> Option<f32> {
+    let services = crate::common::service::get_services(data.database.clone(), user_id);
+    if services.is_err() {
+        eprintln!("An error occurred: {:?}", services.err());
+        return None;
+    }
+
+    for (service_id, service_type) in services.unwrap() {
+        if service_type != ServiceType::NETATMO {
+            continue;
+        }
+
+        let config = get_config(data, &service_type).ok()?;
+        if let Ok(Some(temperature)) = netatmo::get_outdoor_temperature(data.database.clone(), &config, service_id) {
+            return Some(temperature);
+        }
+    }
+
+    None
+}
+
+fn get_config(data: &AppData, service_type: &ServiceType) -> Result<ServicesConfig, ()> {
+    let services_configs = data.get_services_configs();
+    let config = get_service_config(&services_configs, service_type);
+    if config.is_none() {
+        eprintln!("No configuration found for Service type {}", service_type);
+        return Err(());
+    }
+
+    Ok(config.unwrap().clone())
+}
@@ -0,0 +1,485 @@
+use crate::common::service::get_settings;
+use crate::database::Database;
+use crate::types::modbus::{ModbusSettings, ModbusDevice, ModbusRegister, RegisterRole, RegisterType, RegisterDataType};
+use crate::types::device::{Device, DeviceKind, DeviceCapability, DeviceState, DeviceCommand};
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+const DEFAULT_PORT: u16 = 502;
+const DEFAULT_UNIT_ID: u8 = 1;
+const TIMEOUT_SECONDS: u64 = 5;
+
+const FUNCTION_READ_HOLDING_REGISTERS: u8 = 0x03;
+const FUNCTION_READ_INPUT_REGISTERS: u8 = 0x04;
+const FUNCTION_WRITE_SINGLE_REGISTER: u8 = 0x06;
+const FUNCTION_WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+
+/**
+Check that the register map of every device can be read and written
+
+## Parameters
+    settings: The settings of the Service being added
+
+## Returns
+    Err: A message describing what is wrong
+    Ok: If the settings are valid
+*/
+pub fn validate(settings: &ModbusSettings) -> Result<(), String> {
+    for (index, device) in settings.devices.iter().enumerate() {
+        if settings.devices.iter().skip(index + 1).any(|other| other.id == device.id) {
+            return Err(format!("Duplicate device id '{}'", device.id));
+        }
+
+        for register in &device.registers {
+            if register.writable && register.register_type == RegisterType::INPUT {
+                return Err(format!("Register {} of device '{}' is an input register, it can't be written", register.address, device.id));
+            }
+
+            //A value spanning multiple registers must not run past the last address
+            if register.address.checked_add(register.data_type.register_count() - 1).is_none() {
+                return Err(format!("Register {} of device '{}' does not fit a {:?}", register.address, device.id, register.data_type));
+            }
+
+            if let Some(scale) = register.scale {
+                if !scale.is_finite() || scale == 0.0 {
+                    return Err(format!("Register {} of device '{}' has an invalid scale", register.address, device.id));
+                }
+            }
+
+            if register.max_level == Some(0) {
+                return Err(format!("Register {} of device '{}' must have a max_level above 0", register.address, device.id));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/**
+Function to check that a Modbus TCP server is reachable
+
+## Parameters
+    settings: The settings of the Service being added
+
+## Returns
+    Err: If the server could not be reached
+    Ok: If a connection could be established
+*/
+pub fn do_test_connection(settings: &ModbusSettings) -> Result<(), ()> {
+    ModbusClient::connect(&settings.host, settings.port.unwrap_or(DEFAULT_PORT)).map(|_| ())
+}
+
+/**
+Read the registers of all Devices of a Service into Devices
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+
+## Returns
+    Err: If an error occurred fetching the settings
+    Ok:
+        Some: The Devices. Devices of which a register could not be read are reported as offline
+        None: The Service has no valid settings, the User has to add it again
+*/
+pub fn get_devices(db: Database, service_id: String) -> Result<Option<Vec<Device>>, ()> {
+    let settings = load_settings(db, service_id)?;
+    if settings.is_none() {
+        return Ok(None);
+    }
+
+    let settings = settings.unwrap();
+    let mut client = ModbusClient::connect(&settings.host, settings.port.unwrap_or(DEFAULT_PORT)).ok();
+
+    let mut devices: Vec<Device> = vec![];
+    for modbus_device in &settings.devices {
+        let state = match client.as_mut() {
+            Some(client) => read_state(client, modbus_device).unwrap_or_default(),
+            None => DeviceState::default()
+        };
+
+        devices.push(to_device(modbus_device, state));
+    }
+
+    Ok(Some(devices))
+}
+
+/**
+Execute a command on a Device by writing the matching holding register
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+    device_id: The ID of the Device
+    command: The command to execute
+
+## Returns
+    Err: If an error occurred, or if the Device has no writable register for the command
+    Ok: If the register was written
+*/
+pub fn execute(db: Database, service_id: String, device_id: String, command: DeviceCommand) -> Result<(), ()> {
+    let settings = load_settings(db, service_id)?.ok_or(())?;
+
+    let modbus_device = settings.devices.iter().find(|device| device.id == device_id);
+    if modbus_device.is_none() {
+        eprintln!("Unknown Modbus device '{}'", device_id);
+        return Err(());
+    }
+
+    let modbus_device = modbus_device.unwrap();
+    let (role, value) = match command {
+        DeviceCommand::ONOFF(on) => (RegisterRole::ONOFF, if on { 1.0 } else { 0.0 }),
+        DeviceCommand::SETPOINT(temperature) => (RegisterRole::SETPOINT, temperature),
+        DeviceCommand::FANSPEED(speed) => (RegisterRole::FANSPEED, speed as f32),
+        DeviceCommand::BRIGHTNESS(_) => {
+            eprintln!("Command {:?} is not supported by Modbus devices", command);
+            return Err(());
+        }
+    };
+
+    let register = modbus_device.registers.iter().find(|register| register.role == role && register.writable && register.register_type == RegisterType::HOLDING);
+    if register.is_none() {
+        eprintln!("Modbus device '{}' has no writable register for {:?}", device_id, role);
+        return Err(());
+    }
+
+    let register = register.unwrap();
+    let raw = to_raw(register, value);
+
+    let mut client = ModbusClient::connect(&settings.host, settings.port.unwrap_or(DEFAULT_PORT))?;
+    let unit_id = modbus_device.unit_id.unwrap_or(DEFAULT_UNIT_ID);
+    if raw.len() == 1 {
+        client.write_single_register(unit_id, register.address, raw[0])
+    } else {
+        client.write_multiple_registers(unit_id, register.address, &raw)
+    }
+}
+
+/**
+Load the settings of a Service
+
+## Returns
+    Err: If an error occurred
+    Ok:
+        Some: The settings
+        None: The Service has no settings, or they are not valid
+*/
+fn load_settings(db: Database, service_id: String) -> Result<Option<ModbusSettings>, ()> {
+    let settings = get_settings::<ModbusSettings>(db, service_id.clone());
+    if settings.is_err() {
+        eprintln!("An error occurred: {:?}", settings.err());
+        return Err(());
+    }
+
+    let settings_unwrapped = settings.unwrap();
+    if settings_unwrapped.is_none() {
+        eprintln!("No Modbus settings found for Service '{}'", service_id);
+        return Ok(None);
+    }
+
+    let settings_unwrapped = settings_unwrapped.unwrap();
+    if let Err(message) = validate(&settings_unwrapped) {
+        eprintln!("Stored Modbus settings for Service '{}' are invalid: {}", service_id, message);
+        return Ok(None);
+    }
+
+    Ok(Some(settings_unwrapped))
+}
+
+fn to_device(modbus_device: &ModbusDevice, state: DeviceState) -> Device {
+    let mut capabilities: Vec<DeviceCapability> = vec![];
+    let has_role = |role: RegisterRole| modbus_device.registers.iter().any(|register| register.role == role);
+    let has_writable_role = |role: RegisterRole| modbus_device.registers.iter().any(|register| register.role == role && register.writable);
+
+    if has_writable_role(RegisterRole::ONOFF) {
+        capabilities.push(DeviceCapability::ONOFF);
+    }
+
+    if has_writable_role(RegisterRole::SETPOINT) {
+        capabilities.push(DeviceCapability::TEMPERATURESETTING);
+    } else if has_role(RegisterRole::AMBIENTTEMPERATURE) {
+        capabilities.push(DeviceCapability::TEMPERATUREREADING);
+    }
+
+    if has_writable_role(RegisterRole::FANSPEED) {
+        capabilities.push(DeviceCapability::FANSPEED);
+    }
+
+    if has_role(RegisterRole::HUMIDITY) || has_role(RegisterRole::CO2) || has_role(RegisterRole::POWER) {
+        capabilities.push(DeviceCapability::SENSORSTATE);
+    }
+
+    let (min_setpoint, max_setpoint) = if modbus_device.kind == DeviceKind::THERMOSTAT { (Some(5.0), Some(30.0)) } else { (None, None) };
+
+    Device {
+        id: modbus_device.id.clone(),
+        name: modbus_device.name.clone(),
+        kind: modbus_device.kind.clone(),
+        capabilities,
+        manufacturer: "Modbus".to_string(),
+        model: modbus_device.kind.to_string(),
+        min_setpoint,
+        max_setpoint,
+        state
+    }
+}
+
+/**
+Read all registers of a Device into a DeviceState
+*/
+fn read_state(client: &mut ModbusClient, modbus_device: &ModbusDevice) -> Result<DeviceState, ()> {
+    let unit_id = modbus_device.unit_id.unwrap_or(DEFAULT_UNIT_ID);
+    let mut state = DeviceState { online: true, ..DeviceState::default() };
+
+    for register in &modbus_device.registers {
+        let function = match register.register_type {
+            RegisterType::INPUT => FUNCTION_READ_INPUT_REGISTERS,
+            RegisterType::HOLDING => FUNCTION_READ_HOLDING_REGISTERS
+        };
+
+        let raw = client.read_registers(unit_id, function, register.address, register.data_type.register_count())?;
+        let value = from_raw(register, &raw);
+
+        match register.role {
+            RegisterRole::ONOFF => state.on = Some(value != 0.0),
+            RegisterRole::AMBIENTTEMPERATURE => state.ambient_temperature = Some(value),
+            RegisterRole::SETPOINT => state.setpoint_temperature = Some(value),
+            RegisterRole::HUMIDITY => state.humidity = Some(value),
+            RegisterRole::CO2 => state.co2 = Some(value),
+            RegisterRole::FANSPEED => state.fan_speed = Some(value.round().clamp(0.0, 100.0) as u8),
+            RegisterRole::POWER => state.power = Some(value)
+        }
+    }
+
+    Ok(state)
+}
+
+/**
+Convert raw register values into a value in the unit of the register's role
+*/
+fn from_raw(register: &ModbusRegister, raw: &[u16]) -> f32 {
+    let value = match register.data_type {
+        RegisterDataType::U16 => raw[0] as f32,
+        RegisterDataType::I16 => raw[0] as i16 as f32,
+        RegisterDataType::U32 => (((raw[0] as u32) << 16) | raw[1] as u32) as f32,
+        RegisterDataType::I32 => (((raw[0] as u32) << 16) | raw[1] as u32) as i32 as f32,
+        RegisterDataType::F32 => f32::from_bits(((raw[0] as u32) << 16) | raw[1] as u32)
+    };
+
+    let scaled = value * register.scale.unwrap_or(1.0);
+    match (&register.role, register.max_level) {
+        (RegisterRole::FANSPEED, Some(max_level)) => scaled / max_level as f32 * 100.0,
+        _ => scaled
+    }
+}
+
+/**
+Convert a value in the unit of the register's role into raw register values
+*/
+fn to_raw(register: &ModbusRegister, value: f32) -> Vec<u16> {
+    let value = match (&register.role, register.max_level) {
+        (RegisterRole::FANSPEED, Some(max_level)) => (value / 100.0 * max_level as f32).round(),
+        _ => value
+    };
+
+    let unscaled = value / register.scale.unwrap_or(1.0);
+    match register.data_type {
+        RegisterDataType::U16 => vec![unscaled.round() as u16],
+        RegisterDataType::I16 => vec![unscaled.round() as i16 as u16],
+        RegisterDataType::U32 => {
+            let raw = unscaled.round() as u32;
+            vec![(raw >> 16) as u16, raw as u16]
+        },
+        RegisterDataType::I32 => {
+            let raw = unscaled.round() as i32 as u32;
+            vec![(raw >> 16) as u16, raw as u16]
+        },
+        RegisterDataType::F32 => {
+            let raw = unscaled.to_bits();
+            vec![(raw >> 16) as u16, raw as u16]
+        }
+    }
+}
+
+/**
+Minimal Modbus TCP client
+*/
+struct ModbusClient {
+    stream:         TcpStream,
+    transaction_id: u16
+}
+
+impl ModbusClient {
+    fn connect(host: &str, port: u16) -> Result<ModbusClient, ()> {
+        let address = (host, port).to_socket_addrs().ok().and_then(|mut addresses| addresses.next());
+        if address.is_none() {
+            eprintln!("Unable to resolve Modbus host '{}'", host);
+            return Err(());
+        }
+
+        let stream = TcpStream::connect_timeout(&address.unwrap(), Duration::from_secs(TIMEOUT_SECONDS));
+        if stream.is_err() {
+            eprintln!("Unable to connect to Modbus host '{}:{}': {:?}", host, port, stream.err());
+            return Err(());
+        }
+
+        let stream = stream.unwrap();
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(TIMEOUT_SECONDS)));
+        let _ = stream.set_write_timeout(Some(Duration::from_secs(TIMEOUT_SECONDS)));
+
+        Ok(ModbusClient { stream, transaction_id: 0 })
+    }
+
+    fn read_registers(&mut self, unit_id: u8, function: u8, address: u16, count: u16) -> Result<Vec<u16>, ()> {
+        let mut pdu = vec![function];
+        pdu.extend_from_slice(&address.to_be_bytes());
+        pdu.extend_from_slice(&count.to_be_bytes());
+
+        let response = self.request(unit_id, &pdu)?;
+
+        //Response: function, byte count, register values
+        if response.len() < 2 || response[1] as usize != count as usize * 2 || response.len() < 2 + count as usize * 2 {
+            eprintln!("Invalid Modbus response length");
+            return Err(());
+        }
+
+        let registers = response[2..2 + count as usize * 2]
+            .chunks(2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+            .collect();
+
+        Ok(registers)
+    }
+
+    fn write_single_register(&mut self, unit_id: u8, address: u16, value: u16) -> Result<(), ()> {
+        let mut pdu = vec![FUNCTION_WRITE_SINGLE_REGISTER];
+        pdu.extend_from_slice(&address.to_be_bytes());
+        pdu.extend_from_slice(&value.to_be_bytes());
+
+        self.request(unit_id, &pdu).map(|_| ())
+    }
+
+    fn write_multiple_registers(&mut self, unit_id: u8, address: u16, values: &[u16]) -> Result<(), ()> {
+        let mut pdu = vec![FUNCTION_WRITE_MULTIPLE_REGISTERS];
+        pdu.extend_from_slice(&address.to_be_bytes());
+        pdu.extend_from_slice(&(values.len() as u16).to_be_bytes());
+        pdu.push((values.len() * 2) as u8);
+        for value in values {
+            pdu.extend_from_slice(&value.to_be_bytes());
+        }
+
+        self.request(unit_id, &pdu).map(|_| ())
+    }
+
+    /**
+    Send a PDU and return the response PDU
+    */
+    fn request(&mut self, unit_id: u8, pdu: &[u8]) -> Result<Vec<u8>, ()> {
+        self.transaction_id = self.transaction_id.wrapping_add(1);
+
+        //MBAP header: transaction ID, protocol ID (always 0), length of the remaining bytes, unit ID
+        let mut frame: Vec<u8> = vec![];
+        frame.extend_from_slice(&self.transaction_id.to_be_bytes());
+        frame.extend_from_slice(&0u16.to_be_bytes());
+        frame.extend_from_slice(&(pdu.len() as u16 + 1).to_be_bytes());
+        frame.push(unit_id);
+        frame.extend_from_slice(pdu);
+
+        if let Err(e) = self.stream.write_all(&frame) {
+            eprintln!("Unable to write to Modbus host: {:?}", e);
+            return Err(());
+        }
+
+        let mut header = [0u8; 7];
+        if let Err(e) = self.stream.read_exact(&mut header) {
+            eprintln!("Unable to read from Modbus host: {:?}", e);
+            return Err(());
+        }
+
+        let transaction_id = u16::from_be_bytes([header[0], header[1]]);
+        let length = u16::from_be_bytes([header[4], header[5]]) as usize;
+        if transaction_id != self.transaction_id || length < 2 {
+            eprintln!("Unexpected Modbus response header");
+            return Err(());
+        }
+
+        let mut response = vec![0u8; length - 1];
+        if let Err(e) = self.stream.read_exact(&mut response) {
+            eprintln!("Unable to read from Modbus host: {:?}", e);
+            return Err(());
+        }
+
+        //The high bit of the function code is set for exceptions
+        if response[0] & 0x80 != 0 {
+            eprintln!("Modbus exception {} for function {}", response.get(1).unwrap_or(&0), pdu[0]);
+            return Err(());
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn register(role: RegisterRole, data_type: RegisterDataType, scale: Option<f32>, max_level: Option<u16>) -> ModbusRegister {
+        ModbusRegister { role, address: 0, register_type: RegisterType::HOLDING, data_type, scale, writable: true, max_level }
+    }
+
+    #[test]
+    fn raw_values_are_decoded_per_data_type() {
+        assert_eq!(from_raw(&register(RegisterRole::POWER, RegisterDataType::U16, None, None), &[65535]), 65535.0);
+        assert_eq!(from_raw(&register(RegisterRole::POWER, RegisterDataType::I16, None, None), &[65535]), -1.0);
+        assert_eq!(from_raw(&register(RegisterRole::POWER, RegisterDataType::U32, None, None), &[1, 2]), 65538.0);
+        assert_eq!(from_raw(&register(RegisterRole::POWER, RegisterDataType::I32, None, None), &[65535, 65534]), -2.0);
+        assert_eq!(from_raw(&register(RegisterRole::POWER, RegisterDataType::F32, None, None), &[0x41A4, 0x0000]), 20.5);
+    }
+
+    #[test]
+    fn scale_and_max_level_are_applied() {
+        assert_eq!(from_raw(&register(RegisterRole::AMBIENTTEMPERATURE, RegisterDataType::I16, Some(0.1), None), &[215]), 21.5);
+        assert_eq!(from_raw(&register(RegisterRole::FANSPEED, RegisterDataType::U16, None, Some(4)), &[2]), 50.0);
+
+        assert_eq!(to_raw(&register(RegisterRole::SETPOINT, RegisterDataType::I16, Some(0.1), None), 21.5), vec![215]);
+        assert_eq!(to_raw(&register(RegisterRole::FANSPEED, RegisterDataType::U16, None, Some(3)), 70.0), vec![2]);
+    }
+
+    #[test]
+    fn values_survive_a_round_trip() {
+        for data_type in [RegisterDataType::U16, RegisterDataType::I16, RegisterDataType::U32, RegisterDataType::I32, RegisterDataType::F32] {
+            let register = register(RegisterRole::SETPOINT, data_type, Some(0.5), None);
+            assert_eq!(from_raw(&register, &to_raw(&register, 19.5)), 19.5);
+        }
+
+        let register = register(RegisterRole::SETPOINT, RegisterDataType::I32, None, None);
+        assert_eq!(to_raw(&register, -3.0), vec![65535, 65533]);
+        assert_eq!(from_raw(&register, &to_raw(&register, -70000.0)), -70000.0);
+    }
+
+    #[test]
+    fn invalid_register_maps_are_rejected() {
+        let settings = |register: ModbusRegister| ModbusSettings {
+            host: "localhost".to_string(),
+            port: None,
+            devices: vec![ModbusDevice { id: "1".to_string(), name: "Unit".to_string(), kind: DeviceKind::FAN, unit_id: None, registers: vec![register] }]
+        };
+
+        assert!(validate(&settings(register(RegisterRole::SETPOINT, RegisterDataType::I16, Some(0.1), None))).is_ok());
+        assert!(validate(&settings(register(RegisterRole::SETPOINT, RegisterDataType::I16, Some(0.0), None))).is_err());
+        assert!(validate(&settings(register(RegisterRole::SETPOINT, RegisterDataType::I16, Some(f32::NAN), None))).is_err());
+        assert!(validate(&settings(register(RegisterRole::FANSPEED, RegisterDataType::U16, None, Some(0)))).is_err());
+
+        let mut last = register(RegisterRole::POWER, RegisterDataType::F32, None, None);
+        last.address = u16::MAX;
+        assert!(validate(&settings(last.clone())).is_err());
+        last.data_type = RegisterDataType::U16;
+        assert!(validate(&settings(last)).is_ok());
+
+        let mut input = register(RegisterRole::SETPOINT, RegisterDataType::I16, None, None);
+        input.register_type = RegisterType::INPUT;
+        assert!(validate(&settings(input)).is_err());
+    }
+}
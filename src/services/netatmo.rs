@@ -0,0 +1,142 @@
+use crate::config::ServicesConfig;
+use crate::database::Database;
+use crate::types::netatmo::{StationsDataResponse, Station, DashboardData};
+use crate::types::device::{Device, DeviceKind, DeviceCapability, DeviceState};
+
+const NETATMO_API_ENDPOINT: &str = "https://api.netatmo.com";
+
+/// Module type of the outdoor module
+const OUTDOOR_MODULE: &str = "NAModule1";
+
+/// Module types which measure temperature, humidity, CO2 or pressure. Wind and rain gauges are not imported
+const SENSOR_MODULES: [&str; 3] = ["NAMain", OUTDOOR_MODULE, "NAModule4"];
+
+/**
+Get all indoor and outdoor modules of all weather stations of a Service, as sensor Devices
+
+## Parameters
+    db: An instance of Database
+    config: The configuration of the NETATMO Service
+    service_id: The ID of the Service
+
+## Returns
+    Err: If an error occurred
+    Ok: The Devices of the Service
+*/
+pub fn get_devices(db: Database, config: &ServicesConfig, service_id: String) -> Result<Vec<Device>, ()> {
+    let stations = get_stations(db, config, service_id)?;
+
+    let mut devices: Vec<Device> = vec![];
+    for station in &stations {
+        let station_name = station.station_name.clone().unwrap_or_else(|| station.id.clone());
+
+        devices.push(to_device(
+            station.id.clone(),
+            station.module_name.clone().unwrap_or_else(|| station_name.clone()),
+            &station.module_type,
+            station.reachable.unwrap_or(true),
+            station.dashboard_data.as_ref()
+        ));
+
+        for module in &station.modules {
+            if !SENSOR_MODULES.contains(&module.module_type.as_str()) {
+                continue;
+            }
+
+            devices.push(to_device(
+                module.id.clone(),
+                module.module_name.clone().unwrap_or_else(|| format!("{} {}", station_name, module.id)),
+                &module.module_type,
+                module.reachable.unwrap_or(true),
+                module.dashboard_data.as_ref()
+            ));
+        }
+    }
+
+    Ok(devices)
+}
+
+/**
+Get the temperature measured by the first reachable outdoor module of a Service
+
+## Parameters
+    db: An instance of Database
+    config: The configuration of the NETATMO Service
+    service_id: The ID of the Service
+
+## Returns
+    Err: If an error occurred
+    Ok:
+        Some: The outdoor temperature in °C
+        None: The Service has no reachable outdoor module
+*/
+pub fn get_outdoor_temperature(db: Database, config: &ServicesConfig, service_id: String) -> Result<Option<f32>, ()> {
+    let stations = get_stations(db, config, service_id)?;
+
+    let temperature = stations.iter()
+        .flat_map(|station| station.modules.iter())
+        .filter(|module| module.module_type == OUTDOOR_MODULE && module.reachable.unwrap_or(true))
+        .find_map(|module| module.dashboard_data.as_ref().and_then(|data| data.temperature));
+
+    Ok(temperature)
+}
+
+fn get_stations(db: Database, config: &ServicesConfig, service_id: String) -> Result<Vec<Station>, ()> {
+    if config.oauth.is_none() {
+        eprintln!("No OAuth configuration for NETATMO");
+        return Err(());
+    }
+
+    let access_token = crate::common::oauth::get_access_token(db, config.oauth.as_ref().unwrap(), service_id)?;
+    let api_endpoint = config.api_endpoint.clone().unwrap_or_else(|| NETATMO_API_ENDPOINT.to_string());
+
+    let request = reqwest::blocking::Client::new().get(format!("{}/api/getstationsdata", api_endpoint))
+        .bearer_auth(access_token)
+        .send();
+
+    if request.is_err() {
+        eprintln!("An error occurred: {:?}", request.err());
+        return Err(());
+    }
+
+    let response = request.unwrap();
+    if !response.status().is_success() {
+        eprintln!("Netatmo returned status {}", response.status());
+        return Err(());
+    }
+
+    let stations = response.json::<StationsDataResponse>();
+    if stations.is_err() {
+        eprintln!("Unable to deserialize Netatmo stations: {:?}", stations.err());
+        return Err(());
+    }
+
+    Ok(stations.unwrap().body.devices)
+}
+
+fn to_device(id: String, name: String, module_type: &str, reachable: bool, dashboard_data: Option<&DashboardData>) -> Device {
+    let mut state = DeviceState { online: reachable && dashboard_data.is_some(), ..DeviceState::default() };
+    if let Some(data) = dashboard_data {
+        state.ambient_temperature = data.temperature;
+        state.humidity = data.humidity;
+        state.co2 = data.co2;
+        state.pressure = data.pressure;
+    }
+
+    let mut capabilities = vec![DeviceCapability::TEMPERATUREREADING];
+    if state.humidity.is_some() || state.co2.is_some() || state.pressure.is_some() {
+        capabilities.push(DeviceCapability::SENSORSTATE);
+    }
+
+    Device {
+        id,
+        name,
+        kind: DeviceKind::SENSOR,
+        capabilities,
+        manufacturer: "Netatmo".to_string(),
+        model: module_type.to_string(),
+        min_setpoint: None,
+        max_setpoint: None,
+        state
+    }
+}
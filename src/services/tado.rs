@@ -0,0 +1,182 @@
+use crate::config::ServicesConfig;
+use crate::database::Database;
+use crate::types::tado::{Me, Zone, ZoneState, ZoneSetting, Temperature, Overlay, Termination};
+use crate::types::device::{Device, DeviceKind, DeviceCapability, DeviceState, DeviceCommand};
+
+use serde::de::DeserializeOwned;
+
+const TADO_API_ENDPOINT: &str = "https://my.tado.com/api/v2";
+
+/// Setpoint used when a heating zone is turned on without a known setpoint
+const DEFAULT_SETPOINT: f32 = 20.0;
+
+/**
+Get all heating and hot water Zones of all Homes of a Service, as Devices.
+Device ID's are formatted as '{home_id}/{zone_id}'
+
+## Parameters
+    db: An instance of Database
+    config: The configuration of the TADO Service
+    service_id: The ID of the Service
+
+## Returns
+    Err: If an error occurred
+    Ok: The Devices of the Service
+*/
+pub fn get_devices(db: Database, config: &ServicesConfig, service_id: String) -> Result<Vec<Device>, ()> {
+    let client = TadoClient::new(db, config, service_id)?;
+    let me: Me = client.get("/me")?;
+
+    let mut devices: Vec<Device> = vec![];
+    for home in me.homes {
+        let zones: Vec<Zone> = client.get(&format!("/homes/{}/zones", home.id))?;
+        for zone in zones {
+            let (kind, capabilities) = match zone.zone_type.as_str() {
+                "HEATING" => (DeviceKind::THERMOSTAT, vec![DeviceCapability::TEMPERATURESETTING, DeviceCapability::ONOFF]),
+                "HOT_WATER" => (DeviceKind::WATERHEATER, vec![DeviceCapability::ONOFF]),
+                _ => continue
+            };
+
+            let zone_state: ZoneState = client.get(&format!("/homes/{}/zones/{}/state", home.id, zone.id))?;
+            let sensor_data_points = zone_state.sensor_data_points;
+
+            let state = DeviceState {
+                online: zone_state.link.map(|link| link.state == "ONLINE").unwrap_or(true),
+                on: Some(zone_state.setting.power == "ON"),
+                setpoint_temperature: zone_state.setting.temperature.map(|temperature| temperature.celsius),
+                ambient_temperature: sensor_data_points.as_ref().and_then(|points| points.inside_temperature.as_ref()).map(|temperature| temperature.celsius),
+                humidity: sensor_data_points.as_ref().and_then(|points| points.humidity.as_ref()).map(|humidity| humidity.percentage),
+                ..DeviceState::default()
+            };
+
+            let (min_setpoint, max_setpoint) = if kind == DeviceKind::THERMOSTAT { (Some(5.0), Some(25.0)) } else { (None, None) };
+
+            devices.push(Device {
+                id: format!("{}/{}", home.id, zone.id),
+                name: zone.name,
+                kind,
+                capabilities,
+                manufacturer: "tado°".to_string(),
+                model: zone.zone_type,
+                min_setpoint,
+                max_setpoint,
+                state
+            });
+        }
+    }
+
+    Ok(devices)
+}
+
+/**
+Execute a command on a Zone by setting a manual overlay
+
+## Parameters
+    db: An instance of Database
+    config: The configuration of the TADO Service
+    service_id: The ID of the Service
+    device_id: The ID of the Device, formatted as '{home_id}/{zone_id}'
+    command: The command to execute
+
+## Returns
+    Err: If an error occurred, or if the command isn't supported by the Zone
+    Ok: If the command was executed
+*/
+pub fn execute(db: Database, config: &ServicesConfig, service_id: String, device_id: String, command: DeviceCommand) -> Result<(), ()> {
+    let client = TadoClient::new(db, config, service_id)?;
+
+    let parts: Vec<&str> = device_id.split('/').collect();
+    if parts.len() != 2 {
+        eprintln!("Invalid tado° device ID '{}'", device_id);
+        return Err(());
+    }
+
+    let zone_path = format!("/homes/{}/zones/{}", parts[0], parts[1]);
+    let zone_state: ZoneState = client.get(&format!("{}/state", zone_path))?;
+    let zone_type = zone_state.setting.zone_type.clone();
+
+    let setting = match (zone_type.as_str(), &command) {
+        ("HEATING", DeviceCommand::SETPOINT(temperature)) => ZoneSetting { zone_type, power: "ON".to_string(), temperature: Some(Temperature { celsius: *temperature }) },
+        ("HEATING", DeviceCommand::ONOFF(true)) => {
+            let celsius = zone_state.setting.temperature.map(|temperature| temperature.celsius).unwrap_or(DEFAULT_SETPOINT);
+            ZoneSetting { zone_type, power: "ON".to_string(), temperature: Some(Temperature { celsius }) }
+        },
+        ("HEATING", DeviceCommand::ONOFF(false)) | ("HOT_WATER", DeviceCommand::ONOFF(false)) => ZoneSetting { zone_type, power: "OFF".to_string(), temperature: None },
+        ("HOT_WATER", DeviceCommand::ONOFF(true)) => ZoneSetting { zone_type, power: "ON".to_string(), temperature: None },
+        _ => {
+            eprintln!("Command {:?} is not supported for tado° zone '{}'", command, device_id);
+            return Err(());
+        }
+    };
+
+    let overlay = Overlay {
+        setting,
+        termination: Termination { termination_type: "MANUAL".to_string() }
+    };
+
+    client.put(&format!("{}/overlay", zone_path), &overlay)
+}
+
+struct TadoClient {
+    api_endpoint:   String,
+    access_token:   String
+}
+
+impl TadoClient {
+    fn new(db: Database, config: &ServicesConfig, service_id: String) -> Result<TadoClient, ()> {
+        if config.oauth.is_none() {
+            eprintln!("No OAuth configuration for TADO");
+            return Err(());
+        }
+
+        let access_token = crate::common::oauth::get_access_token(db, config.oauth.as_ref().unwrap(), service_id)?;
+        let api_endpoint = config.api_endpoint.clone().unwrap_or_else(|| TADO_API_ENDPOINT.to_string());
+
+        Ok(TadoClient { api_endpoint, access_token })
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ()> {
+        let request = reqwest::blocking::Client::new().get(format!("{}{}", self.api_endpoint, path))
+            .bearer_auth(&self.access_token)
+            .send();
+
+        if request.is_err() {
+            eprintln!("An error occurred: {:?}", request.err());
+            return Err(());
+        }
+
+        let response = request.unwrap();
+        if !response.status().is_success() {
+            eprintln!("tado° returned status {} for {}", response.status(), path);
+            return Err(());
+        }
+
+        let deserialized = response.json::<T>();
+        if deserialized.is_err() {
+            eprintln!("Unable to deserialize tado° response for {}: {:?}", path, deserialized.err());
+            return Err(());
+        }
+
+        Ok(deserialized.unwrap())
+    }
+
+    fn put<T: serde::Serialize>(&self, path: &str, payload: &T) -> Result<(), ()> {
+        let request = reqwest::blocking::Client::new().put(format!("{}{}", self.api_endpoint, path))
+            .bearer_auth(&self.access_token)
+            .json(payload)
+            .send();
+
+        if request.is_err() {
+            eprintln!("An error occurred: {:?}", request.err());
+            return Err(());
+        }
+
+        let status = request.unwrap().status();
+        if !status.is_success() {
+            eprintln!("tado° returned status {} for {}", status, path);
+            return Err(());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+use crate::common::service::{get_settings, set_settings};
+use crate::database::Database;
+use crate::types::virtual_devices::{VirtualSettings, VirtualDevice};
+use crate::types::device::{Device, DeviceKind, DeviceCapability, DeviceState, DeviceCommand};
+
+use std::f32::consts::PI;
+
+/// Temperature a room drifts towards when it isn't heated, in °C
+const UNHEATED_TEMPERATURE: f32 = 15.0;
+
+/// Time constant of the thermal model in seconds, after this time a room has closed ~63% of the gap to its target
+const THERMAL_TIME_CONSTANT: f32 = 1800.0;
+
+const MIN_SETPOINT: f32 = 5.0;
+const MAX_SETPOINT: f32 = 30.0;
+
+/**
+Create the initial state of a simulated Device
+
+## Parameters
+    kind: The kind of the Device
+
+## Returns
+    The state the Device starts in
+*/
+pub fn initial_state(kind: &DeviceKind) -> DeviceState {
+    let mut state = DeviceState { online: true, ..DeviceState::default() };
+    match kind {
+        DeviceKind::THERMOSTAT => {
+            state.on = Some(true);
+            state.setpoint_temperature = Some(20.0);
+            state.ambient_temperature = Some(18.0);
+        },
+        DeviceKind::LIGHT => {
+            state.on = Some(false);
+            state.brightness = Some(100);
+        },
+        DeviceKind::SWITCH | DeviceKind::WATERHEATER => {
+            state.on = Some(false);
+        },
+        DeviceKind::FAN => {
+            state.on = Some(false);
+            state.fan_speed = Some(50);
+        },
+        DeviceKind::SENSOR => {
+            state = simulate_sensor(state, chrono::Utc::now().timestamp());
+        }
+    }
+
+    state
+}
+
+/**
+Get all simulated Devices of a Service. The simulation is advanced to the current time first
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+
+## Returns
+    Err: If an error occurred
+    Ok: The Devices of the Service
+*/
+pub fn get_devices(db: Database, service_id: String) -> Result<Vec<Device>, ()> {
+    let mut settings = load_settings(db.clone(), service_id.clone())?;
+    let now = chrono::Utc::now().timestamp();
+
+    for virtual_device in settings.devices.iter_mut() {
+        simulate(virtual_device, now);
+    }
+
+    store_settings(db, service_id, &settings)?;
+    Ok(settings.devices.iter().map(to_device).collect())
+}
+
+/**
+Execute a command on a simulated Device
+
+## Parameters
+    db: An instance of Database
+    service_id: The ID of the Service
+    device_id: The ID of the Device
+    command: The command to execute
+
+## Returns
+    Err: If an error occurred, or if the command isn't supported by the Device
+    Ok: If the command was executed
+*/
+pub fn execute(db: Database, service_id: String, device_id: String, command: DeviceCommand) -> Result<(), ()> {
+    let mut settings = load_settings(db.clone(), service_id.clone())?;
+
+    let virtual_device = settings.devices.iter_mut().find(|device| device.id == device_id);
+    if virtual_device.is_none() {
+        eprintln!("Unknown virtual device '{}'", device_id);
+        return Err(());
+    }
+
+    let virtual_device = virtual_device.unwrap();
+    if !command.is_supported(&capabilities(&virtual_device.kind)) {
+        eprintln!("Command {:?} is not supported by virtual device '{}'", command, device_id);
+        return Err(());
+    }
+
+    //Bring the simulation up to date, so the new setpoint only affects the time from now on
+    simulate(virtual_device, chrono::Utc::now().timestamp());
+
+    let command = match command {
+        DeviceCommand::SETPOINT(temperature) => DeviceCommand::SETPOINT(temperature.clamp(MIN_SETPOINT, MAX_SETPOINT)),
+        _ => command
+    };
+
+    virtual_device.state.apply(&command);
+    store_settings(db, service_id, &settings)
+}
+
+fn capabilities(kind: &DeviceKind) -> Vec<DeviceCapability> {
+    match kind {
+        DeviceKind::THERMOSTAT => vec![DeviceCapability::TEMPERATURESETTING, DeviceCapability::ONOFF],
+        DeviceKind::LIGHT => vec![DeviceCapability::ONOFF, DeviceCapability::BRIGHTNESS],
+        DeviceKind::SWITCH | DeviceKind::WATERHEATER => vec![DeviceCapability::ONOFF],
+        DeviceKind::FAN => vec![DeviceCapability::ONOFF, DeviceCapability::FANSPEED],
+        DeviceKind::SENSOR => vec![DeviceCapability::TEMPERATUREREADING, DeviceCapability::SENSORSTATE]
+    }
+}
+
+fn to_device(virtual_device: &VirtualDevice) -> Device {
+    let (min_setpoint, max_setpoint) = if virtual_device.kind == DeviceKind::THERMOSTAT { (Some(MIN_SETPOINT), Some(MAX_SETPOINT)) } else { (None, None) };
+
+    Device {
+        id: virtual_device.id.clone(),
+        name: virtual_device.name.clone(),
+        kind: virtual_device.kind.clone(),
+        capabilities: capabilities(&virtual_device.kind),
+        manufacturer: "Virtual".to_string(),
+        model: format!("Simulated {}", virtual_device.kind.to_string().to_lowercase()),
+        min_setpoint,
+        max_setpoint,
+        state: virtual_device.state.clone()
+    }
+}
+
+/**
+Advance the simulated state of a Device to a point in time
+*/
+fn simulate(virtual_device: &mut VirtualDevice, now: i64) {
+    let elapsed = (now - virtual_device.updated_at).max(0) as f32;
+    virtual_device.updated_at = now;
+
+    match virtual_device.kind {
+        DeviceKind::THERMOSTAT => {
+            let state = &mut virtual_device.state;
+            let ambient = state.ambient_temperature.unwrap_or(UNHEATED_TEMPERATURE);
+
+            //A heated room drifts towards the setpoint, an unheated room cools down towards UNHEATED_TEMPERATURE
+            let target = match (state.on, state.setpoint_temperature) {
+                (Some(true), Some(setpoint)) => setpoint,
+                _ => UNHEATED_TEMPERATURE
+            };
+
+            let drifted = ambient + (target - ambient) * (1.0 - (-elapsed / THERMAL_TIME_CONSTANT).exp());
+            state.ambient_temperature = Some((drifted * 10.0).round() / 10.0);
+        },
+        DeviceKind::SENSOR => {
+            virtual_device.state = simulate_sensor(virtual_device.state.clone(), now);
+        },
+        _ => {}
+    }
+}
+
+/**
+Sensor values follow a daily cycle, warmest and driest in the afternoon
+*/
+fn simulate_sensor(mut state: DeviceState, now: i64) -> DeviceState {
+    let hour = (now % 86400) as f32 / 3600.0;
+    let cycle = (2.0 * PI * (hour - 9.0) / 24.0).sin();
+
+    state.online = true;
+    state.ambient_temperature = Some(((19.0 + 2.0 * cycle) * 10.0).round() / 10.0);
+    state.humidity = Some((50.0 - 10.0 * cycle).round());
+    state.co2 = Some((600.0 - 150.0 * cycle).round());
+    state
+}
+
+fn load_settings(db: Database, service_id: String) -> Result<VirtualSettings, ()> {
+    let settings = get_settings::<VirtualSettings>(db, service_id.clone());
+    if settings.is_err() {
+        eprintln!("An error occurred: {:?}", settings.err());
+        return Err(());
+    }
+
+    let settings_unwrapped = settings.unwrap();
+    if settings_unwrapped.is_none() {
+        eprintln!("No virtual devices found for Service '{}'", service_id);
+        return Err(());
+    }
+
+    Ok(settings_unwrapped.unwrap())
+}
+
+fn store_settings(db: Database, service_id: String, settings: &VirtualSettings) -> Result<(), ()> {
+    let set_result = set_settings(db, service_id, settings);
+    if set_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_result.err());
+        return Err(());
+    }
+
+    Ok(())
+}
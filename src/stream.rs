@@ -0,0 +1,130 @@
+use crate::types::stream::{StreamEvent, StreamMessage};
+
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// How many recent events are kept for clients resuming a stream
+const BUFFER_SIZE: usize = 1000;
+
+/**
+Distributes events to the connected clients of the User they belong to. Fed from the EventBus by threads::event_stream.
+Recent events are buffered, so a client which reconnects receives what it missed
+*/
+#[derive(Clone, Default)]
+pub struct StreamHub {
+    inner:          Arc<Mutex<HubState>>
+}
+
+#[derive(Default)]
+struct HubState {
+    next_id:        u64,
+    /// Recent events, with the User they belong to
+    buffer:         VecDeque<(String, StreamMessage)>,
+    /// Connected clients, with the User they belong to
+    clients:        Vec<(String, UnboundedSender<StreamMessage>)>
+}
+
+impl StreamHub {
+
+    /**
+    Publish an event to the clients of a User
+
+    ## Parameters
+        user_id: The User the event belongs to
+        event: The event
+    */
+    pub fn publish(&self, user_id: &str, event: StreamEvent) {
+        let mut state = self.inner.lock().unwrap();
+
+        state.next_id += 1;
+        let message = StreamMessage { id: state.next_id, event };
+
+        if state.buffer.len() >= BUFFER_SIZE {
+            state.buffer.pop_front();
+        }
+
+        state.buffer.push_back((user_id.to_string(), message.clone()));
+
+        //Clients which disconnected are dropped
+        state.clients.retain(|(client_user_id, sender)| client_user_id != user_id || sender.unbounded_send(message.clone()).is_ok());
+    }
+
+    /**
+    Connect a client
+
+    ## Parameters
+        user_id: The User the client belongs to
+        last_event_id: The last event the client received, to resume a stream
+
+    ## Returns
+        The buffered events after last_event_id, and a receiver for new events
+    */
+    pub fn subscribe(&self, user_id: &str, last_event_id: Option<u64>) -> (Vec<StreamMessage>, UnboundedReceiver<StreamMessage>) {
+        let mut state = self.inner.lock().unwrap();
+
+        //An id from before a restart can't be resumed from
+        let missed = match last_event_id {
+            Some(last_event_id) if last_event_id <= state.next_id => state.buffer.iter()
+                .filter(|(event_user_id, message)| event_user_id == user_id && message.id > last_event_id)
+                .map(|(_, message)| message.clone())
+                .collect(),
+            _ => vec![]
+        };
+
+        let (sender, receiver) = unbounded();
+        state.clients.push((user_id.to_string(), sender));
+
+        (missed, receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn away(away: bool) -> StreamEvent {
+        StreamEvent::AWAYMODE { away }
+    }
+
+    fn ids(messages: &[StreamMessage]) -> Vec<u64> {
+        messages.iter().map(|message| message.id).collect()
+    }
+
+    #[test]
+    fn resuming_only_returns_missed_events_of_the_user() {
+        let hub = StreamHub::default();
+        hub.publish("alice", away(true));
+        hub.publish("bob", away(true));
+        hub.publish("alice", away(false));
+        hub.publish("alice", away(true));
+
+        assert_eq!(ids(&hub.subscribe("alice", Some(0)).0), vec![1, 3, 4]);
+        assert_eq!(ids(&hub.subscribe("alice", Some(3)).0), vec![4]);
+        assert_eq!(ids(&hub.subscribe("alice", Some(4)).0), Vec::<u64>::new());
+        assert_eq!(ids(&hub.subscribe("bob", Some(0)).0), vec![2]);
+    }
+
+    #[test]
+    fn nothing_is_resumed_without_a_known_id() {
+        let hub = StreamHub::default();
+        hub.publish("alice", away(true));
+
+        assert!(hub.subscribe("alice", None).0.is_empty());
+
+        //An id from before a restart
+        assert!(hub.subscribe("alice", Some(42)).0.is_empty());
+    }
+
+    #[test]
+    fn clients_only_receive_events_of_their_user() {
+        let hub = StreamHub::default();
+        let (_, mut alice) = hub.subscribe("alice", None);
+        let (_, mut bob) = hub.subscribe("bob", None);
+
+        hub.publish("alice", away(true));
+
+        assert_eq!(alice.try_recv().map(|message| message.id).ok(), Some(1));
+        assert!(bob.try_recv().is_err());
+    }
+}
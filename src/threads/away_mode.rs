@@ -0,0 +1,101 @@
+use crate::appdata::AppData;
+use crate::types::event::Event;
+use crate::types::honeywell::SystemMode;
+use crate::types::presence::AwayAction;
+
+use std::collections::HashMap;
+use std::thread;
+
+/**
+Start a thread which switches the home of a User to away mode when the last person leaves, and back when the first person returns
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    let receiver = data.events.subscribe();
+
+    thread::spawn(move || {
+        for event in receiver {
+            if let Event::PRESENCECHANGED { user_id, .. } = event {
+                update_away_mode(&data, user_id);
+            }
+        }
+    });
+}
+
+fn update_away_mode(data: &AppData, user_id: String) {
+    let anyone_home = crate::common::presence::is_anyone_home(data.database.clone(), user_id.clone());
+    let away_mode = crate::common::presence::get_away_mode(data.database.clone(), user_id.clone());
+    if anyone_home.is_err() || away_mode.is_err() {
+        eprintln!("An error occurred: {:?} {:?}", anyone_home.err(), away_mode.err());
+        return;
+    }
+
+    let away_mode = match away_mode.unwrap() {
+        Some(away_mode) if away_mode.enabled => away_mode,
+        _ => return
+    };
+
+    let away = match anyone_home.unwrap() {
+        Some(anyone_home) => !anyone_home,
+        None => return
+    };
+
+    if away == away_mode.away {
+        return;
+    }
+
+    let mut previous_modes = away_mode.previous_modes;
+    for action in &away_mode.actions {
+        if execute_action(data, &user_id, action, away, &mut previous_modes).is_err() {
+            eprintln!("Away mode action {:?} failed for User '{}'", action, user_id);
+        }
+    }
+
+    if let Err(e) = crate::common::presence::set_away(data.database.clone(), user_id.clone(), away, &previous_modes) {
+        eprintln!("An error occurred: {:?}", e);
+        return;
+    }
+
+    data.events.publish(Event::AWAYMODECHANGED { user_id, away });
+}
+
+fn execute_action(data: &AppData, user_id: &str, action: &AwayAction, away: bool, previous_modes: &mut HashMap<String, SystemMode>) -> Result<(), ()> {
+    match action {
+        AwayAction::HONEYWELL { service_id, mode } if away => {
+            //A mode which can't be read is restored as AUTO
+            if let Ok(Some(previous_mode)) = crate::services::honeywell::get_system_mode(data.database.clone(), service_id.clone()) {
+                previous_modes.insert(service_id.clone(), previous_mode);
+            }
+
+            crate::services::honeywell::set_system_mode(data.database.clone(), service_id.clone(), *mode)
+        },
+        AwayAction::HONEYWELL { service_id, .. } => {
+            let mode = previous_modes.get(service_id).copied().unwrap_or(SystemMode::AUTO);
+            crate::services::honeywell::set_system_mode(data.database.clone(), service_id.clone(), mode)?;
+            previous_modes.remove(service_id);
+            Ok(())
+        },
+        AwayAction::SCENE { away_scene_id, home_scene_id } => {
+            let scene_id = if away { Some(away_scene_id) } else { home_scene_id.as_ref() };
+            if scene_id.is_none() {
+                return Ok(());
+            }
+
+            let scenes = crate::common::scene::get_scenes(data.database.clone(), user_id.to_string());
+            if scenes.is_err() {
+                eprintln!("An error occurred: {:?}", scenes.err());
+                return Err(());
+            }
+
+            let scenes = scenes.unwrap();
+            let scene = scenes.iter().find(|scene| Some(&scene.scene_id) == scene_id);
+            if scene.is_none() {
+                return Err(());
+            }
+
+            crate::common::scene::activate(data, user_id.to_string(), scene.unwrap()).map(|_| ())
+        }
+    }
+}
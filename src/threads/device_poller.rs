@@ -0,0 +1,38 @@
+use crate::appdata::AppData;
+
+use std::thread;
+use std::time::Duration;
+
+/// How often the Devices of all Users are polled
+const POLL_INTERVAL_SECONDS: u64 = 120;
+
+/**
+Start a thread which polls the Devices of all Users. Discovering Devices publishes their state changes
+and the health of their Services on the EventBus, most Services have no way to push changes to us
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    thread::spawn(move || {
+        loop {
+            poll(&data);
+            thread::sleep(Duration::from_secs(POLL_INTERVAL_SECONDS));
+        }
+    });
+}
+
+fn poll(data: &AppData) {
+    let users = crate::common::service::get_users_with_services(data.database.clone());
+    if users.is_err() {
+        eprintln!("Unable to fetch Users: {:?}", users.err());
+        return;
+    }
+
+    for user_id in users.unwrap() {
+        let devices = crate::services::get_user_devices(data, user_id.clone());
+        if devices.is_err() {
+            eprintln!("Unable to poll the Devices of User '{}'", user_id);
+        }
+    }
+}
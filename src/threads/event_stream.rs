@@ -0,0 +1,22 @@
+use crate::appdata::AppData;
+use crate::types::stream::StreamEvent;
+
+use std::thread;
+
+/**
+Start a thread which forwards Events from the EventBus to the clients connected to the live stream
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    let receiver = data.events.subscribe();
+
+    thread::spawn(move || {
+        for event in receiver {
+            if let Some(stream_event) = StreamEvent::from_event(&event) {
+                data.stream.publish(event.user_id(), stream_event);
+            }
+        }
+    });
+}
@@ -0,0 +1,148 @@
+use crate::appdata::AppData;
+use crate::types::device::{DeviceCommand, DeviceProperty};
+use crate::types::schedule::Schedule;
+
+use chrono::TimeZone;
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+/// How often schedules are evaluated. Switchpoints have a resolution of one minute
+const EVALUATE_INTERVAL_SECONDS: u64 = 30;
+/// Heating never starts earlier than this before a switchpoint
+const MAX_PREHEAT_SECONDS: i64 = 4 * 3600;
+/// How long the outdoor temperature of a User is reused, weather stations update every 10 minutes
+const OUTDOOR_TEMPERATURE_TTL_SECONDS: i64 = 900;
+/// A recorded temperature older than this is too stale to base an early start on
+const MAX_AMBIENT_AGE_SECONDS: i64 = 2 * 3600;
+
+/**
+Start a thread which applies the setpoint of a heating schedule when a switchpoint passes.
+A setpoint changed by hand is left alone until the next switchpoint, Devices held by a vacation or with an open window are skipped.
+Zones which learned how fast they heat up start early, so they reach the setpoint of a switchpoint at its time
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    thread::spawn(move || {
+        //The outdoor temperature per User, with the moment it was fetched
+        let mut outdoor_temperatures: HashMap<String, (i64, Option<f32>)> = HashMap::new();
+
+        loop {
+            evaluate(&data, &mut outdoor_temperatures);
+            thread::sleep(Duration::from_secs(EVALUATE_INTERVAL_SECONDS));
+        }
+    });
+}
+
+fn evaluate(data: &AppData, outdoor_temperatures: &mut HashMap<String, (i64, Option<f32>)>) {
+    let schedules = crate::common::schedule::get_enabled_schedules(data.database.clone());
+    if schedules.is_err() {
+        eprintln!("Unable to fetch heating schedules: {:?}", schedules.err());
+        return;
+    }
+
+    //Devices held by a vacation or with an open window are restored by their own threads
+    let held = crate::common::vacation::get_held_devices(data.database.clone());
+    let open_windows = crate::common::open_window::get_open_windows(data.database.clone());
+    if held.is_err() || open_windows.is_err() {
+        eprintln!("Unable to fetch held devices: {:?} {:?}", held.err(), open_windows.err());
+        return;
+    }
+
+    let mut held = held.unwrap();
+    held.extend(open_windows.unwrap().into_iter().map(|(_, open_window)| open_window.device_id));
+    let now = chrono::Local::now();
+    for (user_id, schedule, last_applied) in schedules.unwrap() {
+        if held.contains(&schedule.device_id) {
+            continue;
+        }
+
+        //The switchpoint heating started early for is recorded as applied, so it isn't applied again when it passes
+        if let Some((switchpoint, setpoint)) = preheat_switchpoint(data, &user_id, &schedule, last_applied, now, outdoor_temperatures) {
+            if crate::services::execute_registered(data, schedule.device_id.clone(), DeviceCommand::SETPOINT(setpoint)).is_err() {
+                eprintln!("Unable to start heating early for the schedule of Device '{}', retrying on the next evaluation", schedule.device_id);
+                continue;
+            }
+
+            if let Err(e) = crate::common::schedule::set_applied(data.database.clone(), schedule.device_id.clone(), switchpoint) {
+                eprintln!("An error occurred: {:?}", e);
+            }
+
+            continue;
+        }
+
+        let active = schedule.active_switchpoint(now.naive_local());
+        if active.is_none() {
+            continue;
+        }
+
+        //Around DST changes a local time may not exist, or exist twice
+        let (started, setpoint) = active.unwrap();
+        let started = chrono::Local.from_local_datetime(&started).earliest().map(|started| started.timestamp()).unwrap_or(0);
+        if last_applied >= started {
+            continue;
+        }
+
+        if crate::services::execute_registered(data, schedule.device_id.clone(), DeviceCommand::SETPOINT(setpoint)).is_err() {
+            eprintln!("Unable to apply the heating schedule of Device '{}', retrying on the next evaluation", schedule.device_id);
+            continue;
+        }
+
+        let set_result = crate::common::schedule::set_applied(data.database.clone(), schedule.device_id.clone(), now.timestamp());
+        if set_result.is_err() {
+            eprintln!("An error occurred: {:?}", set_result.err());
+        }
+    }
+}
+
+/**
+Check if a Zone should start heating for its next switchpoint. This is the case when the switchpoint raises the setpoint,
+and the learned model of the Zone says it takes longer to heat up than is left until the switchpoint
+
+## Returns
+    None: If the Zone doesn't have to start heating yet, or has no model
+    Some: The Unix timestamp of the switchpoint, and its setpoint
+*/
+fn preheat_switchpoint(data: &AppData, user_id: &str, schedule: &Schedule, last_applied: i64, now: chrono::DateTime<chrono::Local>, outdoor_temperatures: &mut HashMap<String, (i64, Option<f32>)>) -> Option<(i64, f32)> {
+    let (next, setpoint) = schedule.next_switchpoint(now.naive_local())?;
+    let next = chrono::Local.from_local_datetime(&next).earliest()?.timestamp();
+    if last_applied >= next || next - now.timestamp() > MAX_PREHEAT_SECONDS {
+        return None;
+    }
+
+    let current_setpoint = schedule.active_switchpoint(now.naive_local()).map(|(_, setpoint)| setpoint);
+    if current_setpoint.map(|current_setpoint| setpoint <= current_setpoint).unwrap_or(false) {
+        return None;
+    }
+
+    let model = crate::common::optimal_start::get_model(data.database.clone(), schedule.device_id.clone());
+    let ambient = crate::common::history::get_latest(data.database.clone(), schedule.device_id.clone(), DeviceProperty::AMBIENTTEMPERATURE);
+    if model.is_err() || ambient.is_err() {
+        eprintln!("An error occurred: {:?} {:?}", model.err(), ambient.err());
+        return None;
+    }
+
+    let model = model.unwrap()?;
+    let (recorded_at, ambient) = ambient.unwrap()?;
+    if now.timestamp() - recorded_at > MAX_AMBIENT_AGE_SECONDS || ambient >= setpoint {
+        return None;
+    }
+
+    let outdoor_temperature = match outdoor_temperatures.get(user_id) {
+        Some((fetched_at, temperature)) if now.timestamp() - fetched_at < OUTDOOR_TEMPERATURE_TTL_SECONDS => *temperature,
+        _ => {
+            let temperature = crate::services::get_outdoor_temperature(data, user_id.to_string());
+            outdoor_temperatures.insert(user_id.to_string(), (now.timestamp(), temperature));
+            temperature
+        }
+    };
+
+    let lead = (((setpoint - ambient) * model.minutes_per_degree(outdoor_temperature) * 60.0) as i64).min(MAX_PREHEAT_SECONDS);
+    if now.timestamp() < next - lead {
+        return None;
+    }
+
+    Some((next, setpoint))
+}
@@ -0,0 +1,95 @@
+use crate::appdata::AppData;
+use crate::types::device::{DeviceProperty, DeviceState};
+use crate::types::event::Event;
+
+use std::collections::HashMap;
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often history is downsampled and pruned, and unchanged values are checked for their heartbeat
+const MAINTENANCE_INTERVAL_SECONDS: u64 = 300;
+/// An unchanged value is stored again after this long, so a range always has samples
+const HEARTBEAT_SECONDS: i64 = 3600;
+
+/**
+Start a thread which records the state changes of all Devices from the EventBus. A value is stored when it changed,
+or when it was last stored over an hour ago. The history is downsampled and pruned periodically
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    let receiver = data.events.subscribe();
+
+    thread::spawn(move || {
+        //The last stored value per Device and property, with the moment it was stored
+        let mut last_recorded: HashMap<(String, DeviceProperty), (i64, f32)> = HashMap::new();
+        let mut next_maintenance = Instant::now();
+
+        loop {
+            match receiver.recv_timeout(next_maintenance.saturating_duration_since(Instant::now())) {
+                Ok(Event::DEVICESTATECHANGED { device_id, state, .. }) => record_state(&data, &mut last_recorded, &device_id, &state),
+                Ok(_) => {},
+                Err(RecvTimeoutError::Timeout) => {
+                    maintain(&data, &mut last_recorded);
+                    next_maintenance = Instant::now() + Duration::from_secs(MAINTENANCE_INTERVAL_SECONDS);
+                },
+                Err(RecvTimeoutError::Disconnected) => return
+            }
+        }
+    });
+}
+
+fn record_state(data: &AppData, last_recorded: &mut HashMap<(String, DeviceProperty), (i64, f32)>, device_id: &str, state: &DeviceState) {
+    //Values of an offline Device are stale, and shouldn't be repeated by the heartbeat either
+    if !state.online {
+        last_recorded.retain(|(recorded_device_id, _), _| recorded_device_id != device_id);
+        return;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let mut samples: Vec<(String, DeviceProperty, i64, f32)> = vec![];
+    for property in DeviceProperty::ALL.iter() {
+        let value = property.value(state);
+        if value.is_none() {
+            continue;
+        }
+
+        let value = value.unwrap();
+        let key = (device_id.to_string(), *property);
+        let changed = match last_recorded.get(&key) {
+            Some((_, last_value)) => (value - last_value).abs() > f32::EPSILON,
+            None => true
+        };
+
+        if changed {
+            last_recorded.insert(key, (now, value));
+            samples.push((device_id.to_string(), *property, now, value));
+        }
+    }
+
+    let record_result = crate::common::history::record(data.database.clone(), &samples);
+    if record_result.is_err() {
+        eprintln!("Unable to record Device history: {:?}", record_result.err());
+    }
+}
+
+fn maintain(data: &AppData, last_recorded: &mut HashMap<(String, DeviceProperty), (i64, f32)>) {
+    let now = chrono::Utc::now().timestamp();
+
+    let mut samples: Vec<(String, DeviceProperty, i64, f32)> = vec![];
+    for ((device_id, property), (recorded_at, value)) in last_recorded.iter_mut() {
+        if now - *recorded_at >= HEARTBEAT_SECONDS {
+            *recorded_at = now;
+            samples.push((device_id.clone(), *property, now, *value));
+        }
+    }
+
+    let record_result = crate::common::history::record(data.database.clone(), &samples);
+    let downsample_result = crate::common::history::downsample(data.database.clone(), now);
+    let prune_result = crate::common::history::prune(data.database.clone(), now);
+    if record_result.is_err() || downsample_result.is_err() || prune_result.is_err() {
+        eprintln!("Unable to maintain Device history: {:?} {:?} {:?}", record_result.err(), downsample_result.err(), prune_result.err());
+    }
+}
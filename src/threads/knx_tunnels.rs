@@ -0,0 +1,33 @@
+use crate::appdata::AppData;
+use crate::types::service::ServiceType;
+
+use std::thread;
+use std::time::Duration;
+
+/// How often to check for KNX Services without a tunnel
+const CHECK_INTERVAL_SECONDS: u64 = 60;
+
+/**
+Start a thread which makes sure every KNX Service has a tunnel to its gateway,
+so group telegrams are received even when nobody is querying the Devices
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    thread::spawn(move || {
+        loop {
+            let services = crate::common::service::get_services_of_type(data.database.clone(), ServiceType::KNX);
+            match services {
+                Ok(services) => {
+                    for service_id in services {
+                        let _ = crate::services::knx::ensure_tunnel(&data.knx_tunnels, data.database.clone(), service_id);
+                    }
+                },
+                Err(e) => eprintln!("Unable to fetch KNX Services: {:?}", e)
+            }
+
+            thread::sleep(Duration::from_secs(CHECK_INTERVAL_SECONDS));
+        }
+    });
+}
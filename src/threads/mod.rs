@@ -1,2 +1,17 @@
 pub mod honeywell_refresh_token;
-pub mod google_refresh_token;
\ No newline at end of file
+pub mod google_refresh_token;
+pub mod service_oauth_refresh_token;
+pub mod knx_tunnels;
+pub mod services_config_watcher;
+pub mod heating_schedules;
+pub mod optimal_start;
+pub mod rules;
+pub mod history_recorder;
+pub mod event_stream;
+pub mod report_state;
+pub mod notifier;
+pub mod webhook_dispatcher;
+pub mod away_mode;
+pub mod vacations;
+pub mod open_window;
+pub mod device_poller;
@@ -0,0 +1,189 @@
+use crate::appdata::AppData;
+use crate::types::device::{DeviceAlert, DeviceState};
+use crate::types::event::Event;
+use crate::types::notification::{Notification, NotificationChannel};
+
+use std::thread;
+use tera::Context;
+
+/**
+Start a thread which notifies Users of alerts raised by their Devices, through the channels subscribed to the alert.
+A User is notified once when an alert is raised, and again only after it has cleared and is raised again.
+Open windows are not reported by Devices, they are notified when open window detection publishes them
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    let receiver = data.events.subscribe();
+
+    thread::spawn(move || {
+        for event in receiver {
+            match event {
+                Event::DEVICESTATECHANGED { user_id, device_id, state, .. } => check_alerts(&data, user_id, device_id, &state),
+                Event::OPENWINDOWCHANGED { user_id, device_id, open: true } => notify_open_window(&data, user_id, device_id),
+                _ => {}
+            }
+        }
+    });
+}
+
+fn check_alerts(data: &AppData, user_id: String, device_id: String, state: &DeviceState) {
+    let active = crate::common::notification::get_active_alerts(data.database.clone(), device_id.clone());
+    if active.is_err() {
+        eprintln!("An error occurred: {:?}", active.err());
+        return;
+    }
+
+    let (cleared, raised) = alert_changes(state, &active.unwrap());
+    for alert in cleared {
+        if let Err(e) = crate::common::notification::clear_alert(data.database.clone(), device_id.clone(), alert) {
+            eprintln!("An error occurred: {:?}", e);
+        }
+    }
+
+    if raised.is_empty() {
+        return;
+    }
+
+    let device = crate::common::device::get_device(data.database.clone(), device_id.clone());
+    let channels = crate::common::notification::get_channels(data.database.clone(), user_id.clone());
+    if device.is_err() || channels.is_err() {
+        eprintln!("An error occurred: {:?} {:?}", device.err(), channels.err());
+        return;
+    }
+
+    let device_name = match device.unwrap() {
+        Some(device) => device.display_name(),
+        None => return
+    };
+
+    let channels = channels.unwrap();
+    for alert in raised {
+        //Marked active before sending, a channel which is down shouldn't cause a notification through every other channel on every poll
+        if let Err(e) = crate::common::notification::set_alert_active(data.database.clone(), user_id.clone(), device_id.clone(), alert) {
+            eprintln!("An error occurred: {:?}", e);
+            continue;
+        }
+
+        let mut context = Context::new();
+        context.insert("device_id", &device_id);
+        context.insert("device_name", &device_name);
+        context.insert("alert", &alert);
+        context.insert("title", alert.title());
+        context.insert("raised_at", &chrono::Local::now().format("%Y-%m-%d %H:%M").to_string());
+        context.insert("state", state);
+
+        notify(data, &channels, &device_id, &device_name, alert, &context);
+    }
+}
+
+/**
+Compare the alerts of a Device with the alerts the User was already notified of
+
+## Parameters
+    state: The new state of the Device
+    active: The alerts the User was notified of, which have not cleared since
+
+## Returns
+    The alerts which cleared, and the alerts which were raised
+*/
+fn alert_changes(state: &DeviceState, active: &[DeviceAlert]) -> (Vec<DeviceAlert>, Vec<DeviceAlert>) {
+    //A Device which is offline without alerts is most likely unreachable, we don't know if its alerts cleared
+    if !state.online && state.alerts.is_empty() {
+        return (vec![], vec![]);
+    }
+
+    let cleared = active.iter().filter(|alert| !state.alerts.contains(alert)).copied().collect();
+    let raised = state.alerts.iter().filter(|alert| !active.contains(alert)).copied().collect();
+
+    (cleared, raised)
+}
+
+fn notify_open_window(data: &AppData, user_id: String, device_id: String) {
+    let device = crate::common::device::get_device(data.database.clone(), device_id.clone());
+    let channels = crate::common::notification::get_channels(data.database.clone(), user_id);
+    let detection = crate::common::open_window::get_detection(data.database.clone(), device_id.clone());
+    if device.is_err() || channels.is_err() || detection.is_err() {
+        eprintln!("An error occurred: {:?} {:?} {:?}", device.err(), channels.err(), detection.err());
+        return;
+    }
+
+    let (device_name, detection) = match (device.unwrap(), detection.unwrap()) {
+        (Some(device), Some(detection)) => (device.display_name(), detection),
+        _ => return
+    };
+
+    let alert = DeviceAlert::OPENWINDOW;
+    let mut context = Context::new();
+    context.insert("device_id", &device_id);
+    context.insert("device_name", &device_name);
+    context.insert("alert", &alert);
+    context.insert("title", alert.title());
+    context.insert("raised_at", &chrono::Local::now().format("%Y-%m-%d %H:%M").to_string());
+    context.insert("frost_setpoint", &detection.frost_setpoint);
+
+    notify(data, &channels.unwrap(), &device_id, &device_name, alert, &context);
+}
+
+/**
+Send a notification of an alert through all enabled channels subscribed to it
+*/
+fn notify(data: &AppData, channels: &[NotificationChannel], device_id: &str, device_name: &str, alert: DeviceAlert, context: &Context) {
+    for channel in channels.iter().filter(|channel| channel.enabled && channel.alerts.contains(&alert)) {
+        let body = crate::common::notification::render(data, channel, alert, context);
+        if body.is_err() {
+            continue;
+        }
+
+        let notification = Notification {
+            title: format!("{}: {}", alert.title(), device_name),
+            body: body.unwrap(),
+            alert: Some(alert),
+            device_id: Some(device_id.to_string())
+        };
+
+        if crate::common::notification::send(&channel.target, &notification).is_err() {
+            eprintln!("Unable to send notification through channel '{}'", channel.channel_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(online: bool, alerts: Vec<DeviceAlert>) -> DeviceState {
+        DeviceState { online, alerts, ..DeviceState::default() }
+    }
+
+    #[test]
+    fn alerts_are_raised_once() {
+        let (cleared, raised) = alert_changes(&state(true, vec![DeviceAlert::BATTERYLOW]), &[]);
+        assert!(cleared.is_empty());
+        assert_eq!(raised, vec![DeviceAlert::BATTERYLOW]);
+
+        let (cleared, raised) = alert_changes(&state(true, vec![DeviceAlert::BATTERYLOW, DeviceAlert::COMMLOST]), &[DeviceAlert::BATTERYLOW]);
+        assert!(cleared.is_empty());
+        assert_eq!(raised, vec![DeviceAlert::COMMLOST]);
+    }
+
+    #[test]
+    fn alerts_which_are_gone_are_cleared() {
+        let (cleared, raised) = alert_changes(&state(true, vec![DeviceAlert::COMMLOST]), &[DeviceAlert::BATTERYLOW, DeviceAlert::COMMLOST]);
+        assert_eq!(cleared, vec![DeviceAlert::BATTERYLOW]);
+        assert!(raised.is_empty());
+    }
+
+    #[test]
+    fn unreachable_devices_keep_their_alerts() {
+        let (cleared, raised) = alert_changes(&state(false, vec![]), &[DeviceAlert::BATTERYLOW]);
+        assert!(cleared.is_empty());
+        assert!(raised.is_empty());
+
+        //An offline Device which still reports alerts is reachable
+        let (cleared, raised) = alert_changes(&state(false, vec![DeviceAlert::COMMLOST]), &[DeviceAlert::BATTERYLOW]);
+        assert_eq!(cleared, vec![DeviceAlert::BATTERYLOW]);
+        assert_eq!(raised, vec![DeviceAlert::COMMLOST]);
+    }
+}
@@ -0,0 +1,289 @@
+use crate::appdata::AppData;
+use crate::types::device::{DeviceCommand, DeviceProperty, DeviceState};
+use crate::types::event::Event;
+use crate::types::history::{Aggregation, HistoryPoint, HistoryQuery, Resolution};
+use crate::types::open_window::{OpenWindow, OpenWindowDetection};
+
+use std::collections::HashMap;
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::Duration;
+
+/// How often open windows are checked for their timeout
+const TIMEOUT_CHECK_INTERVAL_SECONDS: u64 = 60;
+/// How far back the temperature is compared to detect a drop
+const DETECTION_WINDOW_SECONDS: i64 = 15 * 60;
+/// A drop smaller than this, in °C, is never an open window. Thermostats round their temperature, so small drops are noise
+const MIN_DROP: f32 = 0.5;
+/// The temperature has recovered once it rose this much, in °C, above the lowest temperature while the window was open
+const RECOVERY_RISE: f32 = 0.3;
+
+/**
+Start a thread which detects open windows from the recorded temperature of Zones with open window detection enabled.
+When the temperature drops sharply the setpoint is lowered to frost protection, it is restored once the temperature recovers or the timeout passes
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    let receiver = data.events.subscribe();
+
+    thread::spawn(move || {
+        //When the setpoint of a Zone was last restored. A drop from before that moment was already acted upon
+        let mut restored_at: HashMap<String, i64> = HashMap::new();
+
+        loop {
+            match receiver.recv_timeout(Duration::from_secs(TIMEOUT_CHECK_INTERVAL_SECONDS)) {
+                Ok(Event::DEVICESTATECHANGED { user_id, device_id, state, .. }) => check_state(&data, &mut restored_at, user_id, device_id, &state),
+                Ok(_) => {},
+                Err(RecvTimeoutError::Timeout) => check_timeouts(&data, &mut restored_at),
+                Err(RecvTimeoutError::Disconnected) => return
+            }
+        }
+    });
+}
+
+fn check_state(data: &AppData, restored_at: &mut HashMap<String, i64>, user_id: String, device_id: String, state: &DeviceState) {
+    let ambient = match state.ambient_temperature {
+        Some(ambient) if state.online => ambient,
+        _ => return
+    };
+
+    let open_window = crate::common::open_window::get_open_window(data.database.clone(), device_id.clone());
+    if open_window.is_err() {
+        eprintln!("Unable to fetch open window: {:?}", open_window.err());
+        return;
+    }
+
+    match open_window.unwrap() {
+        Some(mut open_window) => {
+            if ambient >= open_window.lowest_temperature + RECOVERY_RISE {
+                close(data, restored_at, user_id, &open_window);
+                return;
+            }
+
+            if ambient < open_window.lowest_temperature {
+                open_window.lowest_temperature = ambient;
+                if let Err(e) = crate::common::open_window::set_open_window(data.database.clone(), user_id, &open_window) {
+                    eprintln!("An error occurred: {:?}", e);
+                }
+            }
+        },
+        None => detect(data, restored_at, user_id, device_id, state, ambient)
+    }
+}
+
+fn detect(data: &AppData, restored_at: &HashMap<String, i64>, user_id: String, device_id: String, state: &DeviceState, ambient: f32) {
+    let detection = crate::common::open_window::get_detection(data.database.clone(), device_id.clone());
+    if detection.is_err() {
+        eprintln!("An error occurred: {:?}", detection.err());
+        return;
+    }
+
+    let detection = match detection.unwrap() {
+        Some(detection) if detection.enabled => detection,
+        _ => return
+    };
+
+    //A Zone which isn't heating, e.g. held by a vacation, loses nothing by an open window
+    let setpoint = match state.setpoint_temperature {
+        Some(setpoint) if setpoint > detection.frost_setpoint => setpoint,
+        _ => return
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let restored_at = restored_at.get(&device_id).copied();
+    let query = HistoryQuery {
+        from: detection_start(now, restored_at),
+        to: now,
+        resolution: Resolution::RAW,
+        aggregation: Aggregation::AVG,
+        interval: None
+    };
+
+    let history = crate::common::history::get_history(data.database.clone(), device_id.clone(), DeviceProperty::AMBIENTTEMPERATURE, &query);
+    if history.is_err() {
+        eprintln!("An error occurred: {:?}", history.err());
+        return;
+    }
+
+    if !is_dropping(&history.unwrap(), ambient, now, restored_at, &detection) {
+        return;
+    }
+
+    if crate::services::execute_registered(data, device_id.clone(), DeviceCommand::SETPOINT(detection.frost_setpoint)).is_err() {
+        eprintln!("Unable to lower the setpoint of Device '{}' for an open window", device_id);
+        return;
+    }
+
+    let open_window = OpenWindow { device_id: device_id.clone(), setpoint, lowest_temperature: ambient, detected_at: now };
+    if let Err(e) = crate::common::open_window::set_open_window(data.database.clone(), user_id.clone(), &open_window) {
+        eprintln!("An error occurred: {:?}", e);
+        return;
+    }
+
+    data.events.publish(Event::OPENWINDOWCHANGED { user_id, device_id, open: true });
+}
+
+/**
+The start of the history in which a drop is looked for. A drop from before the setpoint was last restored was already acted upon
+*/
+fn detection_start(now: i64, restored_at: Option<i64>) -> i64 {
+    (now - DETECTION_WINDOW_SECONDS).max(restored_at.unwrap_or(0))
+}
+
+/**
+Check if the temperature drops fast enough for a window to be open. The drop is measured from the highest temperature
+since detection_start to the current one, which may not be recorded yet
+
+## Parameters
+    points: The recorded temperatures
+    ambient: The current temperature
+    now: The current time
+    restored_at: When the setpoint of the Zone was last restored, if it was
+    detection: The open window detection of the Zone
+
+## Returns
+    If the drop is at least MIN_DROP, at the drop rate of the detection or faster
+*/
+fn is_dropping(points: &[HistoryPoint], ambient: f32, now: i64, restored_at: Option<i64>, detection: &OpenWindowDetection) -> bool {
+    let start = detection_start(now, restored_at);
+    let highest = points.iter()
+        .filter(|point| point.timestamp >= start && point.value.is_finite())
+        .max_by(|a, b| a.value.total_cmp(&b.value));
+    let highest = match highest {
+        Some(highest) => highest,
+        None => return false
+    };
+
+    let drop = highest.value - ambient;
+    let minutes = ((now - highest.timestamp) as f32 / 60.0).max(1.0);
+    drop >= MIN_DROP && drop / minutes >= detection.drop_rate
+}
+
+fn check_timeouts(data: &AppData, restored_at: &mut HashMap<String, i64>) {
+    let open_windows = crate::common::open_window::get_open_windows(data.database.clone());
+    if open_windows.is_err() {
+        eprintln!("Unable to fetch open windows: {:?}", open_windows.err());
+        return;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    for (user_id, open_window) in open_windows.unwrap() {
+        let detection = crate::common::open_window::get_detection(data.database.clone(), open_window.device_id.clone());
+        if detection.is_err() {
+            eprintln!("An error occurred: {:?}", detection.err());
+            continue;
+        }
+
+        //A window of which the detection was disabled or removed is restored right away
+        let timed_out = match detection.unwrap() {
+            Some(detection) if detection.enabled => now - open_window.detected_at >= detection.timeout_minutes as i64 * 60,
+            _ => true
+        };
+
+        if timed_out {
+            close(data, restored_at, user_id, &open_window);
+        }
+    }
+}
+
+/**
+Restore the setpoint of a Zone with an open window. A Zone with a schedule gets the setpoint of its active switchpoint,
+as a switchpoint may have passed while the window was open
+*/
+fn close(data: &AppData, restored_at: &mut HashMap<String, i64>, user_id: String, open_window: &OpenWindow) {
+    let held = crate::common::vacation::get_held_devices(data.database.clone());
+    let schedules = crate::common::schedule::get_schedules(data.database.clone(), user_id.clone());
+    if held.is_err() || schedules.is_err() {
+        eprintln!("An error occurred: {:?} {:?}", held.err(), schedules.err());
+        return;
+    }
+
+    let now = chrono::Local::now();
+
+    //A Zone held by a vacation keeps its frost protection setpoint, the vacation restores it
+    if !held.unwrap().contains(&open_window.device_id) {
+        let schedules = schedules.unwrap();
+        let schedule = schedules.iter().find(|schedule| schedule.enabled && schedule.device_id == open_window.device_id);
+        let setpoint = schedule
+            .and_then(|schedule| schedule.active_switchpoint(now.naive_local()))
+            .map(|(_, setpoint)| setpoint)
+            .unwrap_or(open_window.setpoint);
+
+        if crate::services::execute_registered(data, open_window.device_id.clone(), DeviceCommand::SETPOINT(setpoint)).is_err() {
+            eprintln!("Unable to restore the setpoint of Device '{}' after an open window, retrying later", open_window.device_id);
+            return;
+        }
+
+        if schedule.is_some() {
+            if let Err(e) = crate::common::schedule::set_applied(data.database.clone(), open_window.device_id.clone(), now.timestamp()) {
+                eprintln!("An error occurred: {:?}", e);
+            }
+        }
+    }
+
+    if let Err(e) = crate::common::open_window::delete_open_window(data.database.clone(), open_window.device_id.clone()) {
+        eprintln!("An error occurred: {:?}", e);
+        return;
+    }
+
+    restored_at.insert(open_window.device_id.clone(), now.timestamp());
+    data.events.publish(Event::OPENWINDOWCHANGED { user_id, device_id: open_window.device_id.clone(), open: false });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn detection(drop_rate: f32) -> OpenWindowDetection {
+        OpenWindowDetection { device_id: "zone".to_string(), enabled: true, drop_rate, frost_setpoint: 7.0, timeout_minutes: 30 }
+    }
+
+    /// Temperatures recorded the given number of minutes ago
+    fn points(values: &[(i64, f32)]) -> Vec<HistoryPoint> {
+        values.iter().map(|(minutes_ago, value)| HistoryPoint { timestamp: NOW - minutes_ago * 60, value: *value }).collect()
+    }
+
+    #[test]
+    fn a_sharp_drop_is_an_open_window() {
+        let history = points(&[(10, 20.5), (5, 20.5), (2, 20.0)]);
+        //1.5 °C in 5 minutes since the last time it was 20.5
+        assert!(is_dropping(&history, 19.0, NOW, None, &detection(0.2)));
+    }
+
+    #[test]
+    fn a_slow_drop_is_not_an_open_window() {
+        let history = points(&[(14, 20.5), (10, 20.2), (5, 19.9)]);
+        //1 °C in 14 minutes
+        assert!(!is_dropping(&history, 19.5, NOW, None, &detection(0.2)));
+        assert!(is_dropping(&history, 19.5, NOW, None, &detection(0.05)));
+    }
+
+    #[test]
+    fn drops_below_the_noise_floor_are_ignored() {
+        let history = points(&[(1, 20.4)]);
+        //0.4 °C in a minute is fast, but thermostats round their temperature
+        assert!(!is_dropping(&history, 20.0, NOW, None, &detection(0.2)));
+        assert!(is_dropping(&history, 19.9, NOW, None, &detection(0.2)));
+    }
+
+    #[test]
+    fn drops_from_before_a_restore_are_ignored() {
+        //The window was open 10 minutes ago and the setpoint was restored 4 minutes ago
+        let history = points(&[(10, 21.0), (6, 18.0), (3, 18.2)]);
+        assert!(!is_dropping(&history, 18.0, NOW, Some(NOW - 4 * 60), &detection(0.2)));
+
+        //Without the restore, the drop from 21 °C counts
+        assert!(is_dropping(&history, 18.0, NOW, None, &detection(0.2)));
+    }
+
+    #[test]
+    fn history_outside_the_window_is_ignored() {
+        let history = points(&[(30, 22.0), (10, 20.0)]);
+        assert!(!is_dropping(&history, 19.8, NOW, None, &detection(0.05)));
+        assert!(!is_dropping(&[], 19.8, NOW, None, &detection(0.05)));
+    }
+}
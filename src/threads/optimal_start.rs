@@ -0,0 +1,95 @@
+use crate::appdata::AppData;
+use crate::types::optimal_start::{HeatingModel, HeatingObservation};
+
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+/// How often heating hours are collected and the models are learned again
+const LEARN_INTERVAL_SECONDS: u64 = 3600;
+/// How far back heating hours are collected. Hourly history is aggregated a few minutes after the hour, so this spans a few hours
+const LOOKBACK_SECONDS: i64 = 3 * 3600;
+/// How long heating hours are kept, so the models follow changes to the home and the seasons
+const OBSERVATION_RETENTION_SECONDS: i64 = 90 * 86400;
+/// Hours in which the temperature rose less than this, in °C, are too noisy to learn from
+const MIN_RISE: f32 = 0.1;
+
+/**
+Start a thread which learns, for every Zone with a schedule, how many minutes it takes to heat by one degree at the outdoor temperature of the moment.
+Heating hours are taken from the recorded history. The outdoor temperature isn't recorded, so it is fetched from the User's weather station
+every time the models are learned, and remembered per hour until the heating hours of that hour are available
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    thread::spawn(move || {
+        //The outdoor temperature per User and hour, None if the User has no reachable weather station
+        let mut outdoor_temperatures: HashMap<(String, i64), Option<f32>> = HashMap::new();
+
+        loop {
+            learn(&data, &mut outdoor_temperatures);
+            thread::sleep(Duration::from_secs(LEARN_INTERVAL_SECONDS));
+        }
+    });
+}
+
+fn learn(data: &AppData, outdoor_temperatures: &mut HashMap<(String, i64), Option<f32>>) {
+    let schedules = crate::common::schedule::get_enabled_schedules(data.database.clone());
+    if schedules.is_err() {
+        eprintln!("Unable to fetch heating schedules: {:?}", schedules.err());
+        return;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let hour = now - now % 3600;
+
+    let schedules = schedules.unwrap();
+    //Every weather station is asked only once an hour, while its Zones may be heating
+    for (user_id, _, _) in &schedules {
+        outdoor_temperatures.entry((user_id.clone(), hour))
+            .or_insert_with(|| crate::services::get_outdoor_temperature(data, user_id.clone()));
+    }
+
+    for (user_id, schedule, _) in schedules {
+        let samples = crate::common::history::get_heating_samples(data.database.clone(), schedule.device_id.clone(), hour - LOOKBACK_SECONDS, hour);
+        if samples.is_err() {
+            eprintln!("An error occurred: {:?}", samples.err());
+            continue;
+        }
+
+        for (observed_at, rise) in samples.unwrap().into_iter().filter(|(_, rise)| *rise >= MIN_RISE) {
+            //A heating hour of which the outdoor temperature wasn't fetched, e.g. from before a restart, would be paired with the wrong one
+            let outdoor_temperature = match outdoor_temperatures.get(&(user_id.clone(), observed_at)) {
+                Some(outdoor_temperature) => *outdoor_temperature,
+                None => continue
+            };
+
+            let observation = HeatingObservation { observed_at, outdoor_temperature, minutes_per_degree: 60.0 / rise };
+            if let Err(e) = crate::common::optimal_start::record_observation(data.database.clone(), schedule.device_id.clone(), &observation) {
+                eprintln!("An error occurred: {:?}", e);
+            }
+        }
+
+        let observations = crate::common::optimal_start::get_observations(data.database.clone(), schedule.device_id.clone());
+        if observations.is_err() {
+            eprintln!("An error occurred: {:?}", observations.err());
+            continue;
+        }
+
+        let model = HeatingModel::fit(schedule.device_id.clone(), &observations.unwrap(), now);
+        if model.is_none() {
+            continue;
+        }
+
+        if let Err(e) = crate::common::optimal_start::set_model(data.database.clone(), &model.unwrap()) {
+            eprintln!("An error occurred: {:?}", e);
+        }
+    }
+
+    outdoor_temperatures.retain(|(_, fetched_in), _| *fetched_in >= hour - LOOKBACK_SECONDS);
+
+    if let Err(e) = crate::common::optimal_start::prune_observations(data.database.clone(), now - OBSERVATION_RETENTION_SECONDS) {
+        eprintln!("Unable to prune heating observations: {:?}", e);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::appdata::AppData;
+use crate::environment::Environment;
+use crate::types::assistant_outgoing::device_state_to_google;
+use crate::types::device::DeviceState;
+use crate::types::event::Event;
+
+use serde_json::Map;
+use std::thread;
+
+/**
+Start a thread which reports state changes of exposed Devices to Google, and requests a SYNC when Services are added or removed.
+Nothing is started when no Google service account is configured
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    if Environment::new().google_service_account_key.is_none() {
+        return;
+    }
+
+    let receiver = data.events.subscribe();
+
+    thread::spawn(move || {
+        for event in receiver {
+            match event {
+                Event::DEVICESTATECHANGED { user_id, device_id, state, .. } => report_device_state(&data, &user_id, device_id, &state),
+                Event::SERVICEADDED { user_id, .. } | Event::SERVICEREMOVED { user_id, .. } => crate::common::homegraph::request_sync_in_background(user_id),
+                _ => {}
+            }
+        }
+    });
+}
+
+/**
+Report the state of a Device to Google, if the Device is exposed
+*/
+fn report_device_state(data: &AppData, user_id: &str, device_id: String, state: &DeviceState) {
+    let registered = crate::common::device::get_device(data.database.clone(), device_id.clone());
+    if registered.is_err() {
+        eprintln!("An error occurred: {:?}", registered.err());
+        return;
+    }
+
+    let registered = match registered.unwrap() {
+        Some(registered) => registered,
+        None => return
+    };
+
+    let unexposed_services = crate::common::service::get_unexposed_services(data.database.clone(), user_id.to_string());
+    if unexposed_services.is_err() {
+        eprintln!("An error occurred: {:?}", unexposed_services.err());
+        return;
+    }
+
+    if !registered.exposed || unexposed_services.unwrap().contains(&registered.service_id) {
+        return;
+    }
+
+    let mut states = Map::new();
+    states.insert(device_id, device_state_to_google(state, &registered.capabilities).into());
+    if crate::common::homegraph::report_state(user_id, states).is_err() {
+        eprintln!("Report State for User '{}' failed", user_id);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::appdata::AppData;
+use crate::types::device::DeviceState;
+use crate::types::event::Event;
+use crate::types::rule::{Rule, SunEvent, Trigger};
+use crate::types::schedule::parse_time_of_day;
+
+use chrono::{Duration as ChronoDuration, TimeZone};
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often time and sun triggers are evaluated
+const TICK_SECONDS: u64 = 60;
+
+/**
+Start a thread which runs Rules when their triggers fire. Time and sun triggers are evaluated every tick,
+Device triggers on the state changes published on the EventBus.
+Webhook triggers are not evaluated here, they fire through /rules/webhook/{token}
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    let receiver = data.events.subscribe();
+
+    thread::spawn(move || {
+        let mut last_tick = chrono::Utc::now().timestamp();
+        let mut next_tick = Instant::now() + Duration::from_secs(TICK_SECONDS);
+
+        loop {
+            match receiver.recv_timeout(next_tick.saturating_duration_since(Instant::now())) {
+                //The first time a Device is seen is not a change
+                Ok(Event::DEVICESTATECHANGED { user_id, device_id, previous: Some(previous), state }) => evaluate_device(&data, user_id, &device_id, &previous, &state),
+                Ok(_) => {},
+                Err(RecvTimeoutError::Timeout) => {
+                    let now = chrono::Utc::now().timestamp();
+                    evaluate_time(&data, last_tick, now);
+
+                    last_tick = now;
+                    next_tick = Instant::now() + Duration::from_secs(TICK_SECONDS);
+                },
+                Err(RecvTimeoutError::Disconnected) => return
+            }
+        }
+    });
+}
+
+fn evaluate_time(data: &AppData, last_tick: i64, now: i64) {
+    let rules = crate::common::rule::get_enabled_rules(data.database.clone());
+    if rules.is_err() {
+        eprintln!("Unable to fetch Rules: {:?}", rules.err());
+        return;
+    }
+
+    for (user_id, rule) in rules.unwrap() {
+        if rule.triggers.iter().any(|trigger| time_fires(trigger, last_tick, now)) {
+            run(data, user_id, &rule);
+        }
+    }
+}
+
+fn evaluate_device(data: &AppData, user_id: String, device_id: &str, previous: &DeviceState, state: &DeviceState) {
+    let rules = crate::common::rule::get_rules(data.database.clone(), user_id.clone());
+    if rules.is_err() {
+        eprintln!("Unable to fetch Rules: {:?}", rules.err());
+        return;
+    }
+
+    for rule in rules.unwrap().iter().filter(|rule| rule.enabled) {
+        if rule.triggers.iter().any(|trigger| device_fires(trigger, device_id, previous, state)) {
+            run(data, user_id.clone(), rule);
+        }
+    }
+}
+
+fn run(data: &AppData, user_id: String, rule: &Rule) {
+    if crate::common::rule::run(data, user_id, rule, None).is_err() {
+        eprintln!("Unable to run Rule '{}'", rule.rule_id);
+    }
+}
+
+/**
+Check if a time or sun trigger fired between the previous tick (exclusive) and now (inclusive)
+*/
+fn time_fires(trigger: &Trigger, last_tick: i64, now: i64) -> bool {
+    let in_window = |timestamp: i64| last_tick < timestamp && timestamp <= now;
+
+    match trigger {
+        Trigger::TIME { time } => {
+            let minutes = parse_time_of_day(time);
+            if minutes.is_none() {
+                return false;
+            }
+
+            //The window may span midnight
+            let minutes = minutes.unwrap();
+            let today = match chrono::Local.timestamp_opt(now, 0).earliest() {
+                Some(today) => today.date_naive(),
+                None => return false
+            };
+            [today - ChronoDuration::days(1), today].iter()
+                .filter_map(|date| date.and_hms_opt(minutes / 60, minutes % 60, 0))
+                .filter_map(|moment| chrono::Local.from_local_datetime(&moment).earliest())
+                .any(|moment| in_window(moment.timestamp()))
+        },
+        Trigger::SUN { event, offset_minutes, latitude, longitude } => {
+            let today = match chrono::Utc.timestamp_opt(now, 0).single() {
+                Some(today) => today.date_naive(),
+                None => return false
+            };
+            [today - ChronoDuration::days(1), today, today + ChronoDuration::days(1)].iter()
+                .filter_map(|date| crate::common::sun::sun_times(*date, *latitude, *longitude))
+                .map(|(sunrise, sunset)| match event {
+                    SunEvent::SUNRISE => sunrise,
+                    SunEvent::SUNSET => sunset
+                })
+                .any(|moment| in_window(moment + *offset_minutes as i64 * 60))
+        },
+        _ => false
+    }
+}
+
+/**
+Check if a Device trigger fired on a state change of a Device
+*/
+fn device_fires(trigger: &Trigger, changed_device_id: &str, previous: &DeviceState, current: &DeviceState) -> bool {
+    match trigger {
+        Trigger::DEVICESTATE { device_id, on } if device_id == changed_device_id => match on {
+            Some(on) => previous.on != Some(*on) && current.on == Some(*on),
+            None => true
+        },
+        Trigger::THRESHOLD { device_id, property, above, below } if device_id == changed_device_id => {
+            !property.in_range(previous, *above, *below) && property.in_range(current, *above, *below)
+        },
+        _ => false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::device::DeviceProperty;
+    use chrono::NaiveDate;
+
+    fn local_timestamp(date: &str, time: &str) -> i64 {
+        let moment = chrono::NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap();
+        chrono::Local.from_local_datetime(&moment).earliest().unwrap().timestamp()
+    }
+
+    #[test]
+    fn time_trigger_fires_once() {
+        let trigger = Trigger::TIME { time: "07:30".to_string() };
+        let at = local_timestamp("2024-03-12", "07:30");
+
+        assert!(time_fires(&trigger, at - 60, at));
+        assert!(!time_fires(&trigger, at, at + 60));
+        assert!(!time_fires(&trigger, at - 120, at - 60));
+        assert!(!time_fires(&Trigger::TIME { time: "7:30".to_string() }, at - 60, at));
+    }
+
+    #[test]
+    fn time_trigger_fires_across_midnight() {
+        let trigger = Trigger::TIME { time: "23:59".to_string() };
+        let at = local_timestamp("2024-03-12", "23:59");
+
+        //The tick after midnight, e.g. when a tick was delayed
+        assert!(time_fires(&trigger, at - 30, at + 90));
+    }
+
+    #[test]
+    fn sun_trigger_fires_with_offset() {
+        let (sunrise, sunset) = crate::common::sun::sun_times(NaiveDate::from_ymd_opt(2024, 6, 21).unwrap(), 52.37, 4.90).unwrap();
+        let sunrise_trigger = Trigger::SUN { event: SunEvent::SUNRISE, offset_minutes: 0, latitude: 52.37, longitude: 4.90 };
+        let sunset_trigger = Trigger::SUN { event: SunEvent::SUNSET, offset_minutes: -30, latitude: 52.37, longitude: 4.90 };
+
+        assert!(time_fires(&sunrise_trigger, sunrise - 60, sunrise));
+        assert!(!time_fires(&sunrise_trigger, sunrise, sunrise + 60));
+        assert!(time_fires(&sunset_trigger, sunset - 1800 - 60, sunset - 1800));
+        assert!(!time_fires(&sunset_trigger, sunset - 60, sunset));
+    }
+
+    #[test]
+    fn sun_trigger_never_fires_during_polar_night() {
+        let trigger = Trigger::SUN { event: SunEvent::SUNRISE, offset_minutes: 0, latitude: 78.22, longitude: 15.65 };
+        let midnight = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp();
+
+        assert!(!time_fires(&trigger, midnight, midnight + 86400));
+    }
+
+    #[test]
+    fn device_state_trigger_fires_on_change() {
+        let off = DeviceState { online: true, on: Some(false), ..DeviceState::default() };
+        let on = DeviceState { online: true, on: Some(true), ..DeviceState::default() };
+
+        let turned_on = Trigger::DEVICESTATE { device_id: "lamp".to_string(), on: Some(true) };
+        assert!(device_fires(&turned_on, "lamp", &off, &on));
+        assert!(!device_fires(&turned_on, "lamp", &on, &on));
+        assert!(!device_fires(&turned_on, "lamp", &on, &off));
+        assert!(!device_fires(&turned_on, "other", &off, &on));
+
+        let any_change = Trigger::DEVICESTATE { device_id: "lamp".to_string(), on: None };
+        assert!(device_fires(&any_change, "lamp", &on, &off));
+    }
+
+    #[test]
+    fn threshold_trigger_fires_when_entering_the_range() {
+        let temperature = |ambient_temperature: Option<f32>| DeviceState { online: true, ambient_temperature, ..DeviceState::default() };
+        let trigger = Trigger::THRESHOLD { device_id: "zone".to_string(), property: DeviceProperty::AMBIENTTEMPERATURE, above: Some(20.0), below: Some(25.0) };
+
+        assert!(device_fires(&trigger, "zone", &temperature(Some(19.5)), &temperature(Some(21.0))));
+        assert!(device_fires(&trigger, "zone", &temperature(None), &temperature(Some(21.0))));
+        //Already in the range, or leaving it
+        assert!(!device_fires(&trigger, "zone", &temperature(Some(21.0)), &temperature(Some(22.0))));
+        assert!(!device_fires(&trigger, "zone", &temperature(Some(21.0)), &temperature(Some(26.0))));
+        //Both bounds are exclusive
+        assert!(!device_fires(&trigger, "zone", &temperature(Some(19.5)), &temperature(Some(20.0))));
+        assert!(!device_fires(&trigger, "other", &temperature(Some(19.5)), &temperature(Some(21.0))));
+    }
+}
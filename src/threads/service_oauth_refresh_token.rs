@@ -0,0 +1,69 @@
+use crate::appdata::AppData;
+use crate::types::service::ServiceType;
+use crate::types::event::Event;
+
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+/// How often to check for expiring tokens
+const CHECK_INTERVAL_SECONDS: u64 = 60;
+
+/**
+Start a thread which refreshes the OAuth tokens of Services before they expire
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    thread::spawn(move || {
+        //Services of which the refresh failed, so the failure is only announced once
+        let mut failed: HashSet<String> = HashSet::new();
+
+        loop {
+            refresh_expiring(&data, &mut failed);
+            thread::sleep(Duration::from_secs(CHECK_INTERVAL_SECONDS));
+        }
+    });
+}
+
+fn refresh_expiring(data: &AppData, failed: &mut HashSet<String>) {
+    let expiring = crate::common::oauth::get_expiring_services(data.database.clone());
+    if expiring.is_err() {
+        eprintln!("Unable to fetch expiring OAuth tokens: {:?}", expiring.err());
+        return;
+    }
+
+    for (service_id, identifier) in expiring.unwrap() {
+        let service_type = ServiceType::from_str(&identifier);
+        if service_type.is_err() {
+            continue;
+        }
+
+        let service_type = service_type.unwrap();
+        let services_configs = data.get_services_configs();
+        let config = crate::config::get_service_config(&services_configs, &service_type);
+        if config.is_none() || config.unwrap().oauth.is_none() {
+            eprintln!("No OAuth configuration found for {}, unable to refresh tokens of Service '{}'", identifier, service_id);
+            continue;
+        }
+
+        //get_access_token refreshes and stores the tokens if they're about to expire
+        let result = crate::common::oauth::get_access_token(data.database.clone(), config.unwrap().oauth.as_ref().unwrap(), service_id.clone());
+        if result.is_ok() {
+            failed.remove(&service_id);
+            continue;
+        }
+
+        eprintln!("Unable to refresh OAuth tokens for Service '{}'", service_id);
+        if !failed.insert(service_id.clone()) {
+            continue;
+        }
+
+        let service = crate::common::service::get_service(data.database.clone(), service_id.clone());
+        if let Ok(Some((user_id, _))) = service {
+            data.events.publish(Event::SERVICEAUTHFAILED { user_id, service_id, service_type });
+        }
+    }
+}
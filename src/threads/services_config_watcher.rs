@@ -0,0 +1,61 @@
+use crate::appdata::AppData;
+use crate::config::{SERVICES_CONF_PATH, reload_services_conf};
+
+use std::fs;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use actix_rt::signal::unix::{signal, SignalKind};
+
+/// How often to check if services.jsonc was modified
+const CHECK_INTERVAL_SECONDS: u64 = 5;
+
+/**
+Start a thread which reloads services.jsonc when it is modified on disk
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    thread::spawn(move || {
+        let mut last_modified = get_modified();
+
+        loop {
+            thread::sleep(Duration::from_secs(CHECK_INTERVAL_SECONDS));
+
+            let modified = get_modified();
+            if modified.is_none() || modified == last_modified {
+                continue;
+            }
+
+            //Even if the file is invalid we don't retry until it's modified again
+            last_modified = modified;
+            reload_services_conf(&data.services_configs);
+        }
+    });
+}
+
+/**
+Reload services.jsonc whenever the process receives SIGHUP. Must be called from within the actix runtime
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn listen_for_sighup(data: AppData) {
+    let hangup = signal(SignalKind::hangup());
+    if hangup.is_err() {
+        eprintln!("Unable to listen for SIGHUP: {:?}", hangup.err());
+        return;
+    }
+
+    let mut hangup = hangup.unwrap();
+    actix_rt::spawn(async move {
+        while hangup.recv().await.is_some() {
+            println!("Received SIGHUP, reloading {}", SERVICES_CONF_PATH);
+            reload_services_conf(&data.services_configs);
+        }
+    });
+}
+
+fn get_modified() -> Option<SystemTime> {
+    fs::metadata(SERVICES_CONF_PATH).and_then(|metadata| metadata.modified()).ok()
+}
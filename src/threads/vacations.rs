@@ -0,0 +1,366 @@
+use crate::appdata::AppData;
+use crate::services::UserDevice;
+use crate::types::device::{DeviceCapability, DeviceCommand, DeviceKind};
+use crate::types::schedule::Schedule;
+use crate::types::service::ServiceType;
+use crate::types::vacation::{HeldDevice, Vacation, VacationStatus};
+
+use chrono::{NaiveDateTime, TimeZone};
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+/// How often vacations are evaluated
+const EVALUATE_INTERVAL_SECONDS: u64 = 60;
+/// Used when a Zone has too little history to know how fast it heats up, in °C per hour
+const DEFAULT_HEATING_RATE: f32 = 1.0;
+/// How much history is used to estimate how fast a Zone heats up, when it has no heating model yet
+const HEATING_RATE_HISTORY_SECONDS: i64 = 30 * 86400;
+/// How long the outdoor temperature of a User is reused, weather stations update every 10 minutes
+const OUTDOOR_TEMPERATURE_TTL_SECONDS: i64 = 900;
+/// Zones are restored at least this long before the return
+const MIN_LEAD_SECONDS: i64 = 1800;
+/// Zones are restored at most this long before the return
+const MAX_LEAD_SECONDS: i64 = 12 * 3600;
+/// Hot water is switched back on this long before the return
+const HOT_WATER_LEAD_SECONDS: i64 = 3600;
+/// A Device which still can't be restored this long after the return is given up on, so the vacation can end
+const GIVE_UP_SECONDS: i64 = 3600;
+
+/**
+Start a thread which holds the Devices of Users on vacation, and restores them in time for their return.
+Every Zone is restored at its own moment, depending on how fast it heats up at the current outdoor temperature
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    thread::spawn(move || {
+        //The outdoor temperature per User, with the moment it was fetched
+        let mut outdoor_temperatures: HashMap<String, (i64, Option<f32>)> = HashMap::new();
+
+        loop {
+            evaluate(&data, &mut outdoor_temperatures);
+            thread::sleep(Duration::from_secs(EVALUATE_INTERVAL_SECONDS));
+        }
+    });
+}
+
+fn evaluate(data: &AppData, outdoor_temperatures: &mut HashMap<String, (i64, Option<f32>)>) {
+    let vacations = crate::common::vacation::get_vacations(data.database.clone());
+    if vacations.is_err() {
+        eprintln!("Unable to fetch vacations: {:?}", vacations.err());
+        return;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    for (user_id, mut vacation) in vacations.unwrap() {
+        let (start, end) = (vacation.start_timestamp(), vacation.end_timestamp());
+        if start.is_none() || end.is_none() {
+            continue;
+        }
+
+        let (start, end) = (start.unwrap(), end.unwrap());
+        let result = match vacation.status {
+            VacationStatus::SCHEDULED if now >= start => begin(data, &user_id, &mut vacation),
+            VacationStatus::ACTIVE if now >= end - MAX_LEAD_SECONDS => restore(data, &user_id, &mut vacation, end, now, outdoor_temperatures),
+            _ => continue
+        };
+
+        if result.is_err() {
+            eprintln!("Unable to update the vacation of User '{}', retrying on the next evaluation", user_id);
+        }
+    }
+}
+
+/**
+Hold all thermostats at the frost protection setpoint and switch off hot water
+*/
+fn begin(data: &AppData, user_id: &str, vacation: &mut Vacation) -> Result<(), ()> {
+    let devices = crate::services::get_user_devices(data, user_id.to_string())?;
+    let open_windows = crate::common::open_window::get_user_open_windows(data.database.clone(), user_id.to_string());
+    if open_windows.is_err() {
+        eprintln!("An error occurred: {:?}", open_windows.err());
+        return Err(());
+    }
+
+    let open_windows = open_windows.unwrap();
+    let mut held: Vec<HeldDevice> = vec![];
+    for user_device in &devices {
+        let state = &user_device.device.state;
+
+        //A Zone with an open window is at frost protection already, the setpoint from before the window was opened is restored
+        let open_window = open_windows.iter().find(|open_window| open_window.device_id == user_device.registered.device_id);
+        let (command, setpoint, on) = if user_device.device.capabilities.contains(&DeviceCapability::TEMPERATURESETTING) {
+            (DeviceCommand::SETPOINT(vacation.frost_setpoint), open_window.map(|open_window| open_window.setpoint).or(state.setpoint_temperature), None)
+        } else if user_device.device.kind == DeviceKind::WATERHEATER && state.on.is_some() {
+            (DeviceCommand::ONOFF(false), None, state.on)
+        } else {
+            continue;
+        };
+
+        //A Device which can't be held is left alone, so its schedule keeps applying
+        if crate::services::execute(data, user_device.service_id.clone(), user_device.service_type.clone(), user_device.device.id.clone(), command).is_err() {
+            eprintln!("Unable to hold Device '{}' for the vacation of User '{}'", user_device.registered.device_id, user_id);
+            continue;
+        }
+
+        held.push(HeldDevice { device_id: user_device.registered.device_id.clone(), setpoint, on, restore_at: None, restored: false });
+    }
+
+    //The hot water of EvoHome systems is not a Device of its own
+    let mut hot_water_services: Vec<String> = devices.iter()
+        .filter(|user_device| user_device.service_type == ServiceType::HONEYWELL)
+        .map(|user_device| user_device.service_id.clone())
+        .collect();
+    hot_water_services.sort();
+    hot_water_services.dedup();
+    hot_water_services.retain(|service_id| crate::services::honeywell::set_hot_water(data.database.clone(), service_id.clone(), Some(false)).is_ok());
+
+    vacation.status = VacationStatus::ACTIVE;
+    vacation.held = held;
+    vacation.hot_water_services = hot_water_services;
+
+    let set_result = crate::common::vacation::set_vacation(data.database.clone(), user_id.to_string(), vacation);
+    if set_result.is_err() {
+        eprintln!("An error occurred: {:?}", set_result.err());
+        return Err(());
+    }
+
+    Ok(())
+}
+
+/**
+Restore the held Devices of which the moment has come. Once the vacation has ended, everything is restored and the vacation is removed
+*/
+fn restore(data: &AppData, user_id: &str, vacation: &mut Vacation, end: i64, now: i64, outdoor_temperatures: &mut HashMap<String, (i64, Option<f32>)>) -> Result<(), ()> {
+    let devices = crate::services::get_user_devices(data, user_id.to_string())?;
+    let schedules = crate::common::schedule::get_schedules(data.database.clone(), user_id.to_string());
+    if schedules.is_err() {
+        eprintln!("An error occurred: {:?}", schedules.err());
+        return Err(());
+    }
+
+    let schedules: Vec<Schedule> = schedules.unwrap().into_iter().filter(|schedule| schedule.enabled).collect();
+    let return_time = chrono::Local.timestamp_opt(end, 0).earliest().map(|time| time.naive_local());
+
+    for held in vacation.held.iter_mut().filter(|held| !held.restored) {
+        let schedule = schedules.iter().find(|schedule| schedule.device_id == held.device_id);
+        let target = restore_target(held, schedule, return_time);
+
+        let restore_at = match target {
+            Some(target) => {
+                let ambient = crate::services::find_device(&devices, &held.device_id).and_then(|user_device| user_device.device.state.ambient_temperature);
+                end - lead_seconds(data, user_id, &held.device_id, target - ambient.unwrap_or(vacation.frost_setpoint), now, outdoor_temperatures)
+            },
+            None => end - HOT_WATER_LEAD_SECONDS
+        };
+
+        held.restore_at = Some(restore_at);
+        if now < restore_at {
+            continue;
+        }
+
+        //A thermostat of which the setpoint was unknown is left at the frost protection setpoint
+        let command = match (target, held.on) {
+            (Some(target), _) => DeviceCommand::SETPOINT(target),
+            (None, Some(on)) => DeviceCommand::ONOFF(on),
+            (None, None) => {
+                held.restored = true;
+                continue;
+            }
+        };
+
+        if crate::services::execute_registered(data, held.device_id.clone(), command).is_err() {
+            eprintln!("Unable to restore Device '{}' after the vacation of User '{}'", held.device_id, user_id);
+            held.restored = now >= end + GIVE_UP_SECONDS;
+            continue;
+        }
+
+        //The schedule continues from the next switchpoint, it shouldn't undo the pre-heating
+        if schedule.is_some() {
+            if let Err(e) = crate::common::schedule::set_applied(data.database.clone(), held.device_id.clone(), now) {
+                eprintln!("An error occurred: {:?}", e);
+            }
+        }
+
+        held.restored = true;
+    }
+
+    //Hot water is handed back to its schedule in time to heat up, a Service which can't be reached is retried
+    if now >= end - HOT_WATER_LEAD_SECONDS {
+        vacation.hot_water_services.retain(|service_id| crate::services::honeywell::set_hot_water(data.database.clone(), service_id.clone(), None).is_err());
+    }
+
+    if now < end || vacation.held.iter().any(|held| !held.restored) {
+        let set_result = crate::common::vacation::set_vacation(data.database.clone(), user_id.to_string(), vacation);
+        if set_result.is_err() {
+            eprintln!("An error occurred: {:?}", set_result.err());
+            return Err(());
+        }
+
+        return Ok(());
+    }
+
+    finish(data, user_id, vacation, &devices, &schedules)
+}
+
+/**
+Hand Honeywell Zones without a schedule of ours, and hot water which couldn't be handed back before, back to their EvoHome schedule, and remove the vacation
+*/
+fn finish(data: &AppData, user_id: &str, vacation: &Vacation, devices: &[UserDevice], schedules: &[Schedule]) -> Result<(), ()> {
+    for held in &vacation.held {
+        let user_device = crate::services::find_device(devices, &held.device_id);
+        if let Some(user_device) = user_device {
+            if user_device.service_type == ServiceType::HONEYWELL && !schedules.iter().any(|schedule| schedule.device_id == held.device_id) {
+                let _ = crate::services::honeywell::follow_zone_schedule(data.database.clone(), user_device.service_id.clone(), user_device.device.id.clone());
+            }
+        }
+    }
+
+    for service_id in &vacation.hot_water_services {
+        let _ = crate::services::honeywell::set_hot_water(data.database.clone(), service_id.clone(), None);
+    }
+
+    let delete_result = crate::common::vacation::delete_vacation(data.database.clone(), user_id.to_string());
+    if delete_result.is_err() {
+        eprintln!("An error occurred: {:?}", delete_result.err());
+        return Err(());
+    }
+
+    //The vacation mode is no longer synced to Google
+    crate::common::homegraph::request_sync_in_background(user_id.to_string());
+    Ok(())
+}
+
+/**
+The setpoint a held Device is restored to. A Zone with a schedule is heated to the setpoint it has at the moment of return,
+otherwise to the setpoint it had before the vacation
+
+## Returns
+    None: If the Device is not a thermostat, or its setpoint was unknown
+    Some: The setpoint
+*/
+fn restore_target(held: &HeldDevice, schedule: Option<&Schedule>, return_time: Option<NaiveDateTime>) -> Option<f32> {
+    match (schedule, return_time) {
+        (Some(schedule), Some(return_time)) => schedule.active_switchpoint(return_time).map(|(_, setpoint)| setpoint).or(held.setpoint),
+        _ => held.setpoint
+    }
+}
+
+/**
+How long before the return a Zone has to start heating, to rise by a number of degrees.
+The heating model of the Zone is used if it has learned one, otherwise the median heating rate from its history
+*/
+fn lead_seconds(data: &AppData, user_id: &str, device_id: &str, degrees: f32, now: i64, outdoor_temperatures: &mut HashMap<String, (i64, Option<f32>)>) -> i64 {
+    let model = crate::common::optimal_start::get_model(data.database.clone(), device_id.to_string());
+    let model = match model {
+        Ok(model) => model,
+        Err(e) => {
+            eprintln!("An error occurred: {:?}", e);
+            None
+        }
+    };
+
+    let minutes_per_degree = match model {
+        Some(model) => {
+            let outdoor_temperature = match outdoor_temperatures.get(user_id) {
+                Some((fetched_at, temperature)) if now - fetched_at < OUTDOOR_TEMPERATURE_TTL_SECONDS => *temperature,
+                _ => {
+                    let temperature = crate::services::get_outdoor_temperature(data, user_id.to_string());
+                    outdoor_temperatures.insert(user_id.to_string(), (now, temperature));
+                    temperature
+                }
+            };
+
+            Some(model.minutes_per_degree(outdoor_temperature))
+        },
+        None => None
+    };
+
+    let heating_rate = match minutes_per_degree {
+        Some(_) => None,
+        None => {
+            let rate = crate::common::history::get_heating_rate(data.database.clone(), device_id.to_string(), now - HEATING_RATE_HISTORY_SECONDS);
+            match rate {
+                Ok(rate) => rate,
+                Err(e) => {
+                    eprintln!("An error occurred: {:?}", e);
+                    None
+                }
+            }
+        }
+    };
+
+    lead(degrees, minutes_per_degree, heating_rate)
+}
+
+/**
+How long a Zone takes to rise by a number of degrees, clamped to MIN_LEAD_SECONDS and MAX_LEAD_SECONDS
+
+## Parameters
+    degrees: How much the Zone has to rise, in °C
+    minutes_per_degree: From the heating model of the Zone, if it has learned one
+    heating_rate: The median heating rate from the history of the Zone in °C per hour, used without a heating model.
+        DEFAULT_HEATING_RATE is used if neither is known
+*/
+fn lead(degrees: f32, minutes_per_degree: Option<f32>, heating_rate: Option<f32>) -> i64 {
+    let seconds_per_degree = match minutes_per_degree {
+        Some(minutes_per_degree) => minutes_per_degree * 60.0,
+        None => 3600.0 / heating_rate.unwrap_or(DEFAULT_HEATING_RATE)
+    };
+
+    ((degrees.max(0.0) * seconds_per_degree) as i64).clamp(MIN_LEAD_SECONDS, MAX_LEAD_SECONDS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::schedule::{DailySchedule, Day, Switchpoint};
+
+    fn held(setpoint: Option<f32>) -> HeldDevice {
+        HeldDevice { device_id: "zone".to_string(), setpoint, on: None, restore_at: None, restored: false }
+    }
+
+    fn at(date_time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date_time, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn zones_are_restored_to_the_setpoint_at_the_return() {
+        //Mondays, 2024-01-01 is a Monday
+        let schedule = Schedule {
+            device_id: "zone".to_string(),
+            enabled: true,
+            days: vec![DailySchedule { day: Day::MONDAY, switchpoints: vec![Switchpoint { time: "06:30".to_string(), setpoint: 20.0 }, Switchpoint { time: "22:00".to_string(), setpoint: 16.0 }] }],
+            exceptions: vec![]
+        };
+
+        assert_eq!(restore_target(&held(Some(19.0)), Some(&schedule), Some(at("2024-01-01 18:00"))), Some(20.0));
+        assert_eq!(restore_target(&held(Some(19.0)), Some(&schedule), Some(at("2024-01-01 23:00"))), Some(16.0));
+        assert_eq!(restore_target(&held(Some(19.0)), None, Some(at("2024-01-01 18:00"))), Some(19.0));
+        assert_eq!(restore_target(&held(None), None, Some(at("2024-01-01 18:00"))), None);
+
+        //Without switchpoints the setpoint from before the vacation is used
+        let empty = Schedule { days: vec![], ..schedule };
+        assert_eq!(restore_target(&held(Some(19.0)), Some(&empty), Some(at("2024-01-01 18:00"))), Some(19.0));
+    }
+
+    #[test]
+    fn the_heating_model_comes_before_the_history() {
+        //4 degrees at 30 minutes per degree
+        assert_eq!(lead(4.0, Some(30.0), Some(0.5)), 2 * 3600);
+        //4 degrees at 2 degrees per hour
+        assert_eq!(lead(4.0, None, Some(2.0)), 2 * 3600);
+        //4 degrees at the default of 1 degree per hour
+        assert_eq!(lead(4.0, None, None), 4 * 3600);
+    }
+
+    #[test]
+    fn the_lead_is_clamped() {
+        assert_eq!(lead(0.5, Some(30.0), None), MIN_LEAD_SECONDS);
+        assert_eq!(lead(-3.0, None, None), MIN_LEAD_SECONDS);
+        assert_eq!(lead(20.0, None, Some(0.5)), MAX_LEAD_SECONDS);
+        assert_eq!(lead(4.0, None, Some(0.0)), MAX_LEAD_SECONDS);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::appdata::AppData;
+use crate::types::event::Event;
+use crate::types::stream::StreamEvent;
+use crate::types::webhook::{DeliveryAttempt, DeliveryStatus, DueDelivery};
+
+use rand::Rng;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the queue is checked for deliveries of which the next attempt is due
+const QUEUE_INTERVAL_SECONDS: u64 = 10;
+/// The delay before the first retry, doubled for every retry after it
+const RETRY_BASE_SECONDS: i64 = 30;
+/// A delivery is given up on after this many attempts, about two hours after it was created
+const MAX_ATTEMPTS: u32 = 8;
+/// How often old deliveries are removed from the delivery log
+const PRUNE_INTERVAL_SECONDS: u64 = 3600;
+/// Deliveries are kept in the delivery log for this long
+const DELIVERY_RETENTION_SECONDS: i64 = 7 * 86400;
+/// How much of a response body is kept in the delivery log
+const MAX_RESPONSE_LENGTH: usize = 1024;
+/// How long a receiver is given to respond
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+
+/**
+Start a thread which queues Events from the EventBus for the webhook subscriptions of their User, and a thread which delivers the queue.
+Failed deliveries are retried with exponential backoff. The queue lives in the database, so it survives a restart
+
+## Parameters
+    data: An instance of AppData
+*/
+pub fn start(data: AppData) {
+    let receiver = data.events.subscribe();
+    let (wake, woken) = channel::<()>();
+
+    let delivery_data = data.clone();
+    thread::spawn(move || {
+        let mut next_prune = Instant::now();
+        loop {
+            deliver_due(&delivery_data);
+
+            if next_prune <= Instant::now() {
+                let prune_result = crate::common::webhook::prune_deliveries(delivery_data.database.clone(), chrono::Utc::now().timestamp() - DELIVERY_RETENTION_SECONDS);
+                if prune_result.is_err() {
+                    eprintln!("An error occurred: {:?}", prune_result.err());
+                }
+
+                next_prune = Instant::now() + Duration::from_secs(PRUNE_INTERVAL_SECONDS);
+            }
+
+            if let Err(RecvTimeoutError::Disconnected) = woken.recv_timeout(Duration::from_secs(QUEUE_INTERVAL_SECONDS)) {
+                return;
+            }
+
+            //A burst of Events is delivered in one run
+            while woken.try_recv().is_ok() {}
+        }
+    });
+
+    thread::spawn(move || {
+        for event in receiver {
+            //New deliveries are attempted right away
+            if queue_event(&data, &event) && wake.send(()).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+/**
+Queue an Event for the subscriptions it matches
+
+## Returns
+    If anything was queued
+*/
+fn queue_event(data: &AppData, event: &Event) -> bool {
+    let stream_event = StreamEvent::from_event(event);
+    if stream_event.is_none() {
+        return false;
+    }
+
+    let stream_event = stream_event.unwrap();
+    let subscriptions = crate::common::webhook::get_subscriptions(data.database.clone(), event.user_id().to_string());
+    if subscriptions.is_err() {
+        eprintln!("An error occurred: {:?}", subscriptions.err());
+        return false;
+    }
+
+    let mut queued = false;
+    for subscription in subscriptions.unwrap().into_iter().filter(|subscription| subscription.enabled && subscription.matches(&stream_event)) {
+        let delivery_id: String = rand::thread_rng().sample_iter(&rand::distributions::Alphanumeric).take(32).map(char::from).collect();
+        let payload = json!({
+            "id": delivery_id,
+            "created_at": chrono::Utc::now().timestamp(),
+            "event": stream_event
+        }).to_string();
+
+        let queue_result = crate::common::webhook::queue_delivery(data.database.clone(), event.user_id().to_string(), subscription.subscription_id, delivery_id, stream_event.name(), payload);
+        if queue_result.is_err() {
+            eprintln!("An error occurred: {:?}", queue_result.err());
+            continue;
+        }
+
+        queued = true;
+    }
+
+    queued
+}
+
+/**
+Attempt all deliveries which are due. Receivers are delivered to in parallel, so a slow receiver doesn't hold up the others.
+The deliveries to one receiver are attempted in order
+*/
+fn deliver_due(data: &AppData) {
+    let now = chrono::Utc::now().timestamp();
+    let deliveries = crate::common::webhook::get_due_deliveries(data.database.clone(), now);
+    if deliveries.is_err() {
+        eprintln!("An error occurred: {:?}", deliveries.err());
+        return;
+    }
+
+    let mut per_receiver: HashMap<String, Vec<DueDelivery>> = HashMap::new();
+    for delivery in deliveries.unwrap() {
+        per_receiver.entry(delivery.url.clone()).or_default().push(delivery);
+    }
+
+    thread::scope(|scope| {
+        for deliveries in per_receiver.values() {
+            scope.spawn(move || {
+                for delivery in deliveries {
+                    deliver(data, delivery);
+                }
+            });
+        }
+    });
+}
+
+fn deliver(data: &AppData, delivery: &DueDelivery) {
+    let attempt = attempt(delivery);
+    let succeeded = attempt.response_status.map(|status| (200..300).contains(&status)).unwrap_or(false);
+
+    let (status, next_attempt_at) = if succeeded {
+        (DeliveryStatus::DELIVERED, None)
+    } else if delivery.attempts + 1 >= MAX_ATTEMPTS {
+        (DeliveryStatus::FAILED, None)
+    } else {
+        (DeliveryStatus::PENDING, Some(attempt.attempted_at + RETRY_BASE_SECONDS * 2i64.pow(delivery.attempts)))
+    };
+
+    let record_result = crate::common::webhook::record_attempt(data.database.clone(), delivery.delivery_id.clone(), &attempt, status, next_attempt_at);
+    if record_result.is_err() {
+        eprintln!("An error occurred: {:?}", record_result.err());
+    }
+}
+
+/**
+POST a delivery to its subscription. The timestamp is that of the attempt, so receivers can reject deliveries replayed later on
+*/
+fn attempt(delivery: &DueDelivery) -> DeliveryAttempt {
+    let attempted_at = chrono::Utc::now().timestamp();
+    let signature = crate::common::webhook::sign(&delivery.secret, attempted_at, &delivery.payload);
+    if signature.is_err() {
+        return DeliveryAttempt { attempted_at, response_status: None, response: "Unable to sign delivery".to_string() };
+    }
+
+    let request = reqwest::blocking::Client::new().post(&delivery.url)
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECONDS))
+        .header("Content-Type", "application/json")
+        .header("X-Webhook-Id", delivery.delivery_id.clone())
+        .header("X-Webhook-Timestamp", attempted_at.to_string())
+        .header("X-Webhook-Signature-256", format!("sha256={}", signature.unwrap()))
+        .body(delivery.payload.clone())
+        .send();
+
+    match request {
+        Ok(response) => {
+            let response_status = response.status().as_u16();
+            let mut body = response.text().unwrap_or_default();
+            if body.len() > MAX_RESPONSE_LENGTH {
+                let mut end = MAX_RESPONSE_LENGTH;
+                while !body.is_char_boundary(end) {
+                    end -= 1;
+                }
+
+                body.truncate(end);
+            }
+
+            DeliveryAttempt { attempted_at, response_status: Some(response_status), response: body }
+        },
+        Err(e) => DeliveryAttempt { attempted_at, response_status: None, response: e.to_string() }
+    }
+}
@@ -24,14 +24,18 @@ pub enum FulfillmentIntent {
     #[serde(rename(deserialize = "action.devices.SYNC"))]
     SYNC,
     #[serde(rename(deserialize = "action.devices.QUERY"))]
-    QUERY
+    QUERY,
+    #[serde(rename(deserialize = "action.devices.EXECUTE"))]
+    EXECUTE,
+    #[serde(rename(deserialize = "action.devices.DISCONNECT"))]
+    DISCONNECT
 }
 
 #[derive(Deserialize)]
 #[allow(dead_code)]
 pub struct QueryFulfillmentInput {
     pub intent:             FulfillmentIntent,
-    pub payload:            Vec<QueryPayload>
+    pub payload:            QueryPayload
 }
 
 #[derive(Deserialize)]
@@ -43,7 +47,7 @@ pub struct Device {
 #[derive(Deserialize)]
 #[allow(dead_code)]
 pub struct QueryPayload {
-    pub id: Vec<Device>
+    pub devices: Vec<Device>
 }
 
 #[derive(Deserialize)]
@@ -63,7 +67,7 @@ pub struct ExecutePayload<T> {
 #[allow(dead_code)]
 pub struct ExecuteCommand<T> {
     pub devices:            Vec<Device>,
-    pub execution:          Execution<T>
+    pub execution:          Vec<Execution<T>>
 }
 
 #[derive(Deserialize)]
@@ -77,5 +81,16 @@ pub struct Execution<T> {
 #[derive(Deserialize)]
 #[allow(dead_code)]
 pub enum CommandAction {
-
+    #[serde(rename(deserialize = "action.devices.commands.OnOff"))]
+    ONOFF,
+    #[serde(rename(deserialize = "action.devices.commands.BrightnessAbsolute"))]
+    BRIGHTNESSABSOLUTE,
+    #[serde(rename(deserialize = "action.devices.commands.ThermostatTemperatureSetpoint"))]
+    THERMOSTATTEMPERATURESETPOINT,
+    #[serde(rename(deserialize = "action.devices.commands.ThermostatSetMode"))]
+    THERMOSTATSETMODE,
+    #[serde(rename(deserialize = "action.devices.commands.SetFanSpeed"))]
+    SETFANSPEED,
+    #[serde(rename(deserialize = "action.devices.commands.ActivateScene"))]
+    ACTIVATESCENE
 }
\ No newline at end of file
@@ -1,11 +1,17 @@
 use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use crate::types::device::{Device, DeviceKind, DeviceCapability, DeviceState, RegisteredDevice};
+use crate::types::room::{Room, Floor};
+use crate::types::scene::{Scene, SCENE_ID_PREFIX};
+use crate::types::vacation::VACATION_DEVICE_ID;
 
 #[derive(Serialize)]
 #[allow(dead_code)]
 pub struct FulfillmentResponse<T> {
     #[serde(rename(serialize = "requestId"))]
-    request_id:         String,
-    payload:            T
+    pub request_id:     String,
+    pub payload:        T
 }
 
 #[derive(Serialize)]
@@ -17,61 +23,338 @@ pub enum SyncDeviceStatus {
     ERROR
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
 #[allow(dead_code)]
 pub enum ExecuteDeviceStatus {
-
+    SUCCESS,
+    PENDING,
+    OFFLINE,
+    EXCEPTIONS,
+    ERROR
 }
 
 #[derive(Serialize)]
 #[allow(dead_code)]
 pub struct SyncFulfillmentPayload {
     #[serde(rename(serialize = "agentUserId"))]
-    agent_user_id:      String,
-    devices:            Vec<SyncDevice>
+    pub agent_user_id:  String,
+    pub devices:        Vec<SyncDevice>
+}
+
+#[derive(Serialize)]
+pub struct QueryFulfillmentPayload {
+    pub devices:        HashMap<String, Map<String, Value>>
+}
+
+#[derive(Serialize)]
+pub struct ExecuteFulfillmentPayload {
+    pub commands:       Vec<ExecuteCommandResult>
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteCommandResult {
+    pub ids:            Vec<String>,
+    pub status:         ExecuteDeviceStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub states:         Option<Map<String, Value>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code:     Option<String>
 }
 
 #[derive(Serialize)]
 #[allow(dead_code)]
 #[serde(rename_all = "camelCase")]
 pub struct SyncDevice {
-    id:                 String,
+    pub id:                 String,
 
     #[serde(rename(serialize = "type"))]
-    device_type:         DeviceType,
-    traits:             Vec<DeviceTrait>,
-    name:               DeviceName,
-    will_report_state:  bool,
-    device_info:        Option<DeviceInfo>
+    pub device_type:        DeviceType,
+    pub traits:             Vec<DeviceTrait>,
+    pub name:               DeviceName,
+    pub will_report_state:  bool,
+    /// Name of the room the Device is in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub room_hint:          Option<String>,
+    /// Name of the floor the Device is on, Google presents these as separate structures
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structure_hint:     Option<String>,
+    pub attributes:         Map<String, Value>,
+    pub device_info:        Option<DeviceInfo>
 }
 
 #[derive(Serialize)]
 #[allow(dead_code)]
 pub struct DeviceName {
     #[serde(rename(serialize = "defaultNames"))]
-    default_names:      Option<Vec<String>>,
-    name:               String,
-    nicknames:          Option<Vec<String>>
+    pub default_names:      Option<Vec<String>>,
+    pub name:               String,
+    pub nicknames:          Option<Vec<String>>
 }
 
 #[derive(Serialize)]
 #[allow(dead_code)]
 #[serde(rename_all = "camelCase")]
 pub struct DeviceInfo {
-    manufacturer:       String,
-    model:              String,
-    hw_version:         String,
-    sw_version:         String
+    pub manufacturer:       String,
+    pub model:              String,
+    pub hw_version:         String,
+    pub sw_version:         String
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone, PartialEq, Eq)]
 #[allow(dead_code)]
 pub enum DeviceTrait {
-
+    #[serde(rename(serialize = "action.devices.traits.OnOff"))]
+    ONOFF,
+    #[serde(rename(serialize = "action.devices.traits.Brightness"))]
+    BRIGHTNESS,
+    #[serde(rename(serialize = "action.devices.traits.TemperatureSetting"))]
+    TEMPERATURESETTING,
+    #[serde(rename(serialize = "action.devices.traits.TemperatureControl"))]
+    TEMPERATURECONTROL,
+    #[serde(rename(serialize = "action.devices.traits.HumiditySetting"))]
+    HUMIDITYSETTING,
+    #[serde(rename(serialize = "action.devices.traits.SensorState"))]
+    SENSORSTATE,
+    #[serde(rename(serialize = "action.devices.traits.FanSpeed"))]
+    FANSPEED,
+    #[serde(rename(serialize = "action.devices.traits.Scene"))]
+    SCENE
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone, PartialEq, Eq)]
 #[allow(dead_code)]
 pub enum DeviceType {
+    #[serde(rename(serialize = "action.devices.types.THERMOSTAT"))]
+    THERMOSTAT,
+    #[serde(rename(serialize = "action.devices.types.LIGHT"))]
+    LIGHT,
+    #[serde(rename(serialize = "action.devices.types.SWITCH"))]
+    SWITCH,
+    #[serde(rename(serialize = "action.devices.types.SENSOR"))]
+    SENSOR,
+    #[serde(rename(serialize = "action.devices.types.WATERHEATER"))]
+    WATERHEATER,
+    #[serde(rename(serialize = "action.devices.types.FAN"))]
+    FAN,
+    #[serde(rename(serialize = "action.devices.types.SCENE"))]
+    SCENE
+}
+
+impl From<&DeviceKind> for DeviceType {
+    fn from(kind: &DeviceKind) -> DeviceType {
+        match kind {
+            DeviceKind::THERMOSTAT  => DeviceType::THERMOSTAT,
+            DeviceKind::LIGHT       => DeviceType::LIGHT,
+            DeviceKind::SWITCH      => DeviceType::SWITCH,
+            DeviceKind::SENSOR      => DeviceType::SENSOR,
+            DeviceKind::WATERHEATER => DeviceType::WATERHEATER,
+            DeviceKind::FAN         => DeviceType::FAN
+        }
+    }
+}
+
+impl SyncDevice {
 
-}
\ No newline at end of file
+    /**
+    Create a SyncDevice from a Device
+
+    ## Parameters
+        registered: The registry entry of the Device, holding its ID and the names chosen by the user
+        device: The Device to describe
+        room: The room the Device is in
+        floor: The floor the room is on
+    */
+    pub fn from_device(registered: &RegisteredDevice, device: &Device, room: Option<&Room>, floor: Option<&Floor>) -> SyncDevice {
+        let mut traits: Vec<DeviceTrait> = vec![];
+        let mut attributes: Map<String, Value> = Map::new();
+
+        for capability in &device.capabilities {
+            match capability {
+                DeviceCapability::ONOFF => {
+                    traits.push(DeviceTrait::ONOFF);
+                },
+                DeviceCapability::BRIGHTNESS => {
+                    traits.push(DeviceTrait::BRIGHTNESS);
+                },
+                DeviceCapability::TEMPERATURESETTING => {
+                    traits.push(DeviceTrait::TEMPERATURESETTING);
+
+                    let modes = if device.capabilities.contains(&DeviceCapability::ONOFF) { vec!["off", "heat"] } else { vec!["heat"] };
+                    attributes.insert("availableThermostatModes".to_string(), json!(modes));
+                    attributes.insert("thermostatTemperatureUnit".to_string(), json!("C"));
+
+                    if let (Some(min), Some(max)) = (device.min_setpoint, device.max_setpoint) {
+                        attributes.insert("thermostatTemperatureRange".to_string(), json!({
+                            "minThresholdCelsius": min,
+                            "maxThresholdCelsius": max
+                        }));
+                    }
+                },
+                DeviceCapability::TEMPERATUREREADING => {
+                    traits.push(DeviceTrait::TEMPERATURECONTROL);
+                    attributes.insert("queryOnlyTemperatureControl".to_string(), json!(true));
+                    attributes.insert("temperatureUnitForUX".to_string(), json!("C"));
+                    attributes.insert("temperatureRange".to_string(), json!({
+                        "minThresholdCelsius": -40.0,
+                        "maxThresholdCelsius": 60.0
+                    }));
+                },
+                DeviceCapability::SENSORSTATE => {
+                    if device.state.humidity.is_some() && !device.capabilities.contains(&DeviceCapability::TEMPERATURESETTING) {
+                        traits.push(DeviceTrait::HUMIDITYSETTING);
+                        attributes.insert("queryOnlyHumiditySetting".to_string(), json!(true));
+                    }
+
+                    if device.state.co2.is_some() {
+                        traits.push(DeviceTrait::SENSORSTATE);
+                        attributes.insert("sensorStatesSupported".to_string(), json!([{
+                            "name": "CarbonDioxideLevel",
+                            "numericCapabilities": {
+                                "rawValueUnit": "PARTS_PER_MILLION"
+                            }
+                        }]));
+                    }
+                },
+                DeviceCapability::FANSPEED => {
+                    traits.push(DeviceTrait::FANSPEED);
+                    attributes.insert("supportsFanSpeedPercent".to_string(), json!(true));
+                }
+            }
+        }
+
+        SyncDevice {
+            id: registered.device_id.clone(),
+            device_type: DeviceType::from(&device.kind),
+            traits,
+            name: DeviceName {
+                default_names: Some(vec![device.name.clone()]),
+                name: registered.display_name(),
+                nicknames: if registered.nicknames.is_empty() { None } else { Some(registered.nicknames.clone()) }
+            },
+            //State is only reported when we're able to use the HomeGraph API
+            will_report_state: crate::environment::Environment::new().google_service_account_key.is_some(),
+            room_hint: room.map(|room| room.name.clone()),
+            structure_hint: floor.map(|floor| floor.name.clone()),
+            attributes,
+            device_info: Some(DeviceInfo {
+                manufacturer: device.manufacturer.clone(),
+                model: device.model.clone(),
+                hw_version: String::new(),
+                sw_version: String::new()
+            })
+        }
+    }
+
+    /**
+    Create a SyncDevice from a Scene
+
+    ## Parameters
+        scene: The Scene to describe
+    */
+    pub fn from_scene(scene: &Scene) -> SyncDevice {
+        let mut attributes: Map<String, Value> = Map::new();
+        attributes.insert("sceneReversible".to_string(), json!(false));
+
+        SyncDevice {
+            id: format!("{}{}", SCENE_ID_PREFIX, scene.scene_id),
+            device_type: DeviceType::SCENE,
+            traits: vec![DeviceTrait::SCENE],
+            name: DeviceName {
+                default_names: None,
+                name: scene.name.clone(),
+                nicknames: None
+            },
+            will_report_state: false,
+            room_hint: None,
+            structure_hint: None,
+            attributes,
+            device_info: None
+        }
+    }
+
+    /**
+    Create a SyncDevice for the vacation mode of a User. Deactivating it cancels the vacation
+    */
+    pub fn from_vacation() -> SyncDevice {
+        let mut attributes: Map<String, Value> = Map::new();
+        attributes.insert("sceneReversible".to_string(), json!(true));
+
+        SyncDevice {
+            id: VACATION_DEVICE_ID.to_string(),
+            device_type: DeviceType::SCENE,
+            traits: vec![DeviceTrait::SCENE],
+            name: DeviceName {
+                default_names: None,
+                name: "Vacation mode".to_string(),
+                nicknames: None
+            },
+            will_report_state: false,
+            room_hint: None,
+            structure_hint: None,
+            attributes,
+            device_info: None
+        }
+    }
+}
+
+/**
+Convert the state of a Device to the state object Google expects in QUERY and EXECUTE responses
+*/
+pub fn device_state_to_google(state: &DeviceState, capabilities: &[DeviceCapability]) -> Map<String, Value> {
+    let mut result: Map<String, Value> = Map::new();
+    result.insert("online".to_string(), json!(state.online));
+    result.insert("status".to_string(), json!(if state.online { "SUCCESS" } else { "OFFLINE" }));
+
+    if let Some(on) = state.on {
+        result.insert("on".to_string(), json!(on));
+    }
+
+    if let Some(brightness) = state.brightness {
+        result.insert("brightness".to_string(), json!(brightness));
+    }
+
+    if capabilities.contains(&DeviceCapability::TEMPERATURESETTING) {
+        let mode = match state.on {
+            Some(false) => "off",
+            _ => "heat"
+        };
+        result.insert("thermostatMode".to_string(), json!(mode));
+
+        if let Some(setpoint) = state.setpoint_temperature {
+            result.insert("thermostatTemperatureSetpoint".to_string(), json!(setpoint));
+        }
+
+        if let Some(ambient) = state.ambient_temperature {
+            result.insert("thermostatTemperatureAmbient".to_string(), json!(ambient));
+        }
+
+        if let Some(humidity) = state.humidity {
+            result.insert("thermostatHumidityAmbient".to_string(), json!(humidity));
+        }
+    } else {
+        if let Some(ambient) = state.ambient_temperature {
+            result.insert("temperatureAmbientCelsius".to_string(), json!(ambient));
+        }
+
+        if let Some(humidity) = state.humidity {
+            result.insert("humidityAmbientPercent".to_string(), json!(humidity.round() as i64));
+        }
+    }
+
+    if let Some(co2) = state.co2 {
+        result.insert("currentSensorStateData".to_string(), json!([{
+            "name": "CarbonDioxideLevel",
+            "rawValue": co2
+        }]));
+    }
+
+    if let Some(fan_speed) = state.fan_speed {
+        result.insert("currentFanSpeedPercent".to_string(), json!(fan_speed));
+    }
+
+    result
+}
@@ -0,0 +1,312 @@
+use serde::{Serialize, Deserialize};
+use std::fmt;
+
+/**
+The kind of a Device, this decides how the Device is presented to Google
+*/
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum DeviceKind {
+    THERMOSTAT,
+    LIGHT,
+    SWITCH,
+    SENSOR,
+    WATERHEATER,
+    FAN
+}
+
+impl std::str::FromStr for DeviceKind {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<DeviceKind, Self::Err> {
+        match input {
+            "THERMOSTAT"    => Ok(DeviceKind::THERMOSTAT),
+            "LIGHT"         => Ok(DeviceKind::LIGHT),
+            "SWITCH"        => Ok(DeviceKind::SWITCH),
+            "SENSOR"        => Ok(DeviceKind::SENSOR),
+            "WATERHEATER"   => Ok(DeviceKind::WATERHEATER),
+            "FAN"           => Ok(DeviceKind::FAN),
+            _               => Err(())
+        }
+    }
+}
+
+impl fmt::Display for DeviceKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/**
+The things a Device can do, these map onto Google's device traits
+*/
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum DeviceCapability {
+    /// The Device can be turned on and off
+    ONOFF,
+    /// The Device can be dimmed
+    BRIGHTNESS,
+    /// The Device has a temperature setpoint and reports the ambient temperature
+    TEMPERATURESETTING,
+    /// The Device only reports a temperature
+    TEMPERATUREREADING,
+    /// The Device reports sensor values other than temperature, e.g. CO2 or humidity
+    SENSORSTATE,
+    /// The Device has a fan of which the speed can be changed
+    FANSPEED
+}
+
+/**
+The state of a Device as reported by the Service it belongs to.
+Fields which do not apply to a Device are None
+*/
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct DeviceState {
+    pub online:                 bool,
+    pub on:                     Option<bool>,
+    /// Brightness in percent
+    pub brightness:             Option<u8>,
+    /// Ambient temperature in °C
+    pub ambient_temperature:    Option<f32>,
+    /// Target temperature in °C
+    pub setpoint_temperature:   Option<f32>,
+    /// Relative humidity in percent
+    pub humidity:               Option<f32>,
+    /// CO2 in PPM
+    pub co2:                    Option<f32>,
+    /// Air pressure in mbar
+    pub pressure:               Option<f32>,
+    /// Fan speed in percent
+    pub fan_speed:              Option<u8>,
+    /// Power usage in Watt
+    pub power:                  Option<f32>,
+    /// Alerts currently raised by the Device
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alerts:                 Vec<DeviceAlert>
+}
+
+impl DeviceState {
+
+    /**
+    Apply a command to this state, this is the state the Device is expected to be in after executing the command
+    */
+    pub fn apply(&mut self, command: &DeviceCommand) {
+        match command {
+            DeviceCommand::ONOFF(on) => {
+                self.on = Some(*on);
+            },
+            DeviceCommand::BRIGHTNESS(brightness) => {
+                self.brightness = Some(*brightness);
+                self.on = Some(*brightness > 0);
+            },
+            DeviceCommand::SETPOINT(temperature) => {
+                self.setpoint_temperature = Some(*temperature);
+            },
+            DeviceCommand::FANSPEED(speed) => {
+                self.fan_speed = Some(*speed);
+            }
+        }
+    }
+}
+
+/**
+A value in the state of a Device, as a number
+*/
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum DeviceProperty {
+    /// 1 if the Device is on, 0 if it is off
+    ON,
+    BRIGHTNESS,
+    AMBIENTTEMPERATURE,
+    SETPOINTTEMPERATURE,
+    HUMIDITY,
+    CO2,
+    PRESSURE,
+    FANSPEED,
+    POWER
+}
+
+impl DeviceProperty {
+    pub const ALL: [DeviceProperty; 9] = [
+        DeviceProperty::ON,
+        DeviceProperty::BRIGHTNESS,
+        DeviceProperty::AMBIENTTEMPERATURE,
+        DeviceProperty::SETPOINTTEMPERATURE,
+        DeviceProperty::HUMIDITY,
+        DeviceProperty::CO2,
+        DeviceProperty::PRESSURE,
+        DeviceProperty::FANSPEED,
+        DeviceProperty::POWER
+    ];
+
+    /**
+    Get the value of this property from a state, None if the Device does not report it
+    */
+    pub fn value(&self, state: &DeviceState) -> Option<f32> {
+        match self {
+            DeviceProperty::ON                  => state.on.map(|on| if on { 1.0 } else { 0.0 }),
+            DeviceProperty::BRIGHTNESS          => state.brightness.map(f32::from),
+            DeviceProperty::AMBIENTTEMPERATURE  => state.ambient_temperature,
+            DeviceProperty::SETPOINTTEMPERATURE => state.setpoint_temperature,
+            DeviceProperty::HUMIDITY            => state.humidity,
+            DeviceProperty::CO2                 => state.co2,
+            DeviceProperty::PRESSURE            => state.pressure,
+            DeviceProperty::FANSPEED            => state.fan_speed.map(f32::from),
+            DeviceProperty::POWER               => state.power
+        }
+    }
+
+    /**
+    Check if the value of this property is in a range. Both bounds are exclusive, a missing bound is unbounded
+    */
+    pub fn in_range(&self, state: &DeviceState, above: Option<f32>, below: Option<f32>) -> bool {
+        match self.value(state) {
+            Some(value) => above.map(|above| value > above).unwrap_or(true) && below.map(|below| value < below).unwrap_or(true),
+            None => false
+        }
+    }
+}
+
+impl fmt::Display for DeviceProperty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/**
+An alert raised by a Device, which the User should be told about
+*/
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum DeviceAlert {
+    /// The battery of the Device is running low
+    BATTERYLOW,
+    /// The Device lost its connection to its controller
+    COMMLOST,
+    /// A sensor of the Device failed
+    SENSORFAILURE,
+    /// The location the Device is in has alerts which aren't tied to one Device, e.g. of the controller
+    LOCATIONALERT,
+    /// A window was detected as open by the temperature drop in the Zone. Raised by open window detection, not by the Device
+    OPENWINDOW
+}
+
+impl DeviceAlert {
+    pub const ALL: [DeviceAlert; 5] = [DeviceAlert::BATTERYLOW, DeviceAlert::COMMLOST, DeviceAlert::SENSORFAILURE, DeviceAlert::LOCATIONALERT, DeviceAlert::OPENWINDOW];
+
+    /**
+    A short description of the alert, used as the title of notifications
+    */
+    pub fn title(&self) -> &'static str {
+        match self {
+            DeviceAlert::BATTERYLOW     => "Low battery",
+            DeviceAlert::COMMLOST       => "Connection lost",
+            DeviceAlert::SENSORFAILURE  => "Sensor failure",
+            DeviceAlert::LOCATIONALERT  => "Alert at location",
+            DeviceAlert::OPENWINDOW     => "Open window"
+        }
+    }
+
+    /**
+    Parse an alert from its name, as written by Display
+    */
+    pub fn from_name(name: &str) -> Option<DeviceAlert> {
+        DeviceAlert::ALL.iter().find(|alert| alert.to_string() == name).copied()
+    }
+}
+
+impl fmt::Display for DeviceAlert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/**
+A Device as provided by a Service
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Device {
+    /// The ID of the Device, as known by the Service providing it
+    pub id:                 String,
+    pub name:               String,
+    pub kind:               DeviceKind,
+    pub capabilities:       Vec<DeviceCapability>,
+    pub manufacturer:       String,
+    pub model:              String,
+    /// The lowest setpoint allowed, in °C
+    pub min_setpoint:       Option<f32>,
+    /// The highest setpoint allowed, in °C
+    pub max_setpoint:       Option<f32>,
+    pub state:              DeviceState
+}
+
+/**
+A command which can be executed on a Device
+*/
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum DeviceCommand {
+    /// Turn the Device on (true) or off (false)
+    ONOFF(bool),
+    /// Set the brightness, in percent
+    BRIGHTNESS(u8),
+    /// Set the target temperature, in °C
+    SETPOINT(f32),
+    /// Set the fan speed, in percent
+    FANSPEED(u8)
+}
+
+impl DeviceCommand {
+    /**
+    Check if this command can be executed on a Device with the provided capabilities
+    */
+    pub fn is_supported(&self, capabilities: &[DeviceCapability]) -> bool {
+        let required = match self {
+            DeviceCommand::ONOFF(_)         => DeviceCapability::ONOFF,
+            DeviceCommand::BRIGHTNESS(_)    => DeviceCapability::BRIGHTNESS,
+            DeviceCommand::SETPOINT(_)      => DeviceCapability::TEMPERATURESETTING,
+            DeviceCommand::FANSPEED(_)      => DeviceCapability::FANSPEED
+        };
+
+        capabilities.contains(&required)
+    }
+}
+
+/**
+A Device as known in the device registry. Every Device discovered through a Service gets an entry,
+which holds everything the user configured for it
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RegisteredDevice {
+    /// Stable internal ID of the Device, this is the ID known to Google
+    pub device_id:          String,
+    pub user_id:            String,
+    pub service_id:         String,
+    /// The ID of the Device, as known by the Service
+    pub provider_device_id: String,
+    pub kind:               DeviceKind,
+    pub capabilities:       Vec<DeviceCapability>,
+    /// The name of the Device as reported by the Service
+    pub provider_name:      String,
+    /// The name chosen by the user, overrides provider_name
+    pub name:               Option<String>,
+    pub nicknames:          Vec<String>,
+    /// The room the Device is in
+    pub room_id:            Option<String>,
+    /// If the Device is exposed to Google Assistant
+    pub exposed:            bool,
+    /// Unix timestamp at which the Service last reported the Device
+    pub last_seen:          i64
+}
+
+impl RegisteredDevice {
+
+    /**
+    The name to show for the Device, the name chosen by the user if there is one
+    */
+    pub fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.provider_name.clone())
+    }
+}
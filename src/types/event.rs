@@ -0,0 +1,89 @@
+use crate::types::device::DeviceState;
+use crate::types::service::ServiceType;
+
+/**
+Something that happened in the system. Events are published on the EventBus
+*/
+#[derive(Clone, Debug)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Event {
+    /// The state of a Device differs from when it was last seen. previous is None the first time a Device is seen
+    DEVICESTATECHANGED {
+        user_id:        String,
+        device_id:      String,
+        previous:       Option<DeviceState>,
+        state:          DeviceState
+    },
+    SERVICEADDED {
+        user_id:        String,
+        service_id:     String,
+        service_type:   ServiceType
+    },
+    /// device_ids are the Devices removed along with the Service
+    SERVICEREMOVED {
+        user_id:        String,
+        service_id:     String,
+        device_ids:     Vec<String>
+    },
+    /// A Service rejected its credentials or tokens
+    SERVICEAUTHFAILED {
+        user_id:        String,
+        service_id:     String,
+        service_type:   ServiceType
+    },
+    /// A Service started or stopped providing its Devices
+    SERVICEHEALTHCHANGED {
+        user_id:        String,
+        service_id:     String,
+        healthy:        bool
+    },
+    /// The User linked their account to Google Assistant
+    USERLINKEDASSISTANT {
+        user_id:        String
+    },
+    /// A trigger of a Rule fired. executed is false if its conditions did not hold
+    RULEFIRED {
+        user_id:        String,
+        rule_id:        String,
+        name:           String,
+        executed:       bool
+    },
+    /// A person of the User's household came home, or left
+    PRESENCECHANGED {
+        user_id:        String,
+        person:         String,
+        home:           bool
+    },
+    /// The home of the User switched to away mode, or back
+    AWAYMODECHANGED {
+        user_id:        String,
+        away:           bool
+    },
+    /// A window was detected as open by the temperature drop in a Zone, or the setpoint of the Zone was restored
+    OPENWINDOWCHANGED {
+        user_id:        String,
+        device_id:      String,
+        open:           bool
+    }
+}
+
+impl Event {
+
+    /**
+    The User the event belongs to
+    */
+    pub fn user_id(&self) -> &str {
+        match self {
+            Event::DEVICESTATECHANGED { user_id, .. }
+            | Event::SERVICEADDED { user_id, .. }
+            | Event::SERVICEREMOVED { user_id, .. }
+            | Event::SERVICEAUTHFAILED { user_id, .. }
+            | Event::SERVICEHEALTHCHANGED { user_id, .. }
+            | Event::USERLINKEDASSISTANT { user_id }
+            | Event::RULEFIRED { user_id, .. }
+            | Event::PRESENCECHANGED { user_id, .. }
+            | Event::AWAYMODECHANGED { user_id, .. }
+            | Event::OPENWINDOWCHANGED { user_id, .. } => user_id
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{Serialize, Deserialize};
+use crate::types::device::DeviceProperty;
+
+/**
+The granularity of stored history. Raw samples are downsampled into hourly and daily aggregates,
+each level is kept for longer than the one before it
+*/
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Resolution {
+    RAW,
+    HOURLY,
+    DAILY
+}
+
+impl Resolution {
+
+    /**
+    The length of a period in seconds, None for raw samples
+    */
+    pub fn period_seconds(&self) -> Option<i64> {
+        match self {
+            Resolution::RAW     => None,
+            Resolution::HOURLY  => Some(3600),
+            Resolution::DAILY   => Some(86400)
+        }
+    }
+}
+
+/**
+How the values within an interval are combined
+*/
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Aggregation {
+    AVG,
+    MIN,
+    MAX
+}
+
+/**
+Which part of the history to read, and how
+*/
+#[derive(Clone, Copy, Debug)]
+pub struct HistoryQuery {
+    /// Start of the range, Unix timestamp, inclusive
+    pub from:           i64,
+    /// End of the range, Unix timestamp, exclusive
+    pub to:             i64,
+    pub resolution:     Resolution,
+    /// How values are combined within an interval
+    pub aggregation:    Aggregation,
+    /// Length of an interval in seconds. None returns raw samples, or every stored aggregate
+    pub interval:       Option<i64>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HistoryPoint {
+    /// Unix timestamp. For aggregates, the start of the interval
+    pub timestamp:      i64,
+    pub value:          f32
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HistorySeries {
+    pub property:       DeviceProperty,
+    pub points:         Vec<HistoryPoint>
+}
@@ -0,0 +1,40 @@
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, Value};
+use crate::types::service::ServiceType;
+
+/**
+The state of an entity as returned by /api/states
+*/
+#[derive(Deserialize, Clone)]
+#[allow(dead_code)]
+pub struct EntityState {
+    pub entity_id:      String,
+    pub state:          String,
+    pub attributes:     Map<String, Value>
+}
+
+/**
+Payload sent by the frontend when adding a Home Assistant Service
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HomeAssistantService {
+    pub service_type:   ServiceType,
+
+    /// The base URL of the Home Assistant instance, e.g. 'http://homeassistant.local:8123'
+    pub host:           String,
+
+    /// A long-lived access token, created in the Home Assistant user profile
+    pub token:          String,
+
+    /// The entity_id's to import. If None, all supported entities are imported
+    pub entities:       Option<Vec<String>>
+}
+
+/**
+Settings stored for a Home Assistant Service
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HomeAssistantSettings {
+    /// The entity_id's to import. If empty, all supported entities are imported
+    pub entities:       Vec<String>
+}
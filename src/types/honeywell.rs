@@ -30,4 +30,215 @@ pub struct LoginRequest {
     pub redirect_uri:               String,
     pub events:                     Vec<String>,
     pub form_errors:                Vec<String>
-}
\ No newline at end of file
+}
+#[derive(Deserialize)]
+#[allow(dead_code)]
+#[serde(rename_all = "PascalCase")]
+pub struct LocationsResponse {
+    pub content:        Option<LocationsContent>
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+#[serde(rename_all = "PascalCase")]
+pub struct LocationsContent {
+    pub locations:      Vec<Location>
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+#[serde(rename_all = "PascalCase")]
+pub struct Location {
+    pub name:           String,
+    pub id:             String,
+    pub alert_count:    i32,
+    pub zones:          Vec<Zone>
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+#[serde(rename_all = "PascalCase")]
+pub struct Zone {
+    pub id:                         String,
+    pub name:                       String,
+    pub thermostat_model_type:      String,
+    pub is_alive:                   bool,
+    pub has_alerts:                 bool,
+    pub has_comm_lost_alert:        bool,
+    pub has_battery_low_alert:      bool,
+    pub has_sensor_failure_alert:   bool,
+    pub temperature:                f32,
+    pub min_heat_setpoint:          f32,
+    pub max_heat_setpoint:          f32,
+    pub target_heat_temperature:    f32
+}
+
+#[derive(Serialize)]
+#[allow(dead_code)]
+#[serde(rename_all = "camelCase")]
+pub struct SetZoneTemperatureRequest {
+    pub zone_id:                        String,
+    /// None when the Zone should follow its schedule again
+    pub heat_temperature:               Option<String>,
+    pub hot_water_state_is_on:          bool,
+    pub is_permanent:                   bool,
+    pub set_until_hours:                String,
+    pub set_until_minutes:              String,
+    pub location_time_offset_minutes:   i32,
+    pub is_following_schedule:          bool
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+#[serde(rename_all = "PascalCase")]
+pub struct SetZoneTemperatureResponse {
+    pub errors:         Option<serde_json::Value>
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+pub struct EvohomeTokenResponse {
+    pub access_token:   String
+}
+
+/**
+The weekly schedule of a Zone in the EvoHome API
+*/
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneSchedule {
+    pub daily_schedules:    Vec<ZoneDailySchedule>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneDailySchedule {
+    /// English name of the day, e.g. 'Monday'
+    pub day_of_week:    String,
+    pub switchpoints:   Vec<ZoneSwitchpoint>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneSwitchpoint {
+    pub heat_setpoint:  f32,
+    /// Formatted as HH:MM:SS
+    pub time_of_day:    String
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvohomeUserAccount {
+    pub user_id:    String
+}
+
+/**
+A location in the installation info of the EvoHome API
+*/
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvohomeLocation {
+    pub gateways:   Vec<EvohomeGateway>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvohomeGateway {
+    pub temperature_control_systems:    Vec<EvohomeControlSystem>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvohomeControlSystem {
+    pub system_id:  String,
+    /// The hot water of the system, if it has any
+    #[serde(default)]
+    pub dhw:        Option<EvohomeHotWater>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvohomeHotWater {
+    pub dhw_id:     String
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SetHotWaterRequest {
+    /// 'On' or 'Off', None when following the schedule
+    pub state:          Option<String>,
+    /// 'PermanentOverride' or 'FollowSchedule'
+    pub mode:           String,
+    pub until_time:     Option<String>
+}
+
+/**
+The status of an EvoHome system, only the parts we use
+*/
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvohomeSystemStatus {
+    pub system_mode_status: EvohomeSystemModeStatus
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvohomeSystemModeStatus {
+    pub mode:       String
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SetSystemModeRequest {
+    pub system_mode:    String,
+    pub time_until:     Option<String>,
+    pub permanent:      bool
+}
+
+/**
+The mode of an EvoHome system, applied to all of its Zones
+*/
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum SystemMode {
+    /// Follow the schedule
+    AUTO,
+    /// Follow the schedule, a few degrees lower
+    AUTOWITHECO,
+    /// Hold all Zones at their away temperature
+    AWAY,
+    /// Follow the Saturday schedule
+    DAYOFF,
+    /// Hold all Zones at their minimum temperature, this is frost protection
+    HEATINGOFF
+}
+
+impl SystemMode {
+
+    /**
+    The name of the mode in the EvoHome API
+    */
+    pub fn evohome_name(&self) -> &'static str {
+        match self {
+            SystemMode::AUTO        => "Auto",
+            SystemMode::AUTOWITHECO => "AutoWithEco",
+            SystemMode::AWAY        => "Away",
+            SystemMode::DAYOFF      => "DayOff",
+            SystemMode::HEATINGOFF  => "HeatingOff"
+        }
+    }
+
+    /**
+    Parse the name of a mode in the EvoHome API, None for modes we don't support, e.g. Custom
+    */
+    pub fn from_evohome_name(name: &str) -> Option<SystemMode> {
+        match name {
+            "Auto"          => Some(SystemMode::AUTO),
+            "AutoWithEco"   => Some(SystemMode::AUTOWITHECO),
+            "Away"          => Some(SystemMode::AWAY),
+            "DayOff"        => Some(SystemMode::DAYOFF),
+            "HeatingOff"    => Some(SystemMode::HEATINGOFF),
+            _               => None
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use crate::types::service::ServiceType;
+use crate::types::device::DeviceKind;
+
+/**
+A device type defined in services.jsonc, describing how to talk to a device with a simple HTTP API
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HttpDeviceDefinition {
+    /// Unique identifier of the definition, referenced when adding a device
+    pub identifier:     String,
+    pub name:           String,
+    pub kind:           DeviceKind,
+    /// Names of the variables a user must provide when adding a device of this type, e.g. 'host'
+    pub variables:      Vec<String>,
+    /// How to query the state of the device
+    pub state:          Option<HttpStateQuery>,
+    /// The request to send for each supported command. The value of the command is available as '{{ value }}'
+    pub commands:       HashMap<HttpCommand, HttpRequestTemplate>
+}
+
+/**
+A request, url, header values and body are Tera templates.
+The variables of the device are available in the templates, e.g. 'http://{{ host }}/status'.
+Variables used in the url may not contain '/', '?', '#' or '@', a user only fills in the part of the url the definition leaves to them
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HttpRequestTemplate {
+    /// GET, POST, PUT or DELETE. Defaults to GET
+    pub method:         Option<String>,
+    pub url:            String,
+    pub headers:        Option<HashMap<String, String>>,
+    pub body:           Option<String>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HttpStateQuery {
+    pub request:        HttpRequestTemplate,
+    pub mappings:       Vec<HttpStateMapping>
+}
+
+/**
+Maps a value in the JSON response of the state request onto the Device state
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HttpStateMapping {
+    pub field:          HttpStateField,
+    /// JSON path of the value, e.g. '$.relays[0].ison'
+    pub path:           String,
+    /// The value is multiplied by this. Defaults to 1
+    pub scale:          Option<f32>,
+    /// For ON, the value which means 'on'. Defaults to true, 1, "on" or "true"
+    pub on_value:       Option<Value>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum HttpStateField {
+    ON,
+    BRIGHTNESS,
+    AMBIENTTEMPERATURE,
+    SETPOINT,
+    HUMIDITY,
+    CO2,
+    POWER,
+    FANSPEED
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum HttpCommand {
+    ONOFF,
+    BRIGHTNESS,
+    SETPOINT,
+    FANSPEED
+}
+
+/**
+Payload sent by the frontend when adding a HTTP Service
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HttpService {
+    pub service_type:   ServiceType,
+    #[serde(flatten)]
+    pub settings:       HttpSettings
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HttpSettings {
+    pub devices:        Vec<HttpDevice>
+}
+
+/**
+A device using one of the HTTP device definitions
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HttpDevice {
+    /// ID of the Device, must be unique within the Service
+    pub id:             String,
+    pub name:           String,
+    /// Identifier of the HttpDeviceDefinition
+    pub definition:     String,
+    pub variables:      HashMap<String, String>
+}
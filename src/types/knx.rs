@@ -0,0 +1,73 @@
+use serde::{Serialize, Deserialize};
+use crate::types::service::ServiceType;
+use crate::types::device::DeviceKind;
+
+/**
+Payload sent by the frontend when adding a KNX Service
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct KnxService {
+    pub service_type:   ServiceType,
+    #[serde(flatten)]
+    pub settings:       KnxSettings
+}
+
+/**
+Settings stored for a KNX Service, a single KNXnet/IP tunnelling gateway
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct KnxSettings {
+    /// Hostname or IP of the KNXnet/IP gateway
+    pub host:           String,
+    /// Defaults to 3671
+    pub port:           Option<u16>,
+    pub devices:        Vec<KnxDevice>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct KnxDevice {
+    /// ID of the Device, must be unique within the Service
+    pub id:             String,
+    pub name:           String,
+    pub kind:           DeviceKind,
+    pub addresses:      Vec<KnxGroupAddress>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct KnxGroupAddress {
+    /// Which part of the Device state this group address holds
+    pub role:           GroupAddressRole,
+    /// The group address commands are written to, in 3-level notation, e.g. '1/2/3'
+    pub address:        String,
+    /// The group address the actuator reports its state on. Defaults to address
+    pub status_address: Option<String>,
+    pub dpt:            Dpt,
+    /// If commands may be written to this group address
+    pub writable:       bool
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum GroupAddressRole {
+    ONOFF,
+    BRIGHTNESS,
+    AMBIENTTEMPERATURE,
+    SETPOINT
+}
+
+/**
+The supported KNX datapoint types
+*/
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Dpt {
+    /// DPT 1.001, switch
+    #[serde(rename = "1.001")]
+    SWITCH,
+    /// DPT 5.001, percentage 0-100% scaled to 0-255
+    #[serde(rename = "5.001")]
+    SCALING,
+    /// DPT 9.001, temperature in °C as 2-byte float
+    #[serde(rename = "9.001")]
+    TEMPERATURE
+}
@@ -1,4 +1,25 @@
 pub mod assistant_incoming;
 pub mod assistant_outgoing;
 pub mod service;
-pub mod honeywell;
\ No newline at end of file
+pub mod honeywell;
+pub mod device;
+pub mod home_assistant;
+pub mod tado;
+pub mod modbus;
+pub mod knx;
+pub mod http;
+pub mod netatmo;
+pub mod virtual_devices;
+pub mod room;
+pub mod scene;
+pub mod schedule;
+pub mod rule;
+pub mod history;
+pub mod stream;
+pub mod event;
+pub mod notification;
+pub mod webhook;
+pub mod presence;
+pub mod vacation;
+pub mod optimal_start;
+pub mod open_window;
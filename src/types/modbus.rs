@@ -0,0 +1,98 @@
+use serde::{Serialize, Deserialize};
+use crate::types::service::ServiceType;
+use crate::types::device::DeviceKind;
+
+/**
+Payload sent by the frontend when adding a Modbus Service
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ModbusService {
+    pub service_type:   ServiceType,
+    #[serde(flatten)]
+    pub settings:       ModbusSettings
+}
+
+/**
+Settings stored for a Modbus Service, a single Modbus TCP gateway or device
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ModbusSettings {
+    /// Hostname or IP of the Modbus TCP server
+    pub host:           String,
+    /// Defaults to 502
+    pub port:           Option<u16>,
+    pub devices:        Vec<ModbusDevice>
+}
+
+/**
+A Device reachable through Modbus, described by its register map
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ModbusDevice {
+    /// ID of the Device, must be unique within the Service
+    pub id:             String,
+    pub name:           String,
+    pub kind:           DeviceKind,
+    /// The Modbus unit (slave) ID, defaults to 1
+    pub unit_id:        Option<u8>,
+    pub registers:      Vec<ModbusRegister>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ModbusRegister {
+    /// Which part of the Device state this register holds
+    pub role:           RegisterRole,
+    /// The (0-based) register address
+    pub address:        u16,
+    pub register_type:  RegisterType,
+    pub data_type:      RegisterDataType,
+    /// The raw value is multiplied by this to get the value in the unit of the role. Defaults to 1
+    pub scale:          Option<f32>,
+    /// If the register may be written to. Only HOLDING registers can be written
+    pub writable:       bool,
+    /// For FANSPEED registers, the raw value which corresponds to 100%. E.g. 3 for a ventilation unit with levels 0-3
+    pub max_level:      Option<u16>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum RegisterRole {
+    ONOFF,
+    AMBIENTTEMPERATURE,
+    SETPOINT,
+    HUMIDITY,
+    CO2,
+    FANSPEED,
+    POWER
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum RegisterType {
+    INPUT,
+    HOLDING
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum RegisterDataType {
+    U16,
+    I16,
+    /// Two registers, high word first
+    U32,
+    /// Two registers, high word first
+    I32,
+    /// IEEE 754 float over two registers, high word first
+    F32
+}
+
+impl RegisterDataType {
+    /**
+    The amount of 16-bit registers a value of this type spans
+    */
+    pub fn register_count(&self) -> u16 {
+        match self {
+            RegisterDataType::U16 | RegisterDataType::I16 => 1,
+            RegisterDataType::U32 | RegisterDataType::I32 | RegisterDataType::F32 => 2
+        }
+    }
+}
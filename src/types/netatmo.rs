@@ -0,0 +1,58 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+pub struct StationsDataResponse {
+    pub body:               StationsDataBody
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+pub struct StationsDataBody {
+    pub devices:            Vec<Station>
+}
+
+/**
+The main (indoor) module of a weather station
+*/
+#[derive(Deserialize)]
+#[allow(dead_code)]
+pub struct Station {
+    #[serde(rename(deserialize = "_id"))]
+    pub id:                 String,
+    pub station_name:       Option<String>,
+    pub module_name:        Option<String>,
+
+    /// NAMain
+    #[serde(rename(deserialize = "type"))]
+    pub module_type:        String,
+    pub reachable:          Option<bool>,
+    pub dashboard_data:     Option<DashboardData>,
+    #[serde(default)]
+    pub modules:            Vec<Module>
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+pub struct Module {
+    #[serde(rename(deserialize = "_id"))]
+    pub id:                 String,
+    pub module_name:        Option<String>,
+
+    /// NAModule1: outdoor, NAModule2: wind gauge, NAModule3: rain gauge, NAModule4: additional indoor module
+    #[serde(rename(deserialize = "type"))]
+    pub module_type:        String,
+    pub reachable:          Option<bool>,
+    pub dashboard_data:     Option<DashboardData>
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+#[serde(rename_all = "PascalCase")]
+pub struct DashboardData {
+    pub temperature:        Option<f32>,
+    pub humidity:           Option<f32>,
+    #[serde(rename(deserialize = "CO2"))]
+    pub co2:                Option<f32>,
+    pub pressure:           Option<f32>
+}
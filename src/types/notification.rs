@@ -0,0 +1,109 @@
+use serde::{Serialize, Deserialize};
+use crate::types::device::DeviceAlert;
+
+/**
+A channel through which a User is notified of alerts raised by their Devices
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NotificationChannel {
+    pub channel_id:     String,
+    pub name:           String,
+    pub enabled:        bool,
+    pub target:         NotificationTarget,
+    /// The alerts the User wants to be notified of through this channel
+    pub alerts:         Vec<DeviceAlert>,
+    /// Tera template for the message body. The default template of the alert is used if None
+    #[serde(default)]
+    pub template:       Option<String>
+}
+
+impl NotificationChannel {
+
+    /**
+    Check the channel's name, target and template
+
+    ## Returns
+        Err: A description of the first problem found
+        Ok: If the channel is valid
+    */
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() || self.name.len() > 64 {
+            return Err("A name must be between 1 and 64 characters".to_string());
+        }
+
+        let url = match &self.target {
+            NotificationTarget::EMAIL { address } => {
+                if address.parse::<lettre::Address>().is_err() {
+                    return Err(format!("Invalid email address '{}'", address));
+                }
+
+                None
+            },
+            NotificationTarget::NTFY { url, topic, .. } => {
+                if topic.is_empty() || !topic.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                    return Err(format!("Invalid ntfy topic '{}'", topic));
+                }
+
+                Some(url)
+            },
+            NotificationTarget::GOTIFY { url, .. } => Some(url),
+            NotificationTarget::WEBHOOK { url } => Some(url)
+        };
+
+        if let Some(url) = url {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                return Err(format!("Invalid URL '{}'", url));
+            }
+        }
+
+        if let Some(template) = &self.template {
+            if let Err(e) = tera::Tera::default().add_raw_template("body", template) {
+                return Err(format!("Invalid template: {}", e));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/**
+Where a notification is sent to
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type")]
+#[allow(clippy::upper_case_acronyms)]
+pub enum NotificationTarget {
+    /// Send an email, using the SMTP server configured for the application
+    EMAIL {
+        address:        String
+    },
+    /// Publish to a topic on a ntfy server, e.g. https://ntfy.sh
+    NTFY {
+        url:            String,
+        topic:          String,
+        /// Access token, for protected topics
+        #[serde(default)]
+        token:          Option<String>
+    },
+    /// Push a message to a Gotify server
+    GOTIFY {
+        url:            String,
+        /// The token of the Gotify application
+        token:          String
+    },
+    /// POST the notification as JSON to a URL
+    WEBHOOK {
+        url:            String
+    }
+}
+
+/**
+A rendered notification
+*/
+#[derive(Serialize, Clone, Debug)]
+pub struct Notification {
+    pub title:          String,
+    pub body:           String,
+    pub alert:          Option<DeviceAlert>,
+    pub device_id:      Option<String>
+}
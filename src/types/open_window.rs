@@ -0,0 +1,74 @@
+use serde::{Serialize, Deserialize};
+
+/**
+Open window detection for a Zone of which the thermostat doesn't detect open windows itself.
+When the temperature drops sharply the setpoint is lowered to frost protection, until the temperature recovers or the timeout passes
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OpenWindowDetection {
+    /// The internal ID of the Device
+    pub device_id:      String,
+    pub enabled:        bool,
+    /// The drop in °C per minute from which a window is considered open
+    #[serde(default = "default_drop_rate")]
+    pub drop_rate:      f32,
+    /// The setpoint while the window is open, in °C
+    #[serde(default = "default_frost_setpoint")]
+    pub frost_setpoint: f32,
+    /// The setpoint is restored after this many minutes, even if the temperature did not recover
+    #[serde(default = "default_timeout_minutes")]
+    pub timeout_minutes: u32
+}
+
+fn default_drop_rate() -> f32 {
+    0.1
+}
+
+fn default_frost_setpoint() -> f32 {
+    7.0
+}
+
+fn default_timeout_minutes() -> u32 {
+    30
+}
+
+impl OpenWindowDetection {
+
+    /**
+    Check the thresholds of the detection
+
+    ## Returns
+        Err: A description of the first problem found
+        Ok: If the detection is valid
+    */
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.01..=2.0).contains(&self.drop_rate) {
+            return Err("The drop rate must be between 0.01 and 2 °C per minute".to_string());
+        }
+
+        if !(5.0..=12.0).contains(&self.frost_setpoint) {
+            return Err("The frost protection setpoint must be between 5 and 12 °C".to_string());
+        }
+
+        if !(5..=240).contains(&self.timeout_minutes) {
+            return Err("The timeout must be between 5 and 240 minutes".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/**
+A window detected as open, with the setpoint the Zone had before
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OpenWindow {
+    /// The internal ID of the Device
+    pub device_id:      String,
+    /// The setpoint before the window was opened, in °C
+    pub setpoint:       f32,
+    /// The lowest temperature since the window was opened, in °C
+    pub lowest_temperature: f32,
+    /// Unix timestamp at which the window was detected
+    pub detected_at:    i64
+}
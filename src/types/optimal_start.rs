@@ -0,0 +1,162 @@
+use serde::{Serialize, Deserialize};
+
+/// A model is only used once it has learned from this many heating hours
+pub const MIN_OBSERVATIONS: usize = 5;
+/// Below this spread of outdoor temperatures, in °C, the outdoor temperature is not taken into account
+const MIN_OUTDOOR_SPREAD: f32 = 1.0;
+/// The predicted minutes per degree are kept within these bounds
+const MINUTES_PER_DEGREE_RANGE: (f32, f32) = (5.0, 240.0);
+
+/**
+One hour in which a Zone was heating at full power, with the outdoor temperature at that time
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HeatingObservation {
+    /// Unix timestamp of the start of the hour
+    pub observed_at:            i64,
+    /// None if the User has no weather station, or it was unreachable
+    pub outdoor_temperature:    Option<f32>,
+    /// How many minutes it took to heat the Zone by one degree
+    pub minutes_per_degree:     f32
+}
+
+/**
+What a Zone learned about itself: how many minutes it takes to heat by one degree, as a linear function of the outdoor temperature.
+Schedules use it to start heating early enough to reach the setpoint at the switchpoint
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HeatingModel {
+    /// The internal ID of the Device
+    pub device_id:              String,
+    /// Minutes per degree at an outdoor temperature of 0 °C
+    pub intercept:              f32,
+    /// Change of the minutes per degree for every degree the outdoor temperature rises, usually negative
+    pub slope:                  f32,
+    /// The average outdoor temperature of the observations, assumed when the outdoor temperature is unknown
+    pub mean_outdoor_temperature: Option<f32>,
+    /// The number of heating hours the model was learned from
+    pub observations:           usize,
+    /// Unix timestamp at which the model was last learned
+    pub updated_at:             i64
+}
+
+impl HeatingModel {
+
+    /**
+    Fit a model to the observations of a Zone using least squares. Observations without an outdoor temperature
+    are only used when too few outdoor temperatures are known, the model then ignores the outdoor temperature
+
+    ## Parameters
+        device_id: The internal ID of the Device
+        observations: The observations of the Zone
+        now: The current Unix timestamp
+
+    ## Returns
+        None: If there are too few observations
+        Some: The model
+    */
+    pub fn fit(device_id: String, observations: &[HeatingObservation], now: i64) -> Option<HeatingModel> {
+        if observations.len() < MIN_OBSERVATIONS {
+            return None;
+        }
+
+        let points: Vec<(f32, f32)> = observations.iter()
+            .filter_map(|observation| observation.outdoor_temperature.map(|outdoor| (outdoor, observation.minutes_per_degree)))
+            .collect();
+
+        if points.len() < MIN_OBSERVATIONS {
+            let mean = observations.iter().map(|observation| observation.minutes_per_degree).sum::<f32>() / observations.len() as f32;
+            return Some(HeatingModel { device_id, intercept: mean, slope: 0.0, mean_outdoor_temperature: None, observations: observations.len(), updated_at: now });
+        }
+
+        let count = points.len() as f32;
+        let mean_outdoor = points.iter().map(|(outdoor, _)| outdoor).sum::<f32>() / count;
+        let mean_minutes = points.iter().map(|(_, minutes)| minutes).sum::<f32>() / count;
+        let variance = points.iter().map(|(outdoor, _)| (outdoor - mean_outdoor).powi(2)).sum::<f32>() / count;
+        let covariance = points.iter().map(|(outdoor, minutes)| (outdoor - mean_outdoor) * (minutes - mean_minutes)).sum::<f32>() / count;
+
+        //Without a spread in outdoor temperatures the slope can't be told apart from noise
+        let slope = if variance.sqrt() < MIN_OUTDOOR_SPREAD { 0.0 } else { covariance / variance };
+
+        Some(HeatingModel {
+            device_id,
+            intercept: mean_minutes - slope * mean_outdoor,
+            slope,
+            mean_outdoor_temperature: Some(mean_outdoor),
+            observations: points.len(),
+            updated_at: now
+        })
+    }
+
+    /**
+    Predict how many minutes it takes to heat the Zone by one degree
+
+    ## Parameters
+        outdoor_temperature: The current outdoor temperature, None if unknown
+    */
+    pub fn minutes_per_degree(&self, outdoor_temperature: Option<f32>) -> f32 {
+        let outdoor = outdoor_temperature.or(self.mean_outdoor_temperature).unwrap_or(0.0);
+        (self.intercept + self.slope * outdoor).clamp(MINUTES_PER_DEGREE_RANGE.0, MINUTES_PER_DEGREE_RANGE.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observations(points: &[(Option<f32>, f32)]) -> Vec<HeatingObservation> {
+        points.iter().enumerate()
+            .map(|(index, (outdoor_temperature, minutes_per_degree))| HeatingObservation { observed_at: index as i64 * 3600, outdoor_temperature: *outdoor_temperature, minutes_per_degree: *minutes_per_degree })
+            .collect()
+    }
+
+    #[test]
+    fn too_few_observations_give_no_model() {
+        let observations = observations(&[(Some(0.0), 30.0), (Some(5.0), 25.0), (Some(10.0), 20.0), (Some(15.0), 15.0)]);
+        assert!(HeatingModel::fit("device".to_string(), &observations, 0).is_none());
+    }
+
+    #[test]
+    fn slope_follows_the_outdoor_temperature() {
+        let observations = observations(&[(Some(0.0), 60.0), (Some(2.0), 56.0), (Some(4.0), 52.0), (Some(6.0), 48.0), (Some(8.0), 44.0), (None, 100.0)]);
+        let model = HeatingModel::fit("device".to_string(), &observations, 1000).unwrap();
+
+        assert!((model.slope + 2.0).abs() < 1e-4);
+        assert!((model.intercept - 60.0).abs() < 1e-4);
+        assert_eq!(model.mean_outdoor_temperature, Some(4.0));
+        assert_eq!(model.observations, 5);
+        assert_eq!(model.updated_at, 1000);
+
+        assert!((model.minutes_per_degree(Some(10.0)) - 40.0).abs() < 1e-4);
+        //The average outdoor temperature is assumed when it is unknown
+        assert!((model.minutes_per_degree(None) - 52.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn small_outdoor_spread_is_ignored() {
+        let observations = observations(&[(Some(10.0), 20.0), (Some(10.5), 40.0), (Some(10.0), 20.0), (Some(10.5), 40.0), (Some(10.0), 30.0)]);
+        let model = HeatingModel::fit("device".to_string(), &observations, 0).unwrap();
+
+        assert_eq!(model.slope, 0.0);
+        assert!((model.minutes_per_degree(Some(-10.0)) - 30.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn unknown_outdoor_temperatures_give_the_mean() {
+        let observations = observations(&[(None, 10.0), (None, 20.0), (Some(5.0), 30.0), (None, 40.0), (None, 50.0)]);
+        let model = HeatingModel::fit("device".to_string(), &observations, 0).unwrap();
+
+        assert_eq!(model.slope, 0.0);
+        assert_eq!(model.intercept, 30.0);
+        assert_eq!(model.mean_outdoor_temperature, None);
+        assert_eq!(model.observations, 5);
+    }
+
+    #[test]
+    fn predictions_are_clamped() {
+        let model = HeatingModel { device_id: "device".to_string(), intercept: 100.0, slope: -10.0, mean_outdoor_temperature: None, observations: 5, updated_at: 0 };
+        assert_eq!(model.minutes_per_degree(Some(20.0)), 5.0);
+        assert_eq!(model.minutes_per_degree(Some(-20.0)), 240.0);
+        assert_eq!(model.minutes_per_degree(None), 100.0);
+    }
+}
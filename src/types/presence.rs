@@ -0,0 +1,89 @@
+use serde::{Serialize, Deserialize};
+use crate::types::honeywell::SystemMode;
+use std::collections::HashMap;
+
+/**
+Whether a person of the User's household is home
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Presence {
+    pub person:         String,
+    pub home:           bool,
+    pub source:         PresenceSource,
+    /// Unix timestamp of the last update
+    pub updated_at:     i64
+}
+
+/**
+What the last update of a person's presence came from
+*/
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum PresenceSource {
+    /// The person's phone entered or left the home zone
+    GEOFENCE,
+    /// The person's presence was set by hand
+    MANUAL
+}
+
+/**
+A geofence transition of the home zone, as posted by the phone app
+*/
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum GeofenceTransition {
+    ENTER,
+    LEAVE
+}
+
+/**
+What to do when the last person leaves the home, and undo when the first person returns
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AwayMode {
+    pub enabled:        bool,
+    pub actions:        Vec<AwayAction>,
+    /// If the home is currently in away mode. Set by the server
+    #[serde(default)]
+    pub away:           bool,
+    /// Per Honeywell Service, the mode from before the home went away, restored when someone returns. Set by the server
+    #[serde(default)]
+    pub previous_modes: HashMap<String, SystemMode>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type")]
+#[allow(clippy::upper_case_acronyms)]
+pub enum AwayAction {
+    /// Switch all EvoHome systems of a Honeywell Service to a mode when away, e.g. AWAY, and back to the mode they had when home
+    HONEYWELL {
+        service_id:     String,
+        mode:           SystemMode
+    },
+    /// Activate a Scene when away, and optionally another Scene when home
+    SCENE {
+        away_scene_id:  String,
+        #[serde(default)]
+        home_scene_id:  Option<String>
+    }
+}
+
+impl AwayMode {
+
+    /**
+    Check the actions of the away mode
+
+    ## Returns
+        Err: A description of the first problem found
+        Ok: If the away mode is valid
+    */
+    pub fn validate(&self) -> Result<(), String> {
+        for action in &self.actions {
+            if let AwayAction::HONEYWELL { mode: SystemMode::AUTO, .. } = action {
+                return Err("AUTO is the mode restored when someone comes home, it can't be the away mode".to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
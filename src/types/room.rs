@@ -0,0 +1,23 @@
+use serde::{Serialize, Deserialize};
+
+/**
+A floor of a User's home, e.g. 'Upstairs'. Sent to Google as the structure of the Devices on it
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Floor {
+    pub floor_id:       String,
+    pub name:           String,
+    /// Used to order floors, 0 is the ground floor
+    pub level:          i32
+}
+
+/**
+A room of a User's home. Sent to Google as the room of the Devices in it
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Room {
+    pub room_id:        String,
+    pub name:           String,
+    /// The floor the room is on
+    pub floor_id:       Option<String>
+}
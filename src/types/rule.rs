@@ -0,0 +1,178 @@
+use serde::{Serialize, Deserialize};
+use crate::types::device::{DeviceCommand, DeviceProperty};
+use crate::types::schedule::{Day, parse_time_of_day};
+
+/**
+An automation rule. When any of the triggers fires and all conditions hold, the actions are executed in order
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Rule {
+    pub rule_id:        String,
+    pub name:           String,
+    pub enabled:        bool,
+    pub triggers:       Vec<Trigger>,
+    #[serde(default)]
+    pub conditions:     Vec<Condition>,
+    pub actions:        Vec<Action>
+}
+
+impl Rule {
+
+    /**
+    Check the parts of the Rule which do not depend on the Devices and Scenes of the User
+
+    ## Returns
+        Err: A description of the first problem found
+        Ok: If the Rule is valid
+    */
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() || self.name.len() > 64 {
+            return Err("A name must be between 1 and 64 characters".to_string());
+        }
+
+        if self.triggers.is_empty() || self.actions.is_empty() {
+            return Err("A Rule must have at least one trigger and one action".to_string());
+        }
+
+        for trigger in &self.triggers {
+            match trigger {
+                Trigger::TIME { time } if parse_time_of_day(time).is_none() => {
+                    return Err(format!("Invalid time '{}', expected HH:MM", time));
+                },
+                Trigger::SUN { latitude, longitude, .. } if !(-90.0..=90.0).contains(latitude) || !(-180.0..=180.0).contains(longitude) => {
+                    return Err("Invalid latitude or longitude".to_string());
+                },
+                _ => {}
+            }
+        }
+
+        for action in &self.actions {
+            if let Action::NOTIFY { url, .. } = action {
+                if !url.starts_with("https://") && !url.starts_with("http://") {
+                    return Err(format!("Invalid notification URL '{}'", url));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+    Check if any trigger, condition or action of the Rule refers to one of the Devices
+
+    ## Parameters
+        device_ids: The IDs of the Devices
+    */
+    pub fn uses_device(&self, device_ids: &[String]) -> bool {
+        let triggers = self.triggers.iter().any(|trigger| match trigger {
+            Trigger::DEVICESTATE { device_id, .. } | Trigger::THRESHOLD { device_id, .. } => device_ids.contains(device_id),
+            _ => false
+        });
+
+        let conditions = self.conditions.iter().any(|condition| match condition {
+            Condition::DEVICESTATE { device_id, .. } => device_ids.contains(device_id),
+            _ => false
+        });
+
+        let actions = self.actions.iter().any(|action| match action {
+            Action::SETDEVICE { device_id, .. } => device_ids.contains(device_id),
+            _ => false
+        });
+
+        triggers || conditions || actions
+    }
+}
+
+/**
+Something that happens, which causes a Rule to be evaluated
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type")]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Trigger {
+    /// Every day at a time of day, formatted as HH:MM
+    TIME { time: String },
+    /// Every day at sunrise or sunset at a location, optionally with an offset
+    SUN {
+        event:          SunEvent,
+        #[serde(default)]
+        offset_minutes: i32,
+        latitude:       f64,
+        longitude:      f64
+    },
+    /// When the state of a Device changes. If on is set, only when the Device is turned on (true) or off (false)
+    DEVICESTATE {
+        device_id:      String,
+        #[serde(default)]
+        on:             Option<bool>
+    },
+    /// When a value of a Device enters a range
+    THRESHOLD {
+        device_id:      String,
+        property:       DeviceProperty,
+        #[serde(default)]
+        above:          Option<f32>,
+        #[serde(default)]
+        below:          Option<f32>
+    },
+    /// When a request is made to /rules/webhook/{token}. The token is generated when the Rule is saved
+    WEBHOOK {
+        #[serde(default)]
+        token:          String
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum SunEvent {
+    SUNRISE,
+    SUNSET
+}
+
+/**
+Something which must hold for the actions of a Rule to be executed
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type")]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Condition {
+    /// Someone is home (true), or nobody is home (false)
+    PRESENCE { anyone_home: bool },
+    /// A specific person is home (true), or not (false)
+    PERSONHOME { person: String, home: bool },
+    /// It is one of these days of the week
+    DAYOFWEEK { days: Vec<Day> },
+    /// A Device is on or off, and/or one of its values is in a range
+    DEVICESTATE {
+        device_id:      String,
+        #[serde(default)]
+        on:             Option<bool>,
+        #[serde(default)]
+        property:       Option<DeviceProperty>,
+        #[serde(default)]
+        above:          Option<f32>,
+        #[serde(default)]
+        below:          Option<f32>
+    }
+}
+
+/**
+Something a Rule does
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type")]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Action {
+    /// Execute a command on a Device
+    SETDEVICE {
+        device_id:      String,
+        command:        DeviceCommand
+    },
+    /// Activate a Scene
+    ACTIVATESCENE { scene_id: String },
+    /// POST a message to a URL, e.g. an ntfy topic
+    NOTIFY {
+        url:            String,
+        message:        String
+    }
+}
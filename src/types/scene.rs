@@ -0,0 +1,37 @@
+use serde::{Serialize, Deserialize};
+use crate::types::device::DeviceCommand;
+
+/// Scenes are known to Google under their scene_id with this prefix, so they can't be mistaken for a Device
+pub const SCENE_ID_PREFIX: &str = "scene.";
+
+/**
+A named set of target states for Devices of any Service
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Scene {
+    pub scene_id:       String,
+    pub name:           String,
+    pub actions:        Vec<SceneAction>
+}
+
+/**
+A command to execute on a Device when the Scene is activated. A Device may have multiple actions,
+these are executed in order
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SceneAction {
+    /// The internal ID of the Device
+    pub device_id:      String,
+    pub command:        DeviceCommand
+}
+
+/**
+The result of activating a Scene, for a single Device
+*/
+#[derive(Serialize, Clone, Debug)]
+pub struct SceneActionResult {
+    pub device_id:      String,
+    pub success:        bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error:          Option<String>
+}
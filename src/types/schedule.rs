@@ -0,0 +1,306 @@
+use serde::{Serialize, Deserialize};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike, Weekday};
+
+/**
+A day of the week
+*/
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Day {
+    MONDAY,
+    TUESDAY,
+    WEDNESDAY,
+    THURSDAY,
+    FRIDAY,
+    SATURDAY,
+    SUNDAY
+}
+
+impl Day {
+    pub fn from_weekday(weekday: Weekday) -> Day {
+        match weekday {
+            Weekday::Mon => Day::MONDAY,
+            Weekday::Tue => Day::TUESDAY,
+            Weekday::Wed => Day::WEDNESDAY,
+            Weekday::Thu => Day::THURSDAY,
+            Weekday::Fri => Day::FRIDAY,
+            Weekday::Sat => Day::SATURDAY,
+            Weekday::Sun => Day::SUNDAY
+        }
+    }
+}
+
+/**
+From the time of the switchpoint on, the Device should have the setpoint of the switchpoint
+*/
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Switchpoint {
+    /// Time of day, formatted as HH:MM
+    pub time:           String,
+    /// Target temperature in °C
+    pub setpoint:       f32
+}
+
+impl Switchpoint {
+
+    /**
+    The time of day of the switchpoint in minutes since midnight, None if the time is invalid
+    */
+    pub fn minutes(&self) -> Option<u32> {
+        parse_time_of_day(&self.time)
+    }
+}
+
+/**
+The switchpoints for a day of the week
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DailySchedule {
+    pub day:            Day,
+    pub switchpoints:   Vec<Switchpoint>
+}
+
+/**
+A date on which the regular schedule does not apply, e.g. a holiday
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ScheduleException {
+    /// Formatted as YYYY-MM-DD
+    pub date:           String,
+    /// The switchpoints used on this date instead of those of the day of the week
+    pub switchpoints:   Vec<Switchpoint>
+}
+
+/**
+A weekly heating schedule for a Device
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Schedule {
+    /// The internal ID of the Device
+    pub device_id:      String,
+    /// If false, the schedule is stored but not applied
+    pub enabled:        bool,
+    pub days:           Vec<DailySchedule>,
+    #[serde(default)]
+    pub exceptions:     Vec<ScheduleException>
+}
+
+impl Schedule {
+
+    /**
+    Check that all switchpoints and exception dates are valid
+
+    ## Returns
+        Err: A description of the first problem found
+        Ok: If the schedule is valid
+    */
+    pub fn validate(&self) -> Result<(), String> {
+        for (index, daily) in self.days.iter().enumerate() {
+            if self.days[..index].iter().any(|other| other.day == daily.day) {
+                return Err(format!("{:?} is scheduled more than once", daily.day));
+            }
+
+            validate_switchpoints(&daily.switchpoints)?;
+        }
+
+        for exception in &self.exceptions {
+            if NaiveDate::parse_from_str(&exception.date, "%Y-%m-%d").is_err() {
+                return Err(format!("Invalid exception date '{}', expected YYYY-MM-DD", exception.date));
+            }
+
+            validate_switchpoints(&exception.switchpoints)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+    The switchpoints which apply on a date, sorted by time
+    */
+    fn switchpoints_on(&self, date: NaiveDate) -> Vec<(u32, f32)> {
+        let exception = self.exceptions.iter().find(|exception| NaiveDate::parse_from_str(&exception.date, "%Y-%m-%d").ok() == Some(date));
+        let switchpoints = match exception {
+            Some(exception) => Some(&exception.switchpoints),
+            None => self.days.iter().find(|daily| daily.day == Day::from_weekday(date.weekday())).map(|daily| &daily.switchpoints)
+        };
+
+        let mut switchpoints: Vec<(u32, f32)> = switchpoints.map(|switchpoints| switchpoints.iter()
+            .filter_map(|switchpoint| switchpoint.minutes().map(|minutes| (minutes, switchpoint.setpoint)))
+            .collect())
+            .unwrap_or_default();
+
+        switchpoints.sort_by_key(|(minutes, _)| *minutes);
+        switchpoints
+    }
+
+    /**
+    Find the switchpoint which is active at a moment. If no switchpoint passed yet on that day,
+    the last switchpoint of an earlier day is active
+
+    ## Parameters
+        at: The moment, in local time
+
+    ## Returns
+        None: If the schedule has no switchpoints in the week before the moment
+        Some: The moment the active switchpoint started, and its setpoint
+    */
+    pub fn active_switchpoint(&self, at: NaiveDateTime) -> Option<(NaiveDateTime, f32)> {
+        let minutes_now = at.hour() * 60 + at.minute();
+
+        //Exceptions may leave a day without switchpoints, so look back over a full week
+        for days_back in 0..8 {
+            let date = at.date() - Duration::days(days_back);
+            let active = self.switchpoints_on(date).into_iter()
+                .rev()
+                .find(|(minutes, _)| days_back > 0 || *minutes <= minutes_now);
+
+            if let Some((minutes, setpoint)) = active {
+                return Some((date.and_hms_opt(minutes / 60, minutes % 60, 0)?, setpoint));
+            }
+        }
+
+        None
+    }
+
+    /**
+    Find the first switchpoint after a moment
+
+    ## Parameters
+        at: The moment, in local time
+
+    ## Returns
+        None: If the schedule has no switchpoints in the week after the moment
+        Some: The moment the switchpoint starts, and its setpoint
+    */
+    pub fn next_switchpoint(&self, at: NaiveDateTime) -> Option<(NaiveDateTime, f32)> {
+        let minutes_now = at.hour() * 60 + at.minute();
+
+        for days_ahead in 0..8 {
+            let date = at.date() + Duration::days(days_ahead);
+            let next = self.switchpoints_on(date).into_iter()
+                .find(|(minutes, _)| days_ahead > 0 || *minutes > minutes_now);
+
+            if let Some((minutes, setpoint)) = next {
+                return Some((date.and_hms_opt(minutes / 60, minutes % 60, 0)?, setpoint));
+            }
+        }
+
+        None
+    }
+}
+
+/**
+Parse a time of day formatted as HH:MM
+
+## Returns
+    None: If the time is invalid
+    Some: The time in minutes since midnight
+*/
+pub fn parse_time_of_day(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.split_once(':')?;
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+
+    let (hours, minutes) = (hours.parse::<u32>().ok()?, minutes.parse::<u32>().ok()?);
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    Some(hours * 60 + minutes)
+}
+
+fn validate_switchpoints(switchpoints: &[Switchpoint]) -> Result<(), String> {
+    for switchpoint in switchpoints {
+        if switchpoint.minutes().is_none() {
+            return Err(format!("Invalid switchpoint time '{}', expected HH:MM", switchpoint.time));
+        }
+
+        if !(5.0..=35.0).contains(&switchpoint.setpoint) {
+            return Err(format!("Setpoint {} is outside of 5-35°C", switchpoint.setpoint));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn switchpoint(time: &str, setpoint: f32) -> Switchpoint {
+        Switchpoint { time: time.to_string(), setpoint }
+    }
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    /// Mondays and Wednesdays, with an exception on Wednesday 2024-01-03
+    fn schedule() -> Schedule {
+        Schedule {
+            device_id: "device".to_string(),
+            enabled: true,
+            days: vec![
+                DailySchedule { day: Day::MONDAY, switchpoints: vec![switchpoint("22:00", 16.0), switchpoint("06:30", 20.0)] },
+                DailySchedule { day: Day::WEDNESDAY, switchpoints: vec![switchpoint("07:00", 21.0)] }
+            ],
+            exceptions: vec![
+                ScheduleException { date: "2024-01-03".to_string(), switchpoints: vec![switchpoint("09:00", 18.0)] }
+            ]
+        }
+    }
+
+    #[test]
+    fn active_switchpoint_on_the_same_day() {
+        let schedule = schedule();
+        assert_eq!(schedule.active_switchpoint(at("2024-01-01", "07:00")), Some((at("2024-01-01", "06:30"), 20.0)));
+        assert_eq!(schedule.active_switchpoint(at("2024-01-01", "06:30")), Some((at("2024-01-01", "06:30"), 20.0)));
+        assert_eq!(schedule.active_switchpoint(at("2024-01-01", "23:59")), Some((at("2024-01-01", "22:00"), 16.0)));
+    }
+
+    #[test]
+    fn active_switchpoint_from_an_earlier_day() {
+        let schedule = schedule();
+        assert_eq!(schedule.active_switchpoint(at("2024-01-02", "12:00")), Some((at("2024-01-01", "22:00"), 16.0)));
+        //Before the first switchpoint of Monday, the Wednesday of the week before is active
+        assert_eq!(schedule.active_switchpoint(at("2024-01-01", "05:00")), Some((at("2023-12-27", "07:00"), 21.0)));
+    }
+
+    #[test]
+    fn active_switchpoint_follows_exceptions() {
+        let schedule = schedule();
+        assert_eq!(schedule.active_switchpoint(at("2024-01-03", "08:00")), Some((at("2024-01-01", "22:00"), 16.0)));
+        assert_eq!(schedule.active_switchpoint(at("2024-01-03", "10:00")), Some((at("2024-01-03", "09:00"), 18.0)));
+        assert_eq!(schedule.active_switchpoint(at("2024-01-10", "08:00")), Some((at("2024-01-10", "07:00"), 21.0)));
+    }
+
+    #[test]
+    fn empty_schedule_has_no_active_switchpoint() {
+        let schedule = Schedule { device_id: "device".to_string(), enabled: true, days: vec![], exceptions: vec![] };
+        assert_eq!(schedule.active_switchpoint(at("2024-01-01", "12:00")), None);
+    }
+
+    #[test]
+    fn next_switchpoint_on_the_same_day() {
+        let schedule = schedule();
+        assert_eq!(schedule.next_switchpoint(at("2024-01-01", "05:00")), Some((at("2024-01-01", "06:30"), 20.0)));
+        //A switchpoint which starts at the moment itself has passed already
+        assert_eq!(schedule.next_switchpoint(at("2024-01-01", "06:30")), Some((at("2024-01-01", "22:00"), 16.0)));
+    }
+
+    #[test]
+    fn next_switchpoint_on_a_later_day() {
+        let schedule = schedule();
+        //The exception replaces the regular Wednesday switchpoint
+        assert_eq!(schedule.next_switchpoint(at("2024-01-01", "23:00")), Some((at("2024-01-03", "09:00"), 18.0)));
+        assert_eq!(schedule.next_switchpoint(at("2024-01-03", "10:00")), Some((at("2024-01-08", "06:30"), 20.0)));
+        assert_eq!(schedule.next_switchpoint(at("2024-01-08", "23:00")), Some((at("2024-01-10", "07:00"), 21.0)));
+    }
+
+    #[test]
+    fn empty_schedule_has_no_next_switchpoint() {
+        let schedule = Schedule { device_id: "device".to_string(), enabled: true, days: vec![], exceptions: vec![] };
+        assert_eq!(schedule.next_switchpoint(at("2024-01-01", "12:00")), None);
+    }
+}
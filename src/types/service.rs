@@ -14,9 +14,59 @@ pub struct PasswordProtectedService {
     pub password:           String
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+/**
+How a Service is presented to its owner, so multiple accounts of the same type can be told apart
+*/
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct ServiceAppearance {
+    /// User-defined name of the Service, e.g. 'Holiday house'
+    pub label:              Option<String>,
+    /// Path or URL of an icon, overrides the icon from services.jsonc
+    pub icon:               Option<String>,
+    /// Colour in the form '#RRGGBB'
+    pub colour:             Option<String>
+}
+
+impl ServiceAppearance {
+
+    /**
+    Check if the appearance can be stored
+
+    ## Returns
+        Err: A message describing what is wrong
+        Ok: If the appearance is valid
+    */
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(label) = &self.label {
+            if label.trim().is_empty() || label.len() > 64 {
+                return Err("A label must be between 1 and 64 characters".to_string());
+            }
+        }
+
+        if let Some(icon) = &self.icon {
+            if icon.len() > 255 {
+                return Err("An icon path may not be longer than 255 characters".to_string());
+            }
+        }
+
+        if let Some(colour) = &self.colour {
+            let is_hex = colour.len() == 7 && colour.starts_with('#') && colour.chars().skip(1).all(|c| c.is_ascii_hexdigit());
+            if !is_hex {
+                return Err("A colour must be of the form '#RRGGBB'".to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum LoginMethod {
-    PASSWORD
+    PASSWORD,
+    /// A host URL and an access token
+    TOKEN,
+    /// OAuth2 authorization code flow, the OAuth client is configured in services.jsonc
+    OAUTH
 }
 
 impl fmt::Display for LoginMethod {
@@ -26,8 +76,16 @@ impl fmt::Display for LoginMethod {
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
 pub enum ServiceType {
-    HONEYWELL
+    HONEYWELL,
+    HOME_ASSISTANT,
+    TADO,
+    MODBUS,
+    KNX,
+    HTTP,
+    NETATMO,
+    VIRTUAL
 }
 
 impl std::str::FromStr for ServiceType {
@@ -35,8 +93,15 @@ impl std::str::FromStr for ServiceType {
 
     fn from_str(input: &str) -> Result<ServiceType, Self::Err> {
         match input {
-            "HONEYWELL" => Ok(ServiceType::HONEYWELL),
-            _           => Err(())
+            "HONEYWELL"         => Ok(ServiceType::HONEYWELL),
+            "HOME_ASSISTANT"    => Ok(ServiceType::HOME_ASSISTANT),
+            "TADO"              => Ok(ServiceType::TADO),
+            "MODBUS"            => Ok(ServiceType::MODBUS),
+            "KNX"               => Ok(ServiceType::KNX),
+            "HTTP"              => Ok(ServiceType::HTTP),
+            "NETATMO"           => Ok(ServiceType::NETATMO),
+            "VIRTUAL"           => Ok(ServiceType::VIRTUAL),
+            _                   => Err(())
         }
     }
 }
@@ -0,0 +1,111 @@
+use serde::Serialize;
+use crate::types::device::DeviceState;
+use crate::types::service::ServiceType;
+use crate::types::event::Event;
+
+/**
+An event pushed to connected clients
+*/
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "type")]
+#[allow(clippy::upper_case_acronyms)]
+pub enum StreamEvent {
+    /// The state of a Device changed
+    DEVICESTATE {
+        device_id:      String,
+        state:          DeviceState
+    },
+    /// A Service started or stopped providing its Devices
+    SERVICEHEALTH {
+        service_id:     String,
+        healthy:        bool
+    },
+    /// A Service was added
+    SERVICEADDED {
+        service_id:     String,
+        service_type:   ServiceType
+    },
+    /// A Service was removed
+    SERVICEREMOVED {
+        service_id:     String
+    },
+    /// The credentials of a Service are no longer accepted, the User has to reconnect it
+    SERVICEAUTHFAILED {
+        service_id:     String,
+        service_type:   ServiceType
+    },
+    /// A trigger of a Rule fired. executed is false if its conditions did not hold
+    AUTOMATION {
+        rule_id:        String,
+        name:           String,
+        executed:       bool
+    },
+    /// A person came home, or left
+    PRESENCE {
+        person:         String,
+        home:           bool
+    },
+    /// The home switched to away mode, or back
+    AWAYMODE {
+        away:           bool
+    },
+    /// A window was detected as open, or the setpoint of its Zone was restored
+    OPENWINDOW {
+        device_id:      String,
+        open:           bool
+    }
+}
+
+impl StreamEvent {
+    pub const NAMES: [&'static str; 9] = ["DEVICESTATE", "SERVICEHEALTH", "SERVICEADDED", "SERVICEREMOVED", "SERVICEAUTHFAILED", "AUTOMATION", "PRESENCE", "AWAYMODE", "OPENWINDOW"];
+
+    /**
+    Convert an Event from the EventBus to the form clients receive
+
+    ## Returns
+        None: If the Event is not shown to clients
+        Some: The StreamEvent
+    */
+    pub fn from_event(event: &Event) -> Option<StreamEvent> {
+        let stream_event = match event {
+            Event::DEVICESTATECHANGED { device_id, state, .. } => StreamEvent::DEVICESTATE { device_id: device_id.clone(), state: state.clone() },
+            Event::SERVICEHEALTHCHANGED { service_id, healthy, .. } => StreamEvent::SERVICEHEALTH { service_id: service_id.clone(), healthy: *healthy },
+            Event::SERVICEADDED { service_id, service_type, .. } => StreamEvent::SERVICEADDED { service_id: service_id.clone(), service_type: service_type.clone() },
+            Event::SERVICEREMOVED { service_id, .. } => StreamEvent::SERVICEREMOVED { service_id: service_id.clone() },
+            Event::SERVICEAUTHFAILED { service_id, service_type, .. } => StreamEvent::SERVICEAUTHFAILED { service_id: service_id.clone(), service_type: service_type.clone() },
+            Event::RULEFIRED { rule_id, name, executed, .. } => StreamEvent::AUTOMATION { rule_id: rule_id.clone(), name: name.clone(), executed: *executed },
+            Event::PRESENCECHANGED { person, home, .. } => StreamEvent::PRESENCE { person: person.clone(), home: *home },
+            Event::AWAYMODECHANGED { away, .. } => StreamEvent::AWAYMODE { away: *away },
+            Event::OPENWINDOWCHANGED { device_id, open, .. } => StreamEvent::OPENWINDOW { device_id: device_id.clone(), open: *open },
+            Event::USERLINKEDASSISTANT { .. } => return None
+        };
+
+        Some(stream_event)
+    }
+
+    /**
+    The name of the event type, used as the SSE event name
+    */
+    pub fn name(&self) -> &'static str {
+        match self {
+            StreamEvent::DEVICESTATE { .. }     => "DEVICESTATE",
+            StreamEvent::SERVICEHEALTH { .. }   => "SERVICEHEALTH",
+            StreamEvent::SERVICEADDED { .. }    => "SERVICEADDED",
+            StreamEvent::SERVICEREMOVED { .. }  => "SERVICEREMOVED",
+            StreamEvent::SERVICEAUTHFAILED { .. } => "SERVICEAUTHFAILED",
+            StreamEvent::AUTOMATION { .. }      => "AUTOMATION",
+            StreamEvent::PRESENCE { .. }        => "PRESENCE",
+            StreamEvent::AWAYMODE { .. }        => "AWAYMODE",
+            StreamEvent::OPENWINDOW { .. }      => "OPENWINDOW"
+        }
+    }
+}
+
+/**
+An event as sent to a client. Clients can resume from the last id they received
+*/
+#[derive(Serialize, Clone, Debug)]
+pub struct StreamMessage {
+    pub id:             u64,
+    pub event:          StreamEvent
+}
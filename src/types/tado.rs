@@ -0,0 +1,89 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+pub struct Me {
+    pub homes:          Vec<Home>
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+pub struct Home {
+    pub id:             i64,
+    pub name:           String
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+pub struct Zone {
+    pub id:             i64,
+    pub name:           String,
+
+    /// HEATING, HOT_WATER or AIR_CONDITIONING
+    #[serde(rename(deserialize = "type"))]
+    pub zone_type:      String
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneState {
+    pub setting:                ZoneSetting,
+    pub sensor_data_points:     Option<SensorDataPoints>,
+    pub link:                   Option<Link>
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(dead_code)]
+pub struct ZoneSetting {
+    #[serde(rename = "type")]
+    pub zone_type:      String,
+
+    /// ON or OFF
+    pub power:          String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature:    Option<Temperature>
+}
+
+#[derive(Serialize, Deserialize)]
+#[allow(dead_code)]
+pub struct Temperature {
+    pub celsius:        f32
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorDataPoints {
+    pub inside_temperature:     Option<Temperature>,
+    pub humidity:               Option<Percentage>
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+pub struct Percentage {
+    pub percentage:     f32
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+pub struct Link {
+    /// ONLINE or OFFLINE
+    pub state:          String
+}
+
+#[derive(Serialize)]
+#[allow(dead_code)]
+pub struct Overlay {
+    pub setting:        ZoneSetting,
+    pub termination:    Termination
+}
+
+#[derive(Serialize)]
+#[allow(dead_code)]
+pub struct Termination {
+    /// MANUAL, TADO_MODE or TIMER
+    #[serde(rename = "type")]
+    pub termination_type:   String
+}
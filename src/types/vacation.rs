@@ -0,0 +1,117 @@
+use serde::{Serialize, Deserialize};
+use chrono::{NaiveDateTime, TimeZone};
+
+/// The ID of the Vacation mode as known to Google. It is synced as a reversible scene, deactivating it cancels the vacation
+pub const VACATION_DEVICE_ID: &str = "vacation";
+/// The format of the start and end of a vacation, in local time
+pub const VACATION_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/**
+A period in which nobody is home. Thermostats are held at a frost protection setpoint and hot water is switched off,
+until they're restored in time for the return
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Vacation {
+    /// Local time, formatted as YYYY-MM-DD HH:MM
+    pub start:          String,
+    /// The moment of return, local time formatted as YYYY-MM-DD HH:MM
+    pub end:            String,
+    /// The setpoint thermostats are held at, in °C
+    #[serde(default = "default_frost_setpoint")]
+    pub frost_setpoint: f32,
+    /// Set by the server
+    #[serde(default)]
+    pub status:         VacationStatus,
+    /// The Devices held during the vacation. Set by the server
+    #[serde(default)]
+    pub held:           Vec<HeldDevice>,
+    /// The Honeywell Services of which the hot water is switched off, until it is handed back to its schedule. Set by the server
+    #[serde(default)]
+    pub hot_water_services: Vec<String>
+}
+
+fn default_frost_setpoint() -> f32 {
+    7.0
+}
+
+impl Vacation {
+
+    /**
+    Check the period and frost protection setpoint of the vacation
+
+    ## Returns
+        Err: A description of the first problem found
+        Ok: If the vacation is valid
+    */
+    pub fn validate(&self) -> Result<(), String> {
+        let (start, end) = (self.start_timestamp(), self.end_timestamp());
+        if start.is_none() || end.is_none() {
+            return Err("Invalid start or end, expected YYYY-MM-DD HH:MM".to_string());
+        }
+
+        if end.unwrap() <= start.unwrap() {
+            return Err("A vacation must end after it starts".to_string());
+        }
+
+        if !(5.0..=12.0).contains(&self.frost_setpoint) {
+            return Err("The frost protection setpoint must be between 5 and 12 °C".to_string());
+        }
+
+        Ok(())
+    }
+
+    /**
+    The start of the vacation as Unix timestamp
+    */
+    pub fn start_timestamp(&self) -> Option<i64> {
+        parse_local(&self.start)
+    }
+
+    /**
+    The end of the vacation as Unix timestamp
+    */
+    pub fn end_timestamp(&self) -> Option<i64> {
+        parse_local(&self.end)
+    }
+}
+
+/**
+Format a Unix timestamp the way the start and end of a vacation are formatted
+*/
+pub fn format_local(timestamp: i64) -> String {
+    chrono::Local.timestamp_opt(timestamp, 0).earliest()
+        .map(|time| time.format(VACATION_TIME_FORMAT).to_string())
+        .unwrap_or_default()
+}
+
+fn parse_local(time: &str) -> Option<i64> {
+    let naive = NaiveDateTime::parse_from_str(time, VACATION_TIME_FORMAT).ok()?;
+
+    //Around DST changes a local time may not exist, or exist twice
+    chrono::Local.from_local_datetime(&naive).earliest().map(|time| time.timestamp())
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum VacationStatus {
+    /// The vacation has not started yet
+    #[default]
+    SCHEDULED,
+    /// Devices are held
+    ACTIVE
+}
+
+/**
+A Device held during a vacation, with the state it had before
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HeldDevice {
+    pub device_id:      String,
+    /// The setpoint before the vacation, if the Device is a thermostat
+    pub setpoint:       Option<f32>,
+    /// If the Device was on before the vacation, if the Device is a water heater
+    pub on:             Option<bool>,
+    /// When the Device is restored, known once the return is near
+    pub restore_at:     Option<i64>,
+    pub restored:       bool
+}
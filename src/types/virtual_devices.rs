@@ -0,0 +1,38 @@
+use serde::{Serialize, Deserialize};
+use crate::types::service::ServiceType;
+use crate::types::device::{DeviceKind, DeviceState};
+
+/**
+Payload sent by the frontend when adding a Virtual Service
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VirtualService {
+    pub service_type:   ServiceType,
+    #[serde(flatten)]
+    pub settings:       VirtualSettings
+}
+
+/**
+Settings stored for a Virtual Service. The simulated state of the devices is stored along with them
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VirtualSettings {
+    pub devices:        Vec<VirtualDevice>
+}
+
+/**
+A simulated Device
+*/
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VirtualDevice {
+    /// ID of the Device, must be unique within the Service
+    pub id:             String,
+    pub name:           String,
+    pub kind:           DeviceKind,
+    /// The simulated state, set when the Service is added
+    #[serde(default)]
+    pub state:          DeviceState,
+    /// Unix timestamp at which the simulated state was last updated
+    #[serde(default)]
+    pub updated_at:     i64
+}
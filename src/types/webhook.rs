@@ -0,0 +1,118 @@
+use serde::{Serialize, Deserialize};
+use crate::types::stream::StreamEvent;
+
+/**
+A URL to which events of a User are delivered. Deliveries are signed with the subscription's secret
+*/
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WebhookSubscription {
+    pub subscription_id:    String,
+    pub url:                String,
+    pub enabled:            bool,
+    /// The names of the events to deliver, see StreamEvent. All events are delivered if empty
+    #[serde(default)]
+    pub events:             Vec<String>,
+    /// Only deliver events of these Devices. Events of all Devices are delivered if empty
+    #[serde(default)]
+    pub device_ids:         Vec<String>,
+    /// The key used to sign deliveries. Generated by the server, it can't be chosen by the client
+    #[serde(default)]
+    pub secret:             String
+}
+
+impl WebhookSubscription {
+
+    /**
+    Check the URL and event filter of the subscription
+
+    ## Returns
+        Err: A description of the first problem found
+        Ok: If the subscription is valid
+    */
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.url.starts_with("https://") && !self.url.starts_with("http://") {
+            return Err(format!("Invalid URL '{}'", self.url));
+        }
+
+        for event in &self.events {
+            if !StreamEvent::NAMES.contains(&event.as_str()) {
+                return Err(format!("Unknown event '{}'", event));
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+    Check if an event passes the subscription's filters
+    */
+    pub fn matches(&self, event: &StreamEvent) -> bool {
+        if !self.events.is_empty() && !self.events.iter().any(|name| name == event.name()) {
+            return false;
+        }
+
+        if self.device_ids.is_empty() {
+            return true;
+        }
+
+        match event {
+            StreamEvent::DEVICESTATE { device_id, .. } => self.device_ids.contains(device_id),
+            _ => false
+        }
+    }
+}
+
+/**
+The state of a delivery
+*/
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum DeliveryStatus {
+    /// The delivery is waiting for its next attempt
+    PENDING,
+    /// The receiver accepted the delivery
+    DELIVERED,
+    /// All attempts failed, the delivery is not retried
+    FAILED
+}
+
+/**
+A delivery of an event to a subscription, as shown in the delivery log
+*/
+#[derive(Serialize, Clone, Debug)]
+pub struct WebhookDelivery {
+    /// Sent to the receiver as X-Webhook-Id, the same for every attempt
+    pub delivery_id:        String,
+    pub subscription_id:    String,
+    pub event:              String,
+    pub status:             DeliveryStatus,
+    pub created_at:         i64,
+    /// None if the delivery is not retried
+    pub next_attempt_at:    Option<i64>,
+    pub attempts:           Vec<DeliveryAttempt>
+}
+
+/**
+A single attempt of a delivery
+*/
+#[derive(Serialize, Clone, Debug)]
+pub struct DeliveryAttempt {
+    pub attempted_at:       i64,
+    /// The HTTP status returned by the receiver, None if no response was received
+    pub response_status:    Option<u16>,
+    /// The start of the response body, or the error if no response was received
+    pub response:           String
+}
+
+/**
+A pending delivery of which the next attempt is due, with what is needed to attempt it
+*/
+#[derive(Clone, Debug)]
+pub struct DueDelivery {
+    pub delivery_id:        String,
+    /// The number of attempts made so far
+    pub attempts:           u32,
+    pub payload:            String,
+    pub url:                String,
+    pub secret:             String
+}